@@ -0,0 +1,245 @@
+use crate::{Config, DocComment, Example, Param, Return, Section, TagStyle, anchor};
+
+/// Trait representing the ability to be converted into AsciiDoc
+pub trait Asciidocable {
+    fn asciidoc(&self, config: &Config) -> String;
+}
+
+impl Asciidocable for DocComment {
+    /// Convert a DocComment into a level 2 section anchored by its slug
+    fn asciidoc(&self, config: &Config) -> String {
+        let mut adoc = String::new();
+
+        adoc.push_str(&format!("[[{}]]\n", self.slug));
+        adoc.push_str(&format!("== {}\n", self.title));
+        adoc.push_str(&format!("`{}`\n\n", self.signature()));
+
+        if let Some(metadata) = config.metadata(self) {
+            adoc.push_str(&format!("_{}_\n\n", escape(&metadata)));
+        }
+
+        let mut badges: Vec<String> = config
+            .tags_in(TagStyle::Badge)
+            .flat_map(|t| {
+                self.tag_values(&t.name).map(move |v| match v {
+                    "" => format!("`{}`", t.label()),
+                    v => format!("`{}: {v}`", t.label()),
+                })
+            })
+            .collect();
+        let marks = config.marks(self).into_iter();
+        badges.splice(0..0, marks.map(|label| format!("`{label}`")));
+        if !badges.is_empty() {
+            adoc.push_str(&format!("{}\n\n", badges.join(" ")));
+        }
+
+        for tag in config.tags_in(TagStyle::KeyValue) {
+            self.tag_values(&tag.name).for_each(|v| {
+                adoc.push_str(&format!(
+                    "*{}:* {}\n\n",
+                    tag.label(),
+                    config.wrap(&escape(v))
+                ));
+            });
+        }
+
+        if let Some(msg) = &self.deprecated {
+            adoc.push_str(format!("WARNING: *Deprecated* {}", escape(msg)).trim_end());
+            adoc.push_str("\n\n");
+        }
+
+        for section in config.sections() {
+            match section {
+                Section::Description => {
+                    adoc.push_str(&config.wrap(&escape(&self.description)));
+                    adoc.push_str("\n\n");
+                }
+                Section::Params if !self.params.is_empty() => {
+                    adoc.push_str(&format!("=== {}\n", config.label(Section::Params)));
+
+                    for (group, params) in config.param_groups(self) {
+                        if let Some(group) = group {
+                            adoc.push_str(&format!("==== {group}\n"));
+                        }
+                        params.iter().for_each(|p| {
+                            adoc.push_str(&p.asciidoc(config));
+                        });
+                        adoc.push('\n');
+                    }
+                }
+                Section::Returns if !self.returns.is_empty() => {
+                    adoc.push_str(&format!("=== {}\n", config.label(Section::Returns)));
+                    match self.returns.as_slice() {
+                        [ret] => adoc.push_str(&ret.asciidoc(config)),
+                        returns => returns.iter().for_each(|r| {
+                            adoc.push_str(&format!("* {}", r.asciidoc(config)));
+                        }),
+                    }
+                    adoc.push('\n');
+                }
+                Section::Errors if !self.errors.is_empty() => {
+                    adoc.push_str(&format!("=== {}\n", config.label(Section::Errors)));
+
+                    self.errors.iter().for_each(|e| {
+                        adoc.push_str(&format!("* {}\n", config.wrap(&escape(e))));
+                    });
+                    adoc.push('\n');
+                }
+                Section::Examples if !self.examples.is_empty() => {
+                    adoc.push_str(&format!("=== {}\n", config.label(Section::Examples)));
+
+                    self.examples.iter().for_each(|e| {
+                        adoc.push_str(&e.asciidoc(config));
+                    });
+                }
+                Section::Notes if !self.notes.is_empty() => {
+                    adoc.push_str(&format!("=== {}\n", config.label(Section::Notes)));
+
+                    self.notes.iter().for_each(|n| {
+                        adoc.push_str(&format!("* {}\n", config.wrap(&escape(n))));
+                    });
+                    adoc.push('\n');
+                }
+                Section::SeeAlso if !self.see.is_empty() => {
+                    adoc.push_str(&format!("=== {}\n", config.label(Section::SeeAlso)));
+
+                    self.see.iter().for_each(|s| match config.page_of(s) {
+                        "" => adoc.push_str(&format!("* <<{},{s}>>\n", anchor(s))),
+                        page => adoc.push_str(&format!("* <<{page}#{},{s}>>\n", anchor(s))),
+                    });
+                    adoc.push('\n');
+                }
+                _ => {}
+            }
+        }
+
+        for tag in config.tags_in(TagStyle::Section) {
+            let mut values = self.tag_values(&tag.name).peekable();
+            if values.peek().is_some() {
+                adoc.push_str(&format!("=== {}\n", tag.label()));
+
+                values.for_each(|v| {
+                    adoc.push_str(&format!("* {}\n", config.wrap(&escape(v))));
+                });
+                adoc.push('\n');
+            }
+        }
+
+        adoc
+    }
+}
+
+impl Asciidocable for Param {
+    /// Convert a parameter into an entry of a definition list
+    /// Into the form: "`name`:: `type` (optional, default: x) description"
+    fn asciidoc(&self, config: &Config) -> String {
+        let mut parts = Vec::new();
+
+        // params only found in the definition may have no type
+        if !self.data_type.is_empty() {
+            parts.push(format!("`{}`", self.types().join(" | ")));
+        }
+        let requirement = config.locale.requirement(self.is_required());
+        match &self.default {
+            Some(def) => parts.push(format!("({requirement}, default: `{def}`)")),
+            None => parts.push(format!("({requirement})")),
+        }
+        if !self.description.is_empty() {
+            parts.push(escape(&self.description));
+        }
+
+        format!("`{}`:: {}\n", self.label(), config.wrap(&parts.join(" ")))
+    }
+}
+
+impl Asciidocable for Return {
+    /// Convert a Return into the form "`type` description", after "name: " for a field
+    fn asciidoc(&self, config: &Config) -> String {
+        let field = self
+            .name
+            .as_ref()
+            .map(|n| format!("{n}: "))
+            .unwrap_or_default();
+        let line = format!(
+            "{field}`{}` {}",
+            self.data_type.join(" | "),
+            escape(&self.description)
+        );
+        format!("{}\n", config.wrap(line.trim_end()))
+    }
+}
+
+impl Asciidocable for Example {
+    /// Convert an example into a source block in its language
+    /// Code is never wrapped
+    fn asciidoc(&self, _config: &Config) -> String {
+        // the delimiter must be longer than any line of dashes in the code
+        let longest = self
+            .code
+            .lines()
+            .filter(|l| !l.is_empty() && l.chars().all(|c| c == '-'))
+            .map(str::len)
+            .max()
+            .unwrap_or(0);
+        let delimiter = "-".repeat(longest.max(3) + 1);
+
+        let mut adoc = format!(
+            "[source,{}]\n{delimiter}\n{}{delimiter}\n\n",
+            self.language, self.code
+        );
+
+        if let Some(image) = &self.image {
+            adoc.push_str(&format!("image::{image}[Output of the example]\n\n"));
+        }
+
+        adoc
+    }
+}
+
+/// Escape the characters AsciiDoc would read as markup with its built in attributes
+/// `code` spans written like markdown become literal monospace
+pub fn escape(text: &str) -> String {
+    text.split('`')
+        .enumerate()
+        .map(|(i, part)| {
+            if i % 2 == 1 {
+                format!("`+{part}+`")
+            } else {
+                part.chars()
+                    .map(|c| match c {
+                        '*' => "{asterisk}".to_string(),
+                        '|' => "{vbar}".to_string(),
+                        '<' => "{lt}".to_string(),
+                        '>' => "{gt}".to_string(),
+                        '[' => "{startsb}".to_string(),
+                        ']' => "{endsb}".to_string(),
+                        c => c.to_string(),
+                    })
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_block;
+
+    #[test]
+    fn prose_is_escaped() {
+        let doc = parse_block(
+            "f: Pick <<b>> or *a* | b, see `x < y`\n@param a int the <first> * | one\n@return int the *sum*\n",
+        )
+        .unwrap();
+        let adoc = doc.asciidoc(&Config::default());
+
+        assert!(
+            adoc.contains(
+                "Pick {lt}{lt}b{gt}{gt} or {asterisk}a{asterisk} {vbar} b, see `+x < y+`\n"
+            )
+        );
+        assert!(adoc.contains("`a`:: `int` (required) the {lt}first{gt} {asterisk} {vbar} one\n"));
+        assert!(adoc.contains("`int` the {asterisk}sum{asterisk}\n"));
+    }
+}
@@ -0,0 +1,94 @@
+use std::{fs, io};
+
+use marker_typ::Finding;
+use serde::{Deserialize, Serialize};
+
+/// Warnings `marker check` already knew about, which don't fail it,
+/// so a codebase can adopt linting without fixing every old warning first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub findings: Vec<Known>,
+}
+
+/// A warning in a baseline, told apart by its file, rule and message but not its line,
+/// so editing around it doesn't make it new
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Known {
+    pub file: String,
+    pub rule: String,
+    pub message: String,
+}
+
+impl Known {
+    /// The entry of `finding` in `file`
+    pub fn new(file: &str, finding: &Finding) -> Known {
+        Known {
+            file: file.replace('\\', "/"),
+            rule: finding.rule.to_string(),
+            message: finding.message.clone(),
+        }
+    }
+}
+
+impl Baseline {
+    /// Read the baseline at `path`
+    pub fn load(path: &str) -> io::Result<Baseline> {
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(io::Error::other)
+    }
+
+    /// Write the baseline to `path`
+    pub fn write(&self, path: &str) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).expect("baselines are valid json");
+        fs::write(path, format!("{json}\n"))
+    }
+
+    /// Whether the baseline knows the warning `known`, using up its entry
+    /// so another warning just like it is still new
+    pub fn take(&mut self, known: &Known) -> bool {
+        let position = self.findings.iter().position(|k| k == known);
+        position.map(|i| self.findings.remove(i)).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use marker_typ::Span;
+
+    use super::*;
+
+    /// A warning of `rule` at `line`
+    fn finding(line: usize, rule: &'static str, message: &str) -> Finding {
+        Finding {
+            span: Span { line, column: 5 },
+            rule,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn written_baseline_knows_old_warnings_but_not_new_ones() {
+        let path = env::temp_dir().join(format!("marker-baseline-{}.json", process::id()));
+        let path = path.to_str().unwrap();
+        let old = finding(3, "unknown-param", "z is not a param of f");
+
+        Baseline {
+            findings: vec![Known::new("src\\lib.typ", &old)],
+        }
+        .write(path)
+        .unwrap();
+        let mut baseline = Baseline::load(path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        // the same warning after editing above it is still known
+        let moved = finding(7, "unknown-param", "z is not a param of f");
+        assert!(baseline.take(&Known::new("src/lib.typ", &moved)));
+
+        let new = finding(9, "missing-param", "b of f is not documented");
+        assert!(!baseline.take(&Known::new("src/lib.typ", &new)));
+        // a known warning only covers one of its kind
+        assert!(!baseline.take(&Known::new("src/lib.typ", &moved)));
+    }
+}
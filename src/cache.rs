@@ -0,0 +1,178 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, Write},
+};
+
+/// Default location of the cache, relative to the working directory
+pub const CACHE_FILE: &str = ".marker-cache";
+
+/// First line of every cache file, anything else is treated as corrupt
+const CACHE_HEADER: &str = "marker-cache v2";
+
+/// Previously rendered output of a single input file
+#[derive(Debug)]
+struct Entry {
+    hash: u64,
+    /// Warnings emitted while rendering, replayed when the entry is used
+    warnings: Vec<String>,
+    rendered: String,
+}
+
+/// Cache mapping input paths to the hash of their contents
+/// and the output that was rendered from them
+///
+/// The file is laid out as the header followed by one entry per input:
+/// path\thash\twarning_count\tline_count
+/// ...warning_count lines, one per warning
+/// ...line_count lines of rendered output
+#[derive(Debug, Default)]
+pub struct Cache {
+    entries: BTreeMap<String, Entry>,
+}
+
+impl Cache {
+    /// Load the cache from `path`
+    /// A missing or corrupt cache is treated as empty, forcing a full rebuild
+    pub fn load(path: &str) -> Cache {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Cache::default();
+        };
+
+        Cache::parse(&contents).unwrap_or_else(|| {
+            eprintln!("warning: ignoring corrupt cache {path}, rebuilding everything");
+            Cache::default()
+        })
+    }
+
+    /// Parse the cache format, `None` if anything is malformed
+    fn parse(contents: &str) -> Option<Cache> {
+        let mut lines = contents.split('\n');
+
+        if lines.next()? != CACHE_HEADER {
+            return None;
+        }
+
+        let mut entries = BTreeMap::new();
+
+        while let Some(line) = lines.next() {
+            // trailing newline at the end of the file
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split('\t');
+            let file = parts.next()?.to_string();
+            let hash = u64::from_str_radix(parts.next()?, 16).ok()?;
+            let warning_count: usize = parts.next()?.parse().ok()?;
+            let count: usize = parts.next()?.parse().ok()?;
+
+            let warnings = (0..warning_count)
+                .map(|_| lines.next().map(str::to_string))
+                .collect::<Option<Vec<_>>>()?;
+            let rendered = (0..count)
+                .map(|_| lines.next())
+                .collect::<Option<Vec<_>>>()?
+                .join("\n");
+
+            entries.insert(
+                file,
+                Entry {
+                    hash,
+                    warnings,
+                    rendered,
+                },
+            );
+        }
+
+        Some(Cache { entries })
+    }
+
+    /// Get the cached output for `file` and the warnings rendering it emitted,
+    /// if its contents still hash to `hash`
+    pub fn get(&self, file: &str, hash: u64) -> Option<(&str, &[String])> {
+        self.entries
+            .get(file)
+            .filter(|e| e.hash == hash)
+            .map(|e| (e.rendered.as_str(), e.warnings.as_slice()))
+    }
+
+    /// Record the rendered output of `file` and the warnings rendering it emitted
+    /// A warning spanning several lines is stored as one line per line
+    pub fn insert(&mut self, file: &str, hash: u64, rendered: String, warnings: &[String]) {
+        let warnings = warnings
+            .iter()
+            .flat_map(|w| w.split('\n'))
+            .map(str::to_string)
+            .collect();
+        self.entries.insert(
+            file.to_string(),
+            Entry {
+                hash,
+                warnings,
+                rendered,
+            },
+        );
+    }
+
+    /// Write the cache to `path`, unless it already holds the same entries
+    /// Entries are sorted by path, so an unchanged cache writes the same bytes
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut out = String::new();
+        out.push_str(CACHE_HEADER);
+        out.push('\n');
+
+        self.entries.iter().for_each(|(file, e)| {
+            out.push_str(&format!(
+                "{}\t{:x}\t{}\t{}\n",
+                file,
+                e.hash,
+                e.warnings.len(),
+                e.rendered.split('\n').count()
+            ));
+            e.warnings.iter().for_each(|w| {
+                out.push_str(w);
+                out.push('\n');
+            });
+            out.push_str(&e.rendered);
+            out.push('\n');
+        });
+
+        if fs::read(path).is_ok_and(|old| old == out.as_bytes()) {
+            return Ok(());
+        }
+        File::create(path)?.write_all(out.as_bytes())
+    }
+}
+
+/// Hash the contents of a file with 64 bit FNV-1a
+/// This is stable across runs and toolchains, unlike `DefaultHasher`
+pub fn hash_contents(contents: &str) -> u64 {
+    contents.bytes().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_keep_their_warnings_through_a_save() {
+        let path = std::env::temp_dir().join(format!("marker-cache-{}", std::process::id()));
+        let path = path.display().to_string();
+
+        let mut cache = Cache::default();
+        let warnings = ["line 1:1: unknown tag @foo".to_string()];
+        cache.insert("a.typ", 7, "# a\n\ntext".to_string(), &warnings);
+        cache.insert("b.typ", 8, "# b".to_string(), &[]);
+        cache.save(&path).unwrap();
+
+        let loaded = Cache::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.get("a.typ", 7), Some(("# a\n\ntext", &warnings[..])));
+        assert_eq!(loaded.get("b.typ", 8), Some(("# b", &[][..])));
+        assert_eq!(loaded.get("a.typ", 8), None);
+    }
+}
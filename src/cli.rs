@@ -0,0 +1,527 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{Args, Parser, Subcommand};
+use marker_typ::{
+    Config, Format, Language, Locale, NamePatterns, Package, Sort, Theme, check_template,
+};
+
+use crate::{expand::expand_path, project::PROJECT_FILE, stats::today, walk::DEFAULT_GLOB};
+
+/// Generate markdown documentation from typst doc comments
+///
+/// Running `marker [FILES...]` with no subcommand is the same as `marker gen [FILES...]`
+///
+/// Options left out are read from marker.toml when it exists
+#[derive(Debug, Parser)]
+#[command(
+    name = "marker",
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Project file to read default options from, expanding ~ and $VARS
+    #[arg(long, global = true, value_name = "PATH", default_value = PROJECT_FILE, value_parser = parse_path)]
+    pub config: String,
+
+    #[command(flatten)]
+    pub gen_args: GenArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Render documentation for typst files
+    Gen(GenArgs),
+    /// Parse typst files and report problems with their doc comments without rendering
+    Check(CheckArgs),
+    /// Render documentation, then render it again whenever an input changes
+    Watch(WatchArgs),
+    /// Render html documentation, serve it on localhost and reload open pages
+    /// whenever an input changes
+    Serve(ServeArgs),
+    /// Report the fraction of public functions with a doc comment
+    Coverage(CoverageArgs),
+    /// Document every typst package under the inputs into its own directory of the output,
+    /// with an index linking to each
+    Workspace(WorkspaceArgs),
+    /// Report the functions and params added, removed and changed between two git revisions
+    Diff(DiffArgs),
+    /// Write a markdown changelog section of the functions added, changed, deprecated and
+    /// removed since a git revision
+    Changelog(ChangelogArgs),
+    /// Check that the version in typst.toml was bumped far enough for the changes
+    /// to the documented functions since a git revision
+    Semver(SemverArgs),
+    /// Rewrite typst doc comments in canonical form, in place
+    Fix(FixArgs),
+    /// Insert a skeleton doc comment above every public typst function without one
+    Scaffold(ScaffoldArgs),
+}
+
+/// Files or directories to document
+#[derive(Debug, Clone, Args)]
+pub struct InputArgs {
+    /// Typst files, directories to search for typst files, or - to read standard in
+    #[arg(value_name = "FILES or DIRECTORIES")]
+    pub inputs: Vec<String>,
+
+    /// Files to pick out of directories [default: **/*.typ]
+    #[arg(long)]
+    pub glob: Option<String>,
+
+    /// Parse every file as LANG: typst, python, rust or c,
+    /// instead of going by its extension
+    #[arg(long, value_name = "LANG", value_parser = parse_language)]
+    pub lang: Option<Language>,
+
+    /// The package being documented, from its typst.toml
+    #[arg(skip)]
+    pub package: Option<Package>,
+}
+
+impl InputArgs {
+    /// The glob to pick files out of directories with
+    pub fn glob(&self) -> &str {
+        self.glob.as_deref().unwrap_or(DEFAULT_GLOB)
+    }
+
+    /// The language to parse `file` as, from --lang or its extension
+    /// Files of an unknown extension are parsed as typst
+    pub fn language(&self, file: &str) -> Language {
+        self.lang
+            .clone()
+            .or_else(|| Language::from_path(file))
+            .unwrap_or_else(Language::typst)
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct GenArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Directory the paths of pages are relative to, set for each package of a workspace
+    #[arg(skip)]
+    pub root: Option<PathBuf>,
+
+    /// Exit with status 1 if any doc comment has warnings, e.g. an unknown tag,
+    /// while warnings about options or templates, e.g. an unknown section, are only reported
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Re-render every file instead of reusing .marker-cache
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Parse and render at most N files at once [default: one per core]
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// Hard wrap descriptions at COLS columns
+    #[arg(long, value_name = "COLS")]
+    pub wrap: Option<usize>,
+
+    /// Render as `markdown`, plain `text`, `json`, `html`, `asciidoc`, `rst`,
+    /// or `mdbook`, Docusaurus `mdx` or `man` pages in the -o directory [default: markdown]
+    #[arg(long, value_name = "FMT", value_parser = parse_format)]
+    pub format: Option<Format>,
+
+    /// Comma separated order of description,params,returns,errors,examples,notes,see
+    #[arg(long, value_name = "SECTIONS", value_delimiter = ',')]
+    pub section_order: Vec<String>,
+
+    /// Comma separated sections to fold into a collapsible block in markdown and html,
+    /// e.g. params,examples
+    #[arg(long, value_name = "SECTIONS", value_delimiter = ',')]
+    pub collapse: Vec<String>,
+
+    /// Only document functions whose name matches PATTERN, where `*` matches anything,
+    /// `?` a single character and `{a,b}` either alternative, e.g. `question-*` [repeatable]
+    #[arg(long, value_name = "PATTERN")]
+    pub include: Vec<String>,
+
+    /// Leave out functions whose name matches PATTERN, even if included [repeatable]
+    #[arg(long, value_name = "PATTERN")]
+    pub exclude: Vec<String>,
+
+    /// Document private functions, whose name starts with an underscore,
+    /// marked with a badge
+    #[arg(long)]
+    pub document_private: bool,
+
+    /// Level of each function's markdown heading, with sections one level deeper
+    #[arg(long, value_name = "LEVEL", value_parser = clap::value_parser!(u8).range(1..=5))]
+    pub heading_level: Option<u8>,
+
+    /// Language of headings, `en` or `de` [default: en]
+    #[arg(long, value_name = "LANG", value_parser = parse_locale)]
+    pub locale: Option<Locale>,
+
+    /// Order of the functions of a file: as in the `source`, `alpha`betical,
+    /// or `group`ed by @category [default: source]
+    #[arg(long, value_name = "ORDER", value_parser = parse_sort)]
+    pub sort: Option<Sort>,
+
+    /// Use TEXT as the heading of SECTION, e.g. params=Arguments
+    #[arg(long = "label", value_name = "SECTION=TEXT")]
+    pub labels: Vec<String>,
+
+    /// Render each function's markdown through DIR/function.md,
+    /// filling in placeholders like {{ header }} and {{ params }}
+    #[arg(long, value_name = "DIR", value_parser = parse_path)]
+    pub templates: Option<String>,
+
+    /// Style html pages with the style.css, header.html and footer.html in DIR,
+    /// each replacing its part of the bundled theme
+    #[arg(long, value_name = "DIR", value_parser = parse_path)]
+    pub theme: Option<String>,
+
+    /// Render the custom tag @NAME as a `badge`, `key-value` line or `section`
+    #[arg(long = "tag", value_name = "NAME=STYLE")]
+    pub tags: Vec<String>,
+
+    /// Write to PATH instead of standard out, expanding ~ and $VARS
+    #[arg(short, long, value_name = "PATH", value_parser = parse_path)]
+    pub output: Option<String>,
+
+    /// Start each markdown page written beside its source or in a directory
+    /// with YAML front matter holding its title, slug and date
+    #[arg(long)]
+    pub front_matter: bool,
+
+    /// Add KEY: VALUE to the front matter, turning it on, e.g. draft=false
+    #[arg(long = "front-matter-key", value_name = "KEY=VALUE")]
+    pub front_matter_keys: Vec<String>,
+
+    /// Write each file's docs beside it, e.g. exam.typ -> exam.md
+    #[arg(long, conflicts_with = "output")]
+    pub next_to_source: bool,
+
+    /// Write each file's docs to its relative path in DIR, e.g. src/exam.typ -> DIR/src/exam.md
+    #[arg(long, value_name = "DIR", value_parser = parse_path, conflicts_with_all = ["output", "next_to_source"])]
+    pub out_dir: Option<String>,
+
+    /// Write pages into a directory named after VERSION inside the output directory,
+    /// keeping a `latest` copy of the newest and a versions.json listing every version
+    /// beside them. VERSION defaults to the one in typst.toml
+    #[arg(long, value_name = "VERSION", num_args = 0..=1, require_equals = true, default_missing_value = "")]
+    pub version_dir: Option<String>,
+
+    /// Write every file's docs to PATH as one reference, with a section per file
+    /// and a table of contents across all of them
+    #[arg(long, value_name = "PATH", value_parser = parse_path, conflicts_with_all = ["output", "next_to_source", "out_dir"])]
+    pub single_file: Option<String>,
+
+    /// Write the docs into PATH between its <!-- marker:start --> and <!-- marker:end --> comments,
+    /// leaving the rest of it untouched, e.g. a README
+    #[arg(long, value_name = "PATH", value_parser = parse_path, conflicts_with_all = ["output", "next_to_source", "out_dir", "single_file"])]
+    pub inject: Option<String>,
+
+    /// Compile typst examples into images in DIR with the `typst` CLI,
+    /// and show them under each example
+    #[arg(long, value_name = "DIR", value_parser = parse_path)]
+    pub example_images: Option<String>,
+
+    /// Image format of compiled examples
+    #[arg(long, value_name = "FMT", default_value = "svg", value_parser = ["svg", "png"])]
+    pub example_image_format: String,
+
+    /// Write a Docusaurus sidebar listing every page to PATH
+    #[arg(long, value_name = "PATH", value_parser = parse_path)]
+    pub sidebars: Option<String>,
+
+    /// Render markdown parameters as a line each instead of a table
+    #[arg(long)]
+    pub param_list: bool,
+
+    /// Link typst types to their page of the typst reference in markdown and html
+    #[arg(long)]
+    pub link_types: bool,
+
+    /// Start the docs of every file with a linked table of contents
+    #[arg(long)]
+    pub toc: bool,
+
+    /// Write a markdown table of contents linking to the docs of every file to PATH
+    #[arg(long, value_name = "PATH", value_parser = parse_path)]
+    pub toc_index: Option<String>,
+
+    /// Write an alphabetical index of every function across files to PATH
+    #[arg(long, value_name = "PATH", value_parser = parse_path)]
+    pub index: Option<String>,
+
+    /// Write a JSON search index of every function across files to PATH,
+    /// and give html pages a search box using it
+    #[arg(long, value_name = "PATH", value_parser = parse_path)]
+    pub search_index: Option<String>,
+
+    /// Write documentation coverage metrics to PATH as JSON
+    #[arg(long, value_name = "PATH", value_parser = parse_path)]
+    pub stats_json: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Report problems for people, as `github` Actions annotations shown inline
+    /// on pull requests, or as a `sarif` log on standard out for code scanning
+    #[arg(long, value_name = "FMT", default_value = "human", value_parser = ["human", "github", "sarif"])]
+    pub output_format: String,
+
+    /// Only fail on warnings that aren't in the baseline at PATH
+    #[arg(long, value_name = "PATH", value_parser = parse_path)]
+    pub baseline: Option<String>,
+
+    /// Write every warning to a baseline at PATH instead of failing on them
+    #[arg(long, value_name = "PATH", value_parser = parse_path, conflicts_with = "baseline")]
+    pub write_baseline: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct CoverageArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Exit non-zero if total coverage is under PERCENT
+    #[arg(long, value_name = "PERCENT")]
+    pub fail_under: Option<f64>,
+
+    /// Write a shields.io endpoint badge of the total coverage to PATH
+    #[arg(long, value_name = "PATH", value_parser = parse_path)]
+    pub badge: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// Git revision to compare from, e.g. v0.1.0
+    pub from: String,
+
+    /// Git revision to compare to, e.g. HEAD
+    pub to: String,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Print the changes as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ChangelogArgs {
+    /// Git revision of the last release, e.g. v0.2.0
+    #[arg(long, value_name = "REV")]
+    pub since: String,
+
+    /// Git revision to stop at [default: the working tree]
+    #[arg(long, value_name = "REV")]
+    pub until: Option<String>,
+
+    /// Heading of the section [default: the version in typst.toml, or Unreleased]
+    #[arg(long, value_name = "TEXT")]
+    pub heading: Option<String>,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct SemverArgs {
+    /// Git revision of the last release, e.g. v0.2.0
+    #[arg(long, value_name = "REV")]
+    pub since: String,
+
+    /// Git revision of the new release [default: the working tree]
+    #[arg(long, value_name = "REV")]
+    pub until: Option<String>,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// The typst.toml holding the version, beside the project file
+    #[arg(skip)]
+    pub manifest: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct FixArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// List the files that aren't in canonical form instead of fixing them,
+    /// failing if there are any
+    #[arg(long)]
+    pub check: bool,
+
+    /// Wrap prose in doc comments at COLS columns
+    #[arg(long, value_name = "COLS", default_value_t = 100)]
+    pub wrap: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct ScaffoldArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    #[command(flatten)]
+    pub gen_args: GenArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct WorkspaceArgs {
+    #[command(flatten)]
+    pub gen_args: GenArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    #[command(flatten)]
+    pub gen_args: GenArgs,
+
+    /// Port to serve the docs on
+    #[arg(long, default_value_t = 3000)]
+    pub port: u16,
+}
+
+impl GenArgs {
+    /// Directory to write a page per file into, if any
+    /// Formats that are always a directory of pages also take it from -o
+    pub fn out_dir(&self) -> Option<&str> {
+        match self.format.unwrap_or_default().is_directory() {
+            true => self.out_dir.as_deref().or(self.output.as_deref()),
+            false => self.out_dir.as_deref(),
+        }
+    }
+
+    /// Build the render options from the flags
+    pub fn config(&self) -> Config {
+        let mut config = Config {
+            wrap: self.wrap,
+            format: self.format.unwrap_or_default(),
+            toc: self.toc,
+            param_list: self.param_list,
+            link_types: self.link_types,
+            heading_level: self.heading_level.map(usize::from),
+            locale: self.locale.unwrap_or_default(),
+            sort: self.sort.unwrap_or_default(),
+            include: NamePatterns::new(&self.include),
+            exclude: NamePatterns::new(&self.exclude),
+            document_private: self.document_private,
+            // a README being injected into already says what it is about
+            package: self.input.package.clone().filter(|_| self.inject.is_none()),
+            root: self.root.clone(),
+            ..Default::default()
+        };
+
+        let order: Vec<&str> = self.section_order.iter().map(String::as_str).collect();
+        config.set_section_order(&order);
+
+        let collapse: Vec<&str> = self.collapse.iter().map(String::as_str).collect();
+        config.set_collapse(&collapse);
+
+        let labels: Vec<&str> = self.labels.iter().map(String::as_str).collect();
+        config.set_labels(&labels);
+
+        let tags: Vec<&str> = self.tags.iter().map(String::as_str).collect();
+        config.set_tags(&tags);
+
+        if self.front_matter || !self.front_matter_keys.is_empty() {
+            // the date goes first, so a date key replaces it
+            let date = format!("date={}", today());
+            let keys: Vec<&str> = std::iter::once(date.as_str())
+                .chain(self.front_matter_keys.iter().map(String::as_str))
+                .collect();
+            config.set_front_matter(&keys);
+        }
+
+        if let Some(dir) = &self.templates {
+            config.template = load_template(dir, config.format);
+        }
+        if let Some(dir) = &self.theme {
+            config.theme = load_theme(dir);
+        }
+
+        config
+    }
+}
+
+/// File in the templates directory each function is rendered through
+const TEMPLATE_FILE: &str = "function.md";
+
+/// Read the function template in `dir` for `format`
+/// Formats without templates, and missing or invalid templates are ignored with a warning
+fn load_template(dir: &str, format: Format) -> Option<String> {
+    if !matches!(format, Format::Markdown | Format::Mdbook | Format::Mdx) {
+        eprintln!("warning: templates are only used for markdown, mdbook and mdx");
+        return None;
+    }
+
+    let path = Path::new(dir).join(TEMPLATE_FILE);
+    match fs::read_to_string(&path) {
+        Ok(template) => match check_template(&template) {
+            Some(e) => {
+                eprintln!("warning: ignoring templates, {}: {e}", path.display());
+                None
+            }
+            None => Some(template),
+        },
+        Err(e) => {
+            eprintln!("warning: ignoring templates, {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Read the theme in `dir`, where every file is optional
+/// A missing directory is ignored with a warning
+fn load_theme(dir: &str) -> Theme {
+    if !Path::new(dir).is_dir() {
+        eprintln!("warning: ignoring theme, {dir} is not a directory");
+        return Theme::default();
+    }
+
+    let read = |file: &str| fs::read_to_string(Path::new(dir).join(file)).ok();
+    Theme {
+        css: read("style.css"),
+        header: read("header.html"),
+        footer: read("footer.html"),
+    }
+}
+
+/// Parse a `--format` value
+fn parse_format(name: &str) -> Result<Format, String> {
+    Format::from_name(name).ok_or_else(|| {
+        "expected one of: markdown, text, json, html, asciidoc, rst, man, mdbook, mdx".to_string()
+    })
+}
+
+/// Parse a `--lang` value
+fn parse_language(name: &str) -> Result<Language, String> {
+    Language::from_name(name).ok_or_else(|| "expected one of: typst, python, rust, c".to_string())
+}
+
+/// Parse a `--locale` value
+fn parse_locale(name: &str) -> Result<Locale, String> {
+    Locale::from_name(name).ok_or_else(|| "expected one of: en, de".to_string())
+}
+
+/// Parse a `--sort` value
+fn parse_sort(name: &str) -> Result<Sort, String> {
+    Sort::from_name(name).ok_or_else(|| "expected one of: source, alpha, group".to_string())
+}
+
+/// Expand ~ and $VARS in a path argument
+fn parse_path(path: &str) -> Result<String, String> {
+    Ok(expand_path(path))
+}
@@ -0,0 +1,735 @@
+use std::{collections::BTreeMap, fmt, path::PathBuf, sync::OnceLock};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{DocComment, Kind, Param, anchor, man::MAN_SECTION, wrap::wrap};
+
+/// Output formats `marker` can render to
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Format {
+    #[default]
+    Markdown,
+    Text,
+    Json,
+    Html,
+    /// Markdown chapters and a SUMMARY.md for mdBook
+    Mdbook,
+    /// MDX pages with front matter for Docusaurus
+    Mdx,
+    Asciidoc,
+    /// reStructuredText for Sphinx
+    Rst,
+    /// A roff man page per function
+    Man,
+}
+
+impl Format {
+    /// Parse a format from its command line name
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "markdown" | "md" => Some(Format::Markdown),
+            "text" | "txt" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "html" => Some(Format::Html),
+            "mdbook" => Some(Format::Mdbook),
+            "mdx" | "docusaurus" => Some(Format::Mdx),
+            "asciidoc" | "adoc" => Some(Format::Asciidoc),
+            "rst" | "restructuredtext" => Some(Format::Rst),
+            "man" | "roff" => Some(Format::Man),
+            _ => None,
+        }
+    }
+
+    /// Whether the format is a directory of pages rather than a single document
+    pub fn is_directory(&self) -> bool {
+        matches!(self, Format::Mdbook | Format::Mdx | Format::Man)
+    }
+
+    /// File extension used when writing this format next to its source
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Markdown | Format::Mdbook => "md",
+            Format::Text => "txt",
+            Format::Json => "json",
+            Format::Html => "html",
+            Format::Mdx => "mdx",
+            Format::Asciidoc => "adoc",
+            Format::Rst => "rst",
+            Format::Man => MAN_SECTION,
+        }
+    }
+}
+
+/// Sections of a rendered doc comment that can be reordered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Section {
+    Description,
+    Params,
+    Returns,
+    Errors,
+    SeeAlso,
+    Examples,
+    Notes,
+}
+
+impl Section {
+    /// Default order sections are rendered in
+    pub const ALL: [Section; 7] = [
+        Section::Description,
+        Section::Params,
+        Section::Returns,
+        Section::Errors,
+        Section::Examples,
+        Section::Notes,
+        Section::SeeAlso,
+    ];
+
+    /// Parse a section from its name in `section_order`
+    pub fn from_name(name: &str) -> Option<Section> {
+        match name {
+            "description" => Some(Section::Description),
+            "params" => Some(Section::Params),
+            "returns" => Some(Section::Returns),
+            "errors" => Some(Section::Errors),
+            "examples" => Some(Section::Examples),
+            "notes" => Some(Section::Notes),
+            "see" => Some(Section::SeeAlso),
+            _ => None,
+        }
+    }
+
+    /// Name of the section in `section_order`, the inverse of `from_name`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Section::Description => "description",
+            Section::Params => "params",
+            Section::Returns => "returns",
+            Section::Errors => "errors",
+            Section::Examples => "examples",
+            Section::Notes => "notes",
+            Section::SeeAlso => "see",
+        }
+    }
+
+    /// Default heading of the section in rendered output
+    pub fn label(&self) -> &'static str {
+        match self {
+            Section::Description => "Description",
+            Section::Params => "Parameters",
+            Section::Returns => "Returns",
+            Section::Errors => "Errors",
+            Section::SeeAlso => "See also",
+            Section::Examples => "Examples",
+            Section::Notes => "Notes",
+        }
+    }
+}
+
+/// Languages the headings of rendered output come in
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+}
+
+impl Locale {
+    /// Parse a locale from its language code, e.g. "de" or "de-AT"
+    pub fn from_name(name: &str) -> Option<Locale> {
+        let language = name.split(['-', '_']).next().unwrap_or_default();
+        match language.to_lowercase().as_str() {
+            "en" => Some(Locale::English),
+            "de" => Some(Locale::German),
+            _ => None,
+        }
+    }
+
+    /// Heading of `section` in this language
+    pub fn label(&self, section: Section) -> &'static str {
+        match self {
+            Locale::English => section.label(),
+            Locale::German => match section {
+                Section::Description => "Beschreibung",
+                Section::Params => "Parameter",
+                Section::Returns => "Rückgabewert",
+                Section::Errors => "Fehler",
+                Section::SeeAlso => "Siehe auch",
+                Section::Examples => "Beispiele",
+                Section::Notes => "Hinweise",
+            },
+        }
+    }
+
+    /// Text of the stub an alias redirects from, before the link to the function
+    pub fn alias_of(&self) -> &'static str {
+        match self {
+            Locale::English => "Alias of",
+            Locale::German => "Alias für",
+        }
+    }
+
+    /// Label of an item of `kind` in this language
+    pub fn kind(&self, kind: Kind) -> &'static str {
+        match (self, kind) {
+            (Locale::English, Kind::Function) => "function",
+            (Locale::English, Kind::Constant) => "constant",
+            (Locale::English, Kind::State) => "state",
+            (Locale::English, Kind::Counter) => "counter",
+            (Locale::English, Kind::ShowRule) => "show rule",
+            (Locale::English, Kind::SetRule) => "set rule",
+            (Locale::German, Kind::Function) => "Funktion",
+            (Locale::German, Kind::Constant) => "Konstante",
+            (Locale::German, Kind::State) => "Zustand",
+            (Locale::German, Kind::Counter) => "Zähler",
+            (Locale::German, Kind::ShowRule) => "Show-Regel",
+            (Locale::German, Kind::SetRule) => "Set-Regel",
+        }
+    }
+
+    /// Heading of the items of `kind` in a file in this language
+    pub fn kinds(&self, kind: Kind) -> &'static str {
+        match (self, kind) {
+            (Locale::English, Kind::Function) => "Functions",
+            (Locale::English, Kind::Constant) => "Constants",
+            (Locale::English, Kind::State) => "States",
+            (Locale::English, Kind::Counter) => "Counters",
+            (Locale::English, Kind::ShowRule) => "Show rules",
+            (Locale::English, Kind::SetRule) => "Set rules",
+            (Locale::German, Kind::Function) => "Funktionen",
+            (Locale::German, Kind::Constant) => "Konstanten",
+            (Locale::German, Kind::State) => "Zustände",
+            (Locale::German, Kind::Counter) => "Zähler",
+            (Locale::German, Kind::ShowRule) => "Show-Regeln",
+            (Locale::German, Kind::SetRule) => "Set-Regeln",
+        }
+    }
+
+    /// Badge of private functions in this language
+    pub fn private(&self) -> &'static str {
+        match self {
+            Locale::English => "private",
+            Locale::German => "privat",
+        }
+    }
+
+    /// Heading of the functions without a @category in this language
+    pub fn uncategorized(&self) -> &'static str {
+        match self {
+            Locale::English => "Other",
+            Locale::German => "Sonstiges",
+        }
+    }
+
+    /// Marker of a required param, or of an optional one, in this language
+    pub fn requirement(&self, required: bool) -> &'static str {
+        match (self, required) {
+            (Locale::English, true) => "required",
+            (Locale::English, false) => "optional",
+            (Locale::German, true) => "erforderlich",
+            (Locale::German, false) => "optional",
+        }
+    }
+
+    /// How many params of a function are required and how many optional in this language,
+    /// leaving out a count of none, e.g. "2 required, 3 optional"
+    pub fn param_counts(&self, required: usize, optional: usize) -> String {
+        [(required, true), (optional, false)]
+            .into_iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, is_required)| format!("{count} {}", self.requirement(is_required)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Heading of the named params, or of the positional ones, in this language
+    pub fn param_group(&self, named: bool) -> &'static str {
+        match (self, named) {
+            (Locale::English, false) => "Positional",
+            (Locale::English, true) => "Named",
+            (Locale::German, false) => "Positionell",
+            (Locale::German, true) => "Benannt",
+        }
+    }
+
+    /// Column headings of a parameter table in this language
+    pub fn param_columns(&self) -> [&'static str; 4] {
+        match self {
+            Locale::English => ["Name", "Type", "Default", "Description"],
+            Locale::German => ["Name", "Typ", "Standardwert", "Beschreibung"],
+        }
+    }
+}
+
+/// Orders the functions of a file can be rendered in
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Sort {
+    /// As they are in the source
+    #[default]
+    Source,
+    /// Alphabetically by title
+    Alpha,
+    /// By @category, in the order categories first appear,
+    /// then as they are in the source, with uncategorized functions last
+    Group,
+}
+
+impl Sort {
+    /// Parse an order from its name, `source`, `alpha` or `group`
+    pub fn from_name(name: &str) -> Option<Sort> {
+        match name {
+            "source" => Some(Sort::Source),
+            "alpha" => Some(Sort::Alpha),
+            "group" => Some(Sort::Group),
+            _ => None,
+        }
+    }
+}
+
+/// Ways a custom tag can be rendered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TagStyle {
+    /// Inline under the signature, e.g. `Difficulty: hard`
+    Badge,
+    /// A "Label: value" line under the signature
+    KeyValue,
+    /// A section of its own after the built in ones, listing every value
+    Section,
+}
+
+impl TagStyle {
+    /// Parse a style from its name in the config
+    pub fn from_name(name: &str) -> Option<TagStyle> {
+        match name {
+            "badge" => Some(TagStyle::Badge),
+            "key-value" => Some(TagStyle::KeyValue),
+            "section" => Some(TagStyle::Section),
+            _ => None,
+        }
+    }
+}
+
+/// A tag registered by the user, e.g. @rubric
+#[derive(Debug, Clone)]
+pub struct CustomTag {
+    /// Name without the @, lowercase
+    pub name: String,
+    pub style: TagStyle,
+}
+
+impl CustomTag {
+    /// Name of the tag as shown in the output, e.g. "due-date" -> "Due date"
+    pub fn label(&self) -> String {
+        let spaced = self.name.replace(['-', '_'], " ");
+        let mut chars = spaced.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
+}
+
+/// Look of standalone html pages
+#[derive(Debug, Default, Clone)]
+pub struct Theme {
+    /// Stylesheet to use instead of the bundled one
+    pub css: Option<String>,
+    /// Html to use instead of the page's `<header>`, where `{{ title }}` is the page title
+    pub header: Option<String>,
+    /// Html at the end of every page
+    pub footer: Option<String>,
+}
+
+/// The typst package being documented, from the `[package]` of its typst.toml
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    /// File the package is imported through, relative to the manifest
+    pub entrypoint: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl Package {
+    /// The name and version, e.g. "quizzy 0.2.0"
+    pub fn title(&self) -> String {
+        format!("{} {}", self.name, self.version)
+    }
+}
+
+/// Options controlling how doc comments are rendered
+#[derive(Debug, Default, Clone)]
+pub struct Config {
+    /// Column to hard wrap prose at, `None` leaves lines as they are
+    pub wrap: Option<usize>,
+    pub format: Format,
+    /// Sections to render first, in order
+    pub section_order: Vec<Section>,
+    /// Tags to render beyond the built in ones, in order
+    pub tags: Vec<CustomTag>,
+    /// Start the docs of every file with a linked table of contents
+    pub toc: bool,
+    /// Order to render the functions of a file in
+    pub sort: Sort,
+    /// Name patterns of the functions to render, every function when empty
+    pub include: NamePatterns,
+    /// Name patterns of the functions to leave out, even if included
+    pub exclude: NamePatterns,
+    /// Render private functions, whose name starts with an underscore, with a badge
+    pub document_private: bool,
+    /// Render markdown parameters as a line each instead of a table
+    pub param_list: bool,
+    /// Link typst types to their page of the typst reference in markdown and html
+    pub link_types: bool,
+    /// Sections folded into a `<details>` block in markdown and html
+    pub collapse: Vec<Section>,
+    /// Level of each function's markdown heading, 2 when unset
+    /// Section headings go one level deeper
+    pub heading_level: Option<usize>,
+    /// Language of headings
+    pub locale: Locale,
+    /// Headings to use instead of the locale's label of a section
+    pub labels: Vec<(Section, String)>,
+    /// Pages every documented function is on, by title,
+    /// relative to the page being rendered and empty for the same page
+    pub links: BTreeMap<String, String>,
+    /// Template each function's markdown is rendered through, with `{{ part }}` placeholders
+    pub template: Option<String>,
+    pub theme: Theme,
+    /// Link to the search index from the root of the docs,
+    /// html pages get a search box when it is set
+    pub search: Option<String>,
+    /// Keys of the YAML front matter markdown pages start with, after their title and slug,
+    /// `None` for no front matter
+    pub front_matter: Option<Vec<(String, String)>>,
+    /// Package combined docs start with a header for
+    pub package: Option<Package>,
+    /// Directory the paths of pages are relative to, e.g. a package in a workspace
+    pub root: Option<PathBuf>,
+}
+
+impl Config {
+    /// Set `section_order` from a list of section names
+    /// Unknown names are ignored with a warning
+    pub fn set_section_order(&mut self, names: &[&str]) {
+        self.section_order = names
+            .iter()
+            .filter_map(|name| {
+                let section = Section::from_name(name.trim());
+                if section.is_none() {
+                    eprintln!("warning: ignoring unknown section `{}`", name.trim());
+                }
+                section
+            })
+            .collect();
+    }
+
+    /// Set `collapse` from a list of section names
+    /// Unknown names and the description, which has no heading to fold under,
+    /// are ignored with a warning
+    pub fn set_collapse(&mut self, names: &[&str]) {
+        self.collapse = names
+            .iter()
+            .filter_map(|name| match Section::from_name(name.trim()) {
+                Some(Section::Description) => {
+                    eprintln!("warning: the description can't be collapsed");
+                    None
+                }
+                Some(section) => Some(section),
+                None => {
+                    eprintln!("warning: ignoring unknown section `{}`", name.trim());
+                    None
+                }
+            })
+            .collect();
+    }
+
+    /// Set `labels` from specs in the form "section=heading"
+    /// Malformed specs are ignored with a warning
+    pub fn set_labels(&mut self, specs: &[&str]) {
+        self.labels = specs
+            .iter()
+            .filter_map(|spec| {
+                let label = spec.split_once('=').and_then(|(name, text)| {
+                    Some((Section::from_name(name.trim())?, text.trim().to_string()))
+                });
+                if label.is_none() {
+                    eprintln!(
+                        "warning: ignoring label `{}`, expected section=heading",
+                        spec.trim()
+                    );
+                }
+                label
+            })
+            .collect();
+    }
+
+    /// Turn on front matter, with keys from specs in the form "key=value"
+    /// The value is written as is, so it can be any YAML, e.g. "draft=false"
+    /// Malformed specs are ignored with a warning
+    pub fn set_front_matter(&mut self, specs: &[&str]) {
+        let keys = specs
+            .iter()
+            .filter_map(|spec| {
+                let key = spec
+                    .split_once('=')
+                    .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                    .filter(|(key, _)| !key.is_empty());
+                if key.is_none() {
+                    eprintln!(
+                        "warning: ignoring front matter `{}`, expected key=value",
+                        spec.trim()
+                    );
+                }
+                key
+            })
+            .collect();
+        self.front_matter = Some(keys);
+    }
+
+    /// The heading of `section`, e.g. "Parameters"
+    pub fn label(&self, section: Section) -> &str {
+        self.labels
+            .iter()
+            .find(|(s, _)| *s == section)
+            .map_or(self.locale.label(section), |(_, text)| text.as_str())
+    }
+
+    /// Labels `doc` is marked with ahead of its badges, the kind of item it is
+    /// unless it's a function, and whether it is private
+    pub fn marks(&self, doc: &DocComment) -> Vec<&'static str> {
+        let kind = Some(doc.kind())
+            .filter(|k| *k != Kind::Function)
+            .map(|k| self.locale.kind(k));
+        let private = doc.is_private().then(|| self.locale.private());
+        kind.into_iter().chain(private).collect()
+    }
+
+    /// The line under the signature of `doc`, its metadata and how many of its params are required
+    pub fn metadata(&self, doc: &DocComment) -> Option<String> {
+        let required = doc.params.iter().filter(|p| p.is_required()).count();
+        let counts = (!doc.params.is_empty()).then(|| {
+            self.locale
+                .param_counts(required, doc.params.len() - required)
+        });
+        let parts: Vec<String> = doc.metadata().into_iter().chain(counts).collect();
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+
+    /// The params of `doc` under a "Positional" and a "Named" heading, leaving out an empty one,
+    /// or all of them in one group with no heading when none is named
+    pub fn param_groups<'a>(
+        &self,
+        doc: &'a DocComment,
+    ) -> Vec<(Option<&'static str>, Vec<&'a Param>)> {
+        if !doc.params.iter().any(|p| p.named) {
+            return vec![(None, doc.params.iter().collect())];
+        }
+
+        let (named, positional): (Vec<_>, Vec<_>) = doc.params.iter().partition(|p| p.named);
+        [(false, positional), (true, named)]
+            .into_iter()
+            .filter(|(_, params)| !params.is_empty())
+            .map(|(is_named, params)| (Some(self.locale.param_group(is_named)), params))
+            .collect()
+    }
+
+    /// Whether `doc` is rendered, under the include and exclude patterns
+    /// and unless it is private
+    pub fn documents(&self, doc: &DocComment) -> bool {
+        self.includes(&doc.title) && (self.document_private || !doc.is_private())
+    }
+
+    /// Whether the function titled `title` is rendered under the include and exclude patterns
+    pub fn includes(&self, title: &str) -> bool {
+        (self.include.is_empty() || self.include.matches(title)) && !self.exclude.matches(title)
+    }
+
+    /// The markdown heading marker `depth` levels under a function's heading,
+    /// e.g. "###" for sections at the default level
+    pub fn heading(&self, depth: usize) -> String {
+        let level = self.heading_level.unwrap_or(2) + depth;
+        "#".repeat(level.clamp(1, 6))
+    }
+
+    /// Register custom tags from specs in the form "name=style"
+    /// Malformed specs are ignored with a warning
+    pub fn set_tags(&mut self, specs: &[&str]) {
+        self.tags = specs
+            .iter()
+            .filter_map(|spec| {
+                let tag = spec.split_once('=').and_then(|(name, style)| {
+                    Some(CustomTag {
+                        name: name.trim().trim_start_matches('@').to_lowercase(),
+                        style: TagStyle::from_name(style.trim())?,
+                    })
+                });
+                if tag.is_none() {
+                    eprintln!(
+                        "warning: ignoring tag `{}`, expected name=badge, name=key-value or name=section",
+                        spec.trim()
+                    );
+                }
+                tag
+            })
+            .collect();
+    }
+
+    /// Custom tags rendered in `style`
+    pub fn tags_in(&self, style: TagStyle) -> impl Iterator<Item = &CustomTag> {
+        self.tags.iter().filter(move |t| t.style == style)
+    }
+
+    /// Every section in render order
+    /// Sections missing from `section_order` follow in their default order
+    pub fn sections(&self) -> Vec<Section> {
+        let mut sections = self.section_order.clone();
+        Section::ALL.iter().for_each(|s| {
+            if !sections.contains(s) {
+                sections.push(*s);
+            }
+        });
+        sections
+    }
+
+    /// The page `title` is documented on, relative to the page being rendered
+    /// Empty when it is on the same page
+    pub fn page_of(&self, title: &str) -> &str {
+        self.links.get(title).map_or("", String::as_str)
+    }
+
+    /// Turn mentions of documented functions in `text`, written `#name` or `[name]`,
+    /// into links built by `link` from the name and where it points
+    /// Code spans, unknown names and `[name]` already used as a link are left alone
+    pub fn autolink(&self, text: &str, link: impl Fn(&str, &str) -> String) -> String {
+        if self.links.is_empty() {
+            return text.to_string();
+        }
+
+        static MENTION_RE: OnceLock<Regex> = OnceLock::new();
+        let mention_re = MENTION_RE.get_or_init(|| {
+            Regex::new(
+                r"(?P<before>^|[^\w\]&])(?:#(?P<hash>[\w-]+)|\[(?P<bracket>[\w-]+)\](?P<after>[(\[:]?))",
+            )
+            .unwrap()
+        });
+
+        text.split('`')
+            .enumerate()
+            .map(|(i, part)| match i % 2 {
+                1 => part.to_string(),
+                _ => mention_re
+                    .replace_all(part, |caps: &regex::Captures| {
+                        let name = caps
+                            .name("hash")
+                            .or(caps.name("bracket"))
+                            .map_or("", |m| m.as_str());
+                        let linked = caps.name("after").is_some_and(|a| !a.is_empty());
+
+                        match self.links.get(name) {
+                            Some(page) if !linked => {
+                                let href = format!("{page}#{}", anchor(name));
+                                format!("{}{}", &caps["before"], link(name, &href))
+                            }
+                            _ => caps[0].to_string(),
+                        }
+                    })
+                    .to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("`")
+    }
+
+    /// Wrap `text` at the configured column, if any
+    pub fn wrap(&self, text: &str) -> String {
+        match self.wrap {
+            Some(width) => wrap(text, width),
+            None => text.to_string(),
+        }
+    }
+}
+
+/// Globs matching function names, where `*` matches anything, `?` matches a single character,
+/// `[ab]` matches either character and `{a,b}` either alternative, e.g. `question-*`
+/// The globs are compiled once, when the patterns are set
+#[derive(Default, Clone)]
+pub struct NamePatterns {
+    patterns: Vec<String>,
+    set: GlobSet,
+}
+
+impl NamePatterns {
+    /// Compile `patterns`
+    /// Invalid patterns are ignored with a warning, and match nothing
+    pub fn new(patterns: &[String]) -> NamePatterns {
+        let mut builder = GlobSetBuilder::new();
+        patterns
+            .iter()
+            .for_each(|pattern| match Glob::new(pattern) {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(e) => eprintln!("warning: ignoring pattern `{pattern}`, {e}"),
+            });
+
+        NamePatterns {
+            patterns: patterns.to_vec(),
+            set: builder.build().unwrap_or_default(),
+        }
+    }
+
+    /// Whether no patterns were given, even invalid ones
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Whether `name` matches any of the patterns
+    pub fn matches(&self, name: &str) -> bool {
+        self.set.is_match(name)
+    }
+}
+
+// the compiled set is left out, the options are hashed by their debug form
+impl fmt::Debug for NamePatterns {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.patterns.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(patterns: &[&str]) -> NamePatterns {
+        NamePatterns::new(&patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn includes_by_name_globs() {
+        let config = Config {
+            include: patterns(&["question*", "{add,sub}"]),
+            exclude: patterns(&["*-layout"]),
+            ..Default::default()
+        };
+
+        assert!(config.includes("question"));
+        assert!(config.includes("question-text"));
+        assert!(config.includes("sub"));
+        assert!(!config.includes("question-layout"));
+        assert!(!config.includes("mul"));
+        assert!(Config::default().includes("mul"));
+    }
+
+    #[test]
+    fn invalid_pattern_matches_nothing() {
+        let config = Config {
+            include: patterns(&["{add"]),
+            ..Default::default()
+        };
+
+        assert!(!config.includes("add"));
+        assert_eq!(format!("{:?}", config.include), r#"["{add"]"#);
+    }
+}
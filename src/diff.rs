@@ -0,0 +1,639 @@
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+
+use crate::{DocComment, Param, version_numbers};
+
+/// How the documented functions changed between two versions of the sources
+#[derive(Debug, Default, Serialize)]
+pub struct ApiDiff {
+    /// Functions only in the new version
+    pub added: Vec<Function>,
+    /// Functions only in the old version
+    pub removed: Vec<Function>,
+    /// Functions in both whose docs differ
+    pub changed: Vec<FunctionChange>,
+}
+
+/// A function on one side of a diff
+#[derive(Debug, Clone, Serialize)]
+pub struct Function {
+    pub title: String,
+    /// e.g. "question(body, points: 1)"
+    pub signature: String,
+    /// First line of the description
+    pub summary: String,
+}
+
+impl Function {
+    fn new(doc: &DocComment) -> Function {
+        Function {
+            title: doc.title.clone(),
+            signature: doc.signature(),
+            summary: doc.summary().to_string(),
+        }
+    }
+}
+
+/// How the docs of one function changed
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionChange {
+    pub title: String,
+    pub old_signature: String,
+    pub new_signature: String,
+    pub params: Vec<ParamChange>,
+    /// The old and new return types, empty for none, when they differ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<(String, String)>,
+    /// Why the function is no longer to be used, when it was deprecated in the new version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    /// Whether the description was rewritten
+    pub description: bool,
+}
+
+/// How one param of a function changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum ParamChange {
+    Added {
+        name: String,
+        default: Option<String>,
+    },
+    Removed {
+        name: String,
+    },
+    /// The union of types it takes changed
+    Type {
+        name: String,
+        old: Vec<String>,
+        new: Vec<String>,
+    },
+    Default {
+        name: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+}
+
+/// How far a release has to bump the version for the changes in it, from least to most
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Nothing a caller could notice, like a rewritten description
+    Patch,
+    /// Additions, like a new function or an optional param
+    Minor,
+    /// Changes that can break callers, like a removed function
+    Major,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::Patch => "patch",
+            Severity::Minor => "minor",
+            Severity::Major => "major",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// How far going from version `old` to `new` bumps it, `None` if it doesn't go up
+/// or either isn't a version. Before 1.0, the minor version is the one
+/// for breaking changes and the patch version the one for additions
+pub fn bump(old: &str, new: &str) -> Option<Severity> {
+    let (old, new) = (version_numbers(old)?, version_numbers(new)?);
+    if new <= old {
+        return None;
+    }
+
+    let changed = old
+        .iter()
+        .chain(std::iter::repeat(&0))
+        .zip(new.iter().chain(std::iter::repeat(&0)))
+        .position(|(o, n)| o != n)
+        .unwrap_or_default();
+    let changed = match old.first() == Some(&0) && new.first() == Some(&0) {
+        true => changed.saturating_sub(1),
+        false => changed,
+    };
+
+    Some(match changed {
+        0 => Severity::Major,
+        1 => Severity::Minor,
+        _ => Severity::Patch,
+    })
+}
+
+/// Compare the doc comments of two versions of the sources, matching functions by title
+/// Added and changed functions come in the order of `new`, removed ones in the order of `old`
+/// The doc comments of whole files aren't part of the API
+pub fn diff(old: &[DocComment], new: &[DocComment]) -> ApiDiff {
+    let find = |docs: &'_ [DocComment], title: &str| -> Option<usize> {
+        docs.iter().position(|d| !d.module && d.title == title)
+    };
+
+    let mut api = ApiDiff::default();
+
+    for doc in new.iter().filter(|d| !d.module) {
+        match find(old, &doc.title) {
+            Some(i) => {
+                let change = diff_function(&old[i], doc);
+                if change.params.is_empty()
+                    && change.return_type.is_none()
+                    && change.deprecated.is_none()
+                    && !change.description
+                {
+                    continue;
+                }
+                api.changed.push(change);
+            }
+            None => api.added.push(Function::new(doc)),
+        }
+    }
+
+    api.removed = old
+        .iter()
+        .filter(|d| !d.module && find(new, &d.title).is_none())
+        .map(Function::new)
+        .collect();
+
+    api
+}
+
+/// Compare two versions of the doc comment of one function
+fn diff_function(old: &DocComment, new: &DocComment) -> FunctionChange {
+    let return_type = |doc: &DocComment| {
+        doc.returns
+            .iter()
+            .map(|r| r.data_type.join(" | "))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let (old_return, new_return) = (return_type(old), return_type(new));
+
+    FunctionChange {
+        title: new.title.clone(),
+        old_signature: old.signature(),
+        new_signature: new.signature(),
+        params: diff_params(&old.params, &new.params),
+        return_type: (old_return != new_return).then_some((old_return, new_return)),
+        deprecated: new.deprecated.clone().filter(|_| old.deprecated.is_none()),
+        description: old.description.trim() != new.description.trim(),
+    }
+}
+
+/// Compare two versions of the params of one function, matching them by name
+fn diff_params(old: &[Param], new: &[Param]) -> Vec<ParamChange> {
+    let name = |p: &Param| p.name.clone();
+    let find = |params: &'_ [Param], p: &Param| params.iter().find(|o| name(o) == name(p)).cloned();
+
+    let mut changes = Vec::new();
+
+    for p in new {
+        let Some(o) = find(old, p) else {
+            changes.push(ParamChange::Added {
+                name: name(p),
+                default: p.default.clone(),
+            });
+            continue;
+        };
+
+        // a param only documented on one side has no type there to compare
+        if o.types() != p.types() && !o.data_type.is_empty() && !p.data_type.is_empty() {
+            changes.push(ParamChange::Type {
+                name: name(p),
+                old: o.types(),
+                new: p.types(),
+            });
+        }
+        if o.default != p.default {
+            changes.push(ParamChange::Default {
+                name: name(p),
+                old: o.default.clone(),
+                new: p.default.clone(),
+            });
+        }
+    }
+
+    changes.extend(
+        old.iter()
+            .filter(|o| find(new, o).is_none())
+            .map(|o| ParamChange::Removed { name: name(o) }),
+    );
+
+    changes
+}
+
+impl ApiDiff {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Why the changes break callers, one reason per breaking change:
+    /// removed functions and params, new params without a default,
+    /// changed or removed defaults, types that are no longer taken and changed return types
+    /// A param that gains a default only becomes optional
+    pub fn breaking(&self) -> Vec<String> {
+        let mut reasons: Vec<String> = self
+            .removed
+            .iter()
+            .map(|f| format!("removed {}", f.signature))
+            .collect();
+
+        for change in &self.changed {
+            let title = &change.title;
+            for param in &change.params {
+                match param {
+                    ParamChange::Added {
+                        name,
+                        default: None,
+                    } => reasons.push(format!("new param {name} of {title} is required")),
+                    ParamChange::Removed { name } => {
+                        reasons.push(format!("removed param {name} of {title}"))
+                    }
+                    ParamChange::Type { name, old, new } => {
+                        let dropped: Vec<&str> = old
+                            .iter()
+                            .filter(|t| !new.contains(t))
+                            .map(String::as_str)
+                            .collect();
+                        if !dropped.is_empty() {
+                            reasons.push(format!(
+                                "param {name} of {title} no longer takes {}",
+                                dropped.join(" | ")
+                            ));
+                        }
+                    }
+                    ParamChange::Default {
+                        name,
+                        old: Some(old),
+                        new,
+                    } => reasons.push(format!(
+                        "default of param {name} of {title} {old} -> {}",
+                        or_none(new.as_deref().unwrap_or_default())
+                    )),
+                    ParamChange::Added { .. } | ParamChange::Default { old: None, .. } => {}
+                }
+            }
+            if let Some((old, new)) = &change.return_type {
+                reasons.push(format!(
+                    "return type of {title} {} -> {}",
+                    or_none(old),
+                    or_none(new)
+                ));
+            }
+        }
+
+        reasons
+    }
+
+    /// How far a release with these changes has to bump its version
+    pub fn severity(&self) -> Severity {
+        let additive = !self.added.is_empty()
+            || self
+                .changed
+                .iter()
+                .any(|c| !c.params.is_empty() || c.deprecated.is_some());
+
+        match (self.breaking().is_empty(), additive) {
+            (false, _) => Severity::Major,
+            (true, true) => Severity::Minor,
+            (true, false) => Severity::Patch,
+        }
+    }
+
+    /// A markdown changelog section titled `heading`, e.g. a version,
+    /// grouping added, changed, deprecated and removed functions
+    /// Changes to descriptions alone are left out
+    pub fn changelog(&self, heading: &str) -> String {
+        let mut out = format!("## {heading}\n");
+
+        let added: Vec<String> = self
+            .added
+            .iter()
+            .map(|f| match f.summary.is_empty() {
+                true => format!("- `{}`", f.signature),
+                false => format!("- `{}`: {}", f.signature, f.summary),
+            })
+            .collect();
+
+        let changed: Vec<String> = self
+            .changed
+            .iter()
+            .filter(|c| !c.params.is_empty() || c.return_type.is_some())
+            .map(|c| {
+                let mut entry = format!("- `{}`", c.title);
+                if c.old_signature != c.new_signature {
+                    entry += &format!(" is now `{}`", c.new_signature);
+                }
+                for param in &c.params {
+                    entry += &format!("\n  - {}", param.changelog());
+                }
+                if let Some((old, new)) = &c.return_type {
+                    entry += &format!("\n  - returns {} instead of {}", code(new), code(old));
+                }
+                entry
+            })
+            .collect();
+
+        let deprecated: Vec<String> = self
+            .changed
+            .iter()
+            .filter_map(|c| {
+                let message = c.deprecated.as_ref()?;
+                Some(match message.is_empty() {
+                    true => format!("- `{}`", c.title),
+                    false => format!("- `{}`: {message}", c.title),
+                })
+            })
+            .collect();
+
+        let removed: Vec<String> = self
+            .removed
+            .iter()
+            .map(|f| format!("- `{}`", f.signature))
+            .collect();
+
+        let groups = [
+            ("Added", added),
+            ("Changed", changed),
+            ("Deprecated", deprecated),
+            ("Removed", removed),
+        ];
+        if groups.iter().all(|(_, entries)| entries.is_empty()) {
+            out += "\nNo changes to the documented functions.\n";
+        }
+        for (title, entries) in groups.iter().filter(|(_, e)| !e.is_empty()) {
+            out += &format!("\n### {title}\n\n{}\n", entries.join("\n"));
+        }
+
+        out
+    }
+}
+
+/// A line per function, `+` for added, `-` for removed and `~` for changed,
+/// with an indented line per change
+impl Display for ApiDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no changes to the documented functions");
+        }
+
+        for function in &self.added {
+            writeln!(f, "+ {}", function.signature)?;
+        }
+        for function in &self.removed {
+            writeln!(f, "- {}", function.signature)?;
+        }
+        for change in &self.changed {
+            match change.old_signature == change.new_signature {
+                true => writeln!(f, "~ {}", change.new_signature)?,
+                false => writeln!(f, "~ {} -> {}", change.old_signature, change.new_signature)?,
+            }
+            for param in &change.params {
+                writeln!(f, "    {param}")?;
+            }
+            if let Some((old, new)) = &change.return_type {
+                writeln!(f, "    return type {} -> {}", or_none(old), or_none(new))?;
+            }
+            if let Some(message) = &change.deprecated {
+                match message.is_empty() {
+                    true => writeln!(f, "    deprecated")?,
+                    false => writeln!(f, "    deprecated: {message}")?,
+                }
+            }
+            if change.description {
+                writeln!(f, "    description changed")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ParamChange {
+    /// The change as an entry of a changelog
+    fn changelog(&self) -> String {
+        match self {
+            ParamChange::Added {
+                name,
+                default: Some(default),
+            } => format!("new param `{name}`, defaulting to `{default}`"),
+            ParamChange::Added {
+                name,
+                default: None,
+            } => format!("new param `{name}`"),
+            ParamChange::Removed { name } => format!("param `{name}` was removed"),
+            ParamChange::Type { name, old, new } => format!(
+                "`{name}` takes `{}` instead of `{}`",
+                new.join(" | "),
+                old.join(" | ")
+            ),
+            ParamChange::Default { name, old, new } => match (old, new) {
+                (Some(old), Some(new)) => {
+                    format!("`{name}` defaults to `{new}` instead of `{old}`")
+                }
+                (None, Some(new)) => format!("`{name}` is now optional, defaulting to `{new}`"),
+                (Some(_), None) => format!("`{name}` is now required"),
+                (None, None) => format!("`{name}` changed"),
+            },
+        }
+    }
+}
+
+impl Display for ParamChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamChange::Added { name, .. } => write!(f, "added param {name}"),
+            ParamChange::Removed { name } => write!(f, "removed param {name}"),
+            ParamChange::Type { name, old, new } => {
+                write!(
+                    f,
+                    "type of {name} {} -> {}",
+                    old.join(" | "),
+                    new.join(" | ")
+                )
+            }
+            ParamChange::Default { name, old, new } => write!(
+                f,
+                "default of {name} {} -> {}",
+                or_none(old.as_deref().unwrap_or_default()),
+                or_none(new.as_deref().unwrap_or_default())
+            ),
+        }
+    }
+}
+
+/// `text` as inline code, or "nothing" when it is empty
+fn code(text: &str) -> String {
+    match text {
+        "" => "nothing".to_string(),
+        text => format!("`{text}`"),
+    }
+}
+
+/// `text`, or "(none)" when it is empty, apart from typst's `none`
+fn or_none(text: &str) -> &str {
+    match text {
+        "" => "(none)",
+        text => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_block;
+
+    /// A doc comment from its lines
+    fn doc(block: &str) -> DocComment {
+        parse_block(block).unwrap()
+    }
+
+    #[test]
+    fn added_and_removed_params() {
+        let old = [doc(
+            "f: Do it\n@param a int the first\n@param b int the second\n",
+        )];
+        let new = [doc(
+            "f: Do it\n@param a int the first\n@param c int the third\n",
+        )];
+        let api = diff(&old, &new);
+
+        assert!(api.added.is_empty() && api.removed.is_empty());
+        assert_eq!(
+            api.changed[0].params,
+            [
+                ParamChange::Added {
+                    name: "c".to_string(),
+                    default: None
+                },
+                ParamChange::Removed {
+                    name: "b".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn added_and_removed_functions() {
+        let old = [
+            doc("f: Do it\n"),
+            doc("g: Do that\n@param x any the input\n"),
+        ];
+        let new = [doc("f: Do it\n"), doc("h: Do the other\n")];
+        let api = diff(&old, &new);
+
+        assert_eq!(api.added[0].title, "h");
+        assert_eq!(api.removed[0].signature, "g(x)");
+        assert!(api.changed.is_empty());
+    }
+
+    #[test]
+    fn same_docs_have_no_changes() {
+        let docs = [doc(
+            "f: Do it\n@param a int = 1 the first\n@return int the result\n",
+        )];
+
+        assert!(diff(&docs, &docs).is_empty());
+    }
+
+    #[test]
+    fn changelog_groups_changes() {
+        let old = [
+            doc("f: Do it\n@param a int the first\n"),
+            doc("g: Do that\n@param x any the input\n"),
+        ];
+        let new = [
+            doc(
+                "f: Do it\n@param a int the first\n@param b int = 2 the second\n@deprecated use h\n",
+            ),
+            doc("h: Do the other\n"),
+        ];
+
+        assert_eq!(
+            diff(&old, &new).changelog("1.1.0"),
+            "## 1.1.0\n\
+             \n### Added\n\n- `h()`: Do the other\n\
+             \n### Changed\n\n- `f` is now `f(a, b: 2)`\n  - new param `b`, defaulting to `2`\n\
+             \n### Deprecated\n\n- `f`: use h\n\
+             \n### Removed\n\n- `g(x)`\n"
+        );
+    }
+
+    #[test]
+    fn changelog_without_changes_says_so() {
+        let docs = [doc("f: Do it\n")];
+
+        assert_eq!(
+            diff(&docs, &docs).changelog("Unreleased"),
+            "## Unreleased\n\nNo changes to the documented functions.\n"
+        );
+    }
+
+    #[test]
+    fn bump_between_versions() {
+        assert_eq!(bump("1.2.3", "2.0.0"), Some(Severity::Major));
+        assert_eq!(bump("1.2.3", "1.3.0"), Some(Severity::Minor));
+        assert_eq!(bump("1.2.3", "1.2.4"), Some(Severity::Patch));
+        // before 1.0 everything moves down one
+        assert_eq!(bump("0.1.0", "0.2.0"), Some(Severity::Major));
+        assert_eq!(bump("0.1.0", "0.1.1"), Some(Severity::Minor));
+        assert_eq!(bump("1.2.3", "1.2.3"), None);
+        assert_eq!(bump("1.2.3", "1.0.0"), None);
+    }
+
+    #[test]
+    fn param_gaining_a_default_is_a_minor_change() {
+        let old = [doc("f: Do it\n@param a int the first\n")];
+        let new = [doc("f: Do it\n@param a int = 1 the first\n")];
+        let api = diff(&old, &new);
+
+        assert!(api.breaking().is_empty());
+        assert_eq!(api.severity(), Severity::Minor);
+    }
+
+    #[test]
+    fn required_param_added_is_a_major_change() {
+        let old = [doc("f: Do it\n@param a int the first\n")];
+        let optional = [doc(
+            "f: Do it\n@param a int the first\n@param b int = 2 the second\n",
+        )];
+        let required = [doc(
+            "f: Do it\n@param a int the first\n@param b int the second\n",
+        )];
+
+        assert_eq!(diff(&old, &optional).severity(), Severity::Minor);
+        assert_eq!(
+            diff(&old, &required).breaking(),
+            ["new param b of f is required"]
+        );
+        assert_eq!(diff(&old, &required).severity(), Severity::Major);
+    }
+
+    #[test]
+    fn removed_function_or_changed_default_is_a_major_change() {
+        let old = [
+            doc("f: Do it\n@param a int = 1 the first\n"),
+            doc("g: Do that\n"),
+        ];
+        let new = [doc("f: Do it\n@param a int = 2 the first\n")];
+
+        assert_eq!(
+            diff(&old, &new).breaking(),
+            ["removed g()", "default of param a of f 1 -> 2"]
+        );
+        assert_eq!(diff(&old, &new).severity(), Severity::Major);
+    }
+
+    #[test]
+    fn rewritten_description_is_a_patch() {
+        let old = [doc("f: Do it\n")];
+        let new = [doc("f: Do it now\n")];
+
+        assert_eq!(diff(&old, &new).severity(), Severity::Patch);
+    }
+}
@@ -0,0 +1,67 @@
+/// Names of the rules behind every warning about a doc comment, which `marker:allow` turns off
+pub const RULES: [&str; 14] = [
+    "duplicate-param",
+    "unknown-tag",
+    "not-a-tag",
+    "unknown-rule",
+    "unknown-param",
+    "missing-param",
+    "wrong-default",
+    "wrong-type",
+    "unknown-type",
+    "unknown-choice",
+    "unknown-see",
+    "future-since",
+    "unknown-inheritdoc",
+    "inheritdoc-cycle",
+];
+
+/// A `marker:` comment telling `marker` how to treat the code around it
+#[derive(Debug, PartialEq)]
+pub(crate) enum Directive {
+    /// Leave the doc comment out of the docs and every check
+    Ignore,
+    /// Leave the whole file out
+    IgnoreFile,
+    /// Turn off the rules of some warnings for the doc comment
+    Allow(Vec<String>),
+}
+
+/// The directive on a comment line, with or without its comment markers,
+/// e.g. "/// marker:allow missing-param, wrong-default"
+/// `None` for any other line
+pub(crate) fn directive(line: &str) -> Option<Directive> {
+    let comment_chars = |c: char| c.is_whitespace() || "/*#!-".contains(c);
+    let text = line.trim_matches(comment_chars).strip_prefix("marker:")?;
+
+    match text.split_once(char::is_whitespace) {
+        Some(("allow", rules)) => Some(Directive::Allow(
+            rules
+                .split([',', ' '])
+                .filter(|r| !r.is_empty())
+                .map(str::to_string)
+                .collect(),
+        )),
+        Some(_) => None,
+        None => match text {
+            "ignore" => Some(Directive::Ignore),
+            "ignore-file" => Some(Directive::IgnoreFile),
+            _ => None,
+        },
+    }
+}
+
+/// Whether the file `input` opts out of documentation with a `marker:ignore-file` comment,
+/// e.g. because it is generated
+pub fn ignores_file(input: &str) -> bool {
+    input
+        .lines()
+        .any(|line| directive(line) == Some(Directive::IgnoreFile))
+}
+
+/// Whether a doc comment block opts out of documentation with `marker:ignore`
+pub(crate) fn ignores_block(block: &str) -> bool {
+    block
+        .lines()
+        .any(|line| directive(line) == Some(Directive::Ignore))
+}
@@ -0,0 +1,556 @@
+use serde::Serialize;
+
+use crate::{Span, anchor, grammar::union};
+
+/// Structure for all `marker` doc comments in the form
+/// title: Description
+/// ...
+/// @param name type description ...
+/// @param name type description ...
+/// @return type description
+/// @example lang
+/// ...
+/// @deprecated message
+/// @since version, @version version, @author name
+/// @panics description
+/// @public or @internal
+/// @inheritdoc other_function
+/// @alias other_name, ...
+/// @module name, for the doc comment of a whole file
+/// @see other_function, ...
+/// @anything value, kept for custom tags
+/// Any prose after the first tag is kept as a note
+#[derive(Debug, Default, Serialize)]
+pub struct DocComment {
+    pub title: String,
+    /// Anchor of the title's heading, unique within its file
+    pub slug: String,
+    pub description: String,
+    pub params: Vec<Param>,
+    /// What the function gives back, one per @return
+    pub returns: Vec<Return>,
+    pub examples: Vec<Example>,
+    /// What makes the function panic, from @panics or @throws
+    pub errors: Vec<String>,
+    /// Titles of related functions, from @see
+    pub see: Vec<String>,
+    /// Tags that are not built in, rendered if they are registered in the config
+    pub tags: Vec<Tag>,
+    pub notes: Vec<String>,
+    /// Why the function should no longer be used, possibly empty
+    pub deprecated: Option<String>,
+    /// Version of the package the function was added in
+    pub since: Option<String>,
+    /// Version of the function itself
+    pub version: Option<String>,
+    pub authors: Vec<String>,
+    /// Whether the function is part of the package's API, from @public or @internal,
+    /// otherwise from whether its name starts with an underscore
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
+    /// Whether this documents the whole file rather than an item in it,
+    /// from //! comments at its top or @module
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub module: bool,
+    /// Other names the function goes by, like a shorthand or an old name, from @alias
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Function the params and return not documented here are taken from,
+    /// from @inheritdoc or @copydoc
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherit: Option<String>,
+    /// The `#let` the doc comment sits above, when there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub definition: Option<Definition>,
+    /// Where the header starts, relative to the block from `parse_block`
+    /// and to the file from `parse_document`
+    pub span: Span,
+    /// Rules of warnings turned off for this doc comment, from `marker:allow`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+}
+
+impl DocComment {
+    /// Create a DocComment with no params, return, examples or notes
+    pub fn new(title: impl Into<String>, description: impl Into<String>) -> DocComment {
+        let title = title.into();
+        DocComment {
+            slug: anchor(&title),
+            title,
+            description: description.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether warnings of `rule` are turned off for this doc comment
+    pub fn allows(&self, rule: &str) -> bool {
+        self.allow.iter().any(|r| r == rule)
+    }
+
+    /// Add a parameter to the end of the parameter list
+    pub fn with_param(mut self, param: Param) -> DocComment {
+        self.params.push(param);
+        self
+    }
+
+    /// Add a return to the function, one of several when it returns different things
+    pub fn with_return(mut self, ret: Return) -> DocComment {
+        self.returns.push(ret);
+        self
+    }
+
+    /// Add an example to the end of the example list
+    pub fn with_example(mut self, example: Example) -> DocComment {
+        self.examples.push(example);
+        self
+    }
+
+    /// Add a failure to the end of the error list
+    pub fn with_error(mut self, error: impl Into<String>) -> DocComment {
+        self.errors.push(error.into());
+        self
+    }
+
+    /// Add a related function to the end of the see also list
+    pub fn with_see(mut self, title: impl Into<String>) -> DocComment {
+        self.see.push(title.into());
+        self
+    }
+
+    /// Add a custom tag to the end of the tag list
+    pub fn with_tag(mut self, tag: Tag) -> DocComment {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Values of every custom tag called `name`
+    pub fn tag_values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.tags
+            .iter()
+            .filter(move |t| t.name == name)
+            .map(|t| t.value.as_str())
+    }
+
+    /// Whether the function is internal to its package, by @internal,
+    /// or by convention when its name starts with an underscore like `_num_to_fr_units`
+    pub fn is_private(&self) -> bool {
+        match self.visibility {
+            Some(visibility) => visibility == Visibility::Private,
+            None => Visibility::of(&self.title) == Visibility::Private,
+        }
+    }
+
+    /// The category the function is listed under, from @category or @group
+    pub fn category(&self) -> Option<&str> {
+        self.tag_values("category")
+            .chain(self.tag_values("group"))
+            .find(|c| !c.is_empty())
+    }
+
+    /// Add a note to the end of the note list
+    pub fn with_note(mut self, note: impl Into<String>) -> DocComment {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Mark the function as deprecated, with a message saying what to use instead
+    pub fn with_deprecated(mut self, message: impl Into<String>) -> DocComment {
+        self.deprecated = Some(message.into());
+        self
+    }
+
+    /// Set the version the function was added in
+    pub fn with_since(mut self, since: impl Into<String>) -> DocComment {
+        self.since = Some(since.into());
+        self
+    }
+
+    /// Set the version of the function
+    pub fn with_version(mut self, version: impl Into<String>) -> DocComment {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Add an author to the end of the author list
+    pub fn with_author(mut self, author: impl Into<String>) -> DocComment {
+        self.authors.push(author.into());
+        self
+    }
+
+    /// The first line of the description, for listings like the man page NAME
+    pub fn summary(&self) -> &str {
+        self.description.lines().next().unwrap_or_default().trim()
+    }
+
+    /// The @since, @version and @author tags as a single line
+    /// e.g. "Since 1.2 · Version 2 · By Reese, Sam", None if there are none
+    pub fn metadata(&self) -> Option<String> {
+        let mut parts = Vec::new();
+
+        if let Some(since) = &self.since {
+            parts.push(format!("Since {since}"));
+        }
+        if let Some(version) = &self.version {
+            parts.push(format!("Version {version}"));
+        }
+        if !self.authors.is_empty() {
+            parts.push(format!("By {}", self.authors.join(", ")));
+        }
+
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+
+    /// Merge in the `#let` the doc comment documents
+    /// Params are put in the order of the definition, and undocumented ones are added
+    /// with no description, and no type unless the definition has one
+    /// Documented params missing from the definition are kept at the end
+    pub fn with_definition(mut self, definition: Definition) -> DocComment {
+        let mut documented = std::mem::take(&mut self.params);
+
+        for p in &definition.params {
+            let position = documented.iter().position(|d| d.name == p.name);
+
+            let param = match position {
+                Some(i) => {
+                    // `@param answers` may document `..answers`
+                    let mut d = documented.remove(i);
+                    d.variadic |= p.variadic;
+                    // the definition knows best whether it has a default
+                    d.named = p.named;
+                    d.default = d.default.or_else(|| p.default.clone());
+                    if d.data_type.is_empty() {
+                        d.data_type = p.data_type.clone();
+                    }
+                    d
+                }
+                None => Param {
+                    name: p.name.clone(),
+                    data_type: p.data_type.clone(),
+                    default: p.default.clone(),
+                    named: p.named,
+                    variadic: p.variadic,
+                    ..Default::default()
+                },
+            };
+            self.params.push(param);
+        }
+
+        self.params.extend(documented);
+        self.visibility
+            .get_or_insert(Visibility::of(&definition.name));
+        self.definition = Some(definition);
+        self
+    }
+
+    /// Take the params and return `source` documents that this doc comment doesn't
+    /// Its own @param and @return come first, so they override or add to the inherited ones
+    /// Without a definition, params only `source` documents are added after its own
+    pub fn inherit_from(&mut self, source: &DocComment) {
+        let same = |a: &Param, b: &Param| a.name == b.name;
+
+        for param in self.params.iter_mut() {
+            let Some(inherited) = source.params.iter().find(|p| same(p, param)) else {
+                continue;
+            };
+            if param.description.is_empty() {
+                param.description = inherited.description.clone();
+            }
+            if param.data_type.is_empty() {
+                param.data_type = inherited.data_type.clone();
+                param.choices = inherited.choices.clone();
+            }
+            if param.default.is_none() && self.definition.is_none() {
+                param.default = inherited.default.clone();
+                param.named |= inherited.named;
+            }
+            // an inherited param is documented by the @inheritdoc
+            param.span.get_or_insert(self.span);
+        }
+
+        if self.definition.is_none() {
+            let missing: Vec<Param> = source
+                .params
+                .iter()
+                .filter(|p| !self.params.iter().any(|own| same(own, p)))
+                .cloned()
+                .collect();
+            self.params.extend(missing);
+        }
+
+        if self.returns.is_empty() {
+            self.returns = source.returns.clone();
+        }
+    }
+
+    /// What the doc comment documents, a function when there is no definition
+    pub fn kind(&self) -> Kind {
+        self.definition.as_ref().map_or(Kind::Function, |d| d.kind)
+    }
+
+    /// Reconstruct the function signature from the parsed params
+    /// e.g. "question(body, points: 1)", "indents = (1fr, 10fr)" for a constant,
+    /// or the rule itself for a show or set rule
+    pub fn signature(&self) -> String {
+        if let Some(value) = self.definition.as_ref().and_then(|d| d.value.as_ref()) {
+            return match self.kind() {
+                Kind::ShowRule | Kind::SetRule => value.clone(),
+                _ => format!("{} = {value}", self.title),
+            };
+        }
+
+        let args: Vec<String> = self
+            .params
+            .iter()
+            .map(|p| match &p.default {
+                Some(def) => format!("{}: {}", p.source_name(), def),
+                None => p.source_name(),
+            })
+            .collect();
+
+        format!("{}({})", self.title, args.join(", "))
+    }
+}
+
+/// Structure for a tag that is not built in, e.g. "@difficulty hard"
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Tag {
+    /// Name without the @, lowercase
+    pub name: String,
+    pub value: String,
+}
+
+impl Tag {
+    /// Create a Tag, lowercasing its name
+    pub fn new(name: &str, value: impl Into<String>) -> Tag {
+        Tag {
+            name: name.to_lowercase(),
+            value: value.into(),
+        }
+    }
+}
+
+/// Structure for the `#let name(args) = ...` line following a doc comment,
+/// or the python `def` a docstring belongs to
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Definition {
+    pub name: String,
+    /// Only the name and default of these are known,
+    /// and the type for annotated python
+    pub params: Vec<Param>,
+    /// What a `#let name = value` binding that isn't a function is set to,
+    /// or the whole of a show or set rule, joined into a single line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub kind: Kind,
+}
+
+/// What a definition is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kind {
+    #[default]
+    Function,
+    Constant,
+    /// A `state(...)` shared across the document
+    State,
+    /// A `counter(...)`, like the total points of an exam
+    Counter,
+    ShowRule,
+    SetRule,
+}
+
+impl Kind {
+    /// Every kind, in the order they are rendered in
+    pub const ALL: [Kind; 6] = [
+        Kind::Function,
+        Kind::Constant,
+        Kind::State,
+        Kind::Counter,
+        Kind::ShowRule,
+        Kind::SetRule,
+    ];
+
+    /// The kind of a binding set to `value`, e.g. a state for `state("title", none)`
+    pub fn of_value(value: &str) -> Kind {
+        match value {
+            v if v.starts_with("state(") => Kind::State,
+            v if v.starts_with("counter(") => Kind::Counter,
+            _ => Kind::Constant,
+        }
+    }
+}
+
+/// Whether a function is part of the API of its package
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    Public,
+    Private,
+}
+
+impl Visibility {
+    /// The visibility of a function called `name` by convention,
+    /// private when it starts with an underscore
+    pub fn of(name: &str) -> Visibility {
+        match name.starts_with('_') {
+            true => Visibility::Private,
+            false => Visibility::Public,
+        }
+    }
+}
+
+/// Structure for an @return piece of a doc comment
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Return {
+    /// Name of the field it is, from "@returns name: type"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The types it may be, more than one for a union
+    pub data_type: Vec<String>,
+    pub description: String,
+    /// Where the @return tag is, like the span of its doc comment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+impl Return {
+    /// Create a Return of `data_type` with an empty description
+    /// `data_type` may be a single type or a union in the form "[a | b]"
+    pub fn new(data_type: &str) -> Return {
+        Return {
+            data_type: union(data_type),
+            ..Default::default()
+        }
+    }
+
+    /// Name the field of the result the return describes
+    pub fn with_name(mut self, name: impl Into<String>) -> Return {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the description of the return
+    pub fn with_description(mut self, description: impl Into<String>) -> Return {
+        self.description = description.into();
+        self
+    }
+}
+
+/// Structure for an @example piece of a doc comment
+/// Every line after the tag up until the next tag is kept verbatim,
+/// less the indentation shared by all of them
+#[derive(Debug, Serialize)]
+pub struct Example {
+    pub language: String,
+    pub code: String,
+    /// Path of the compiled output of the example, when it was compiled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
+impl Default for Example {
+    /// An empty typst example
+    fn default() -> Example {
+        Example {
+            language: "typst".to_string(),
+            code: String::new(),
+            image: None,
+        }
+    }
+}
+
+impl Example {
+    /// Set the language used for the code fence
+    pub fn with_language(mut self, language: impl Into<String>) -> Example {
+        self.language = language.into();
+        self
+    }
+}
+
+/// Structure for an @param piece of a doc comment
+/// Params only found in the definition have no span, and no type unless it is annotated python
+/// or defaults to a typst literal
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Param {
+    pub name: String,
+    pub data_type: Vec<String>,
+    /// The string literals it may be, from a type like ["left" | "right"],
+    /// which stand in its `data_type` as a single str
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+    pub default: Option<String>,
+    pub description: String,
+    /// Whether it is passed by name, like a typst argument with a default
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub named: bool,
+    /// Whether it takes the rest of the arguments, written `..name`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub variadic: bool,
+    /// Where the @param tag is, like the span of its doc comment,
+    /// `None` for params only found in the definition
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+impl Param {
+    /// Create a Param with no default and an empty description
+    /// `data_type` may be a single type or a union in the form "[a | b]"
+    pub fn new(name: impl Into<String>, data_type: &str) -> Param {
+        Param {
+            name: name.into(),
+            data_type: union(data_type),
+            ..Default::default()
+        }
+    }
+
+    /// Whether it must be passed, having no default and not taking the rest of the arguments
+    pub fn is_required(&self) -> bool {
+        self.default.is_none() && !self.variadic
+    }
+
+    /// Its types as documented, with the choices in place of the str they stand for
+    pub fn types(&self) -> Vec<String> {
+        self.data_type
+            .iter()
+            .flat_map(|t| match t == "str" && !self.choices.is_empty() {
+                true => self.choices.clone(),
+                false => vec![t.clone()],
+            })
+            .collect()
+    }
+
+    /// Whether `value` may be passed, false only for a str literal that isn't one of the choices
+    pub fn is_choice(&self, value: &str) -> bool {
+        self.choices.is_empty()
+            || !value.trim_start().starts_with('"')
+            || self.choices.iter().any(|c| c == value.trim())
+    }
+
+    /// Name as rendered in docs, with "..." before it if it is variadic
+    pub fn label(&self) -> String {
+        match self.variadic {
+            true => format!("...{}", self.name),
+            false => self.name.clone(),
+        }
+    }
+
+    /// Name as written in the source, with ".." before it if it is variadic
+    pub fn source_name(&self) -> String {
+        match self.variadic {
+            true => format!("..{}", self.name),
+            false => self.name.clone(),
+        }
+    }
+
+    /// Set the default argument of the parameter
+    pub fn with_default(mut self, default: impl Into<String>) -> Param {
+        self.default = Some(default.into());
+        self
+    }
+
+    /// Set the description of the parameter
+    pub fn with_description(mut self, description: impl Into<String>) -> Param {
+        self.description = description.into();
+        self
+    }
+}
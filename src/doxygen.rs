@@ -0,0 +1,546 @@
+use regex::{Captures, Regex};
+
+use crate::{
+    Definition, DocComment, Example, Language, Param, Return, Span, Tag,
+    markdown::slugs,
+    parse::{block_gutter, dedent, gutter, offset},
+    rust::{find_top, split_top},
+    warn,
+};
+
+/// Words before a function's return type that aren't part of it
+const SPECIFIERS: [&str; 7] = [
+    "static",
+    "inline",
+    "extern",
+    "virtual",
+    "constexpr",
+    "explicit",
+    "friend",
+];
+
+/// Parses a C or C++ file into a DocComment per function with a Doxygen comment,
+/// a `/** */`, `/*! */`, `///` or `//!` comment whose commands start with `@` or `\`
+/// The title is the name of the function, the description is the text before the
+/// first command other than `@brief`, and types are taken from the declaration
+pub fn parse_doxygen(input: &str) -> Vec<DocComment> {
+    parse(input, &Language::c())
+}
+
+/// Parses a file of a language with the `Doxygen` syntax
+pub(crate) fn parse(input: &str, language: &Language) -> Vec<DocComment> {
+    let mut docs: Vec<DocComment> = comments(input, language)
+        .into_iter()
+        .map(|(declaration, lines)| parse_comment(&declaration, &lines))
+        .collect();
+
+    // overloads share a name
+    let slugs = slugs(docs.iter().map(|d| d.title.as_str()));
+    docs.iter_mut()
+        .zip(slugs)
+        .for_each(|(doc, slug)| doc.slug = slug);
+
+    docs
+}
+
+/// Titles of every function with a Doxygen comment in a C or C++ file
+pub fn doxygen_titles(input: &str) -> Vec<String> {
+    titles(input, &Language::c())
+}
+
+/// Titles of every documented function in a file of a language with the `Doxygen` syntax
+pub(crate) fn titles(input: &str, language: &Language) -> Vec<String> {
+    comments(input, language)
+        .into_iter()
+        .map(|(declaration, _)| declaration.name)
+        .collect()
+}
+
+/// A function declaration, split around its name
+struct Declaration {
+    name: String,
+    /// Empty for constructors and destructors
+    return_type: String,
+    /// Everything between the parentheses
+    args: String,
+}
+
+/// The lines of a comment with the comment markers stripped, each with where it starts in the file
+type DocLines<'a> = Vec<(&'a str, Span)>;
+
+/// Every Doxygen comment in a file followed by a function declaration, with the declaration
+fn comments<'a>(input: &'a str, language: &Language) -> Vec<(Declaration, DocLines<'a>)> {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut found = Vec::new();
+    let mut block = Vec::new();
+    let mut in_block = None;
+
+    for (number, line) in lines.iter().enumerate() {
+        let rest = match comment_line(line, language, &mut in_block) {
+            Some((content, closes)) => {
+                let origin = Span {
+                    line: number + 1,
+                    column: line[..offset(line, content)].chars().count() + 1,
+                };
+                block.push((content, origin));
+                if !closes {
+                    continue;
+                }
+                &lines[number + 1..]
+            }
+            None if !block.is_empty() => &lines[number..],
+            None => continue,
+        };
+
+        // the comment is over, and documents the declaration after it
+        let doc_lines = std::mem::take(&mut block);
+        if let Some(declaration) = declaration(rest) {
+            found.push((declaration, doc_lines));
+        }
+    }
+
+    found
+}
+
+/// What a line adds to a comment with its markers stripped, and whether it ends a
+/// /** */ block, `None` if it isn't part of a comment
+/// `in_block` is the index of the block comment the scan is inside
+fn comment_line<'a>(
+    line: &'a str,
+    language: &Language,
+    in_block: &mut Option<usize>,
+) -> Option<(&'a str, bool)> {
+    let trimmed = line.trim();
+
+    if let Some((_, close)) = in_block.map(|i| &language.block_comments[i]) {
+        let (inside, closes) = match line.split_once(close.as_str()) {
+            Some((inside, _)) => (inside, true),
+            None => (line, false),
+        };
+        // the * gutter is optional
+        let inside = match inside.trim_start().strip_prefix(block_gutter(close)) {
+            Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+            None => inside,
+        };
+        if closes {
+            *in_block = None;
+        }
+        return Some((inside.trim_end(), closes));
+    }
+
+    let opened = language
+        .block_comments
+        .iter()
+        .enumerate()
+        .find_map(|(i, (open, close))| {
+            Some((i, open, close, trimmed.strip_prefix(open.as_str())?))
+        });
+    if let Some((i, open, close, rest)) = opened {
+        // /*** is a separator, and /**/ an empty comment
+        if rest.starts_with(gutter(open)) || rest.starts_with('/') {
+            return None;
+        }
+        let (inside, closes) = match rest.split_once(close.as_str()) {
+            Some((inside, _)) => (inside, true),
+            None => (rest, false),
+        };
+        *in_block = (!closes).then_some(i);
+        return Some((inside.trim(), closes));
+    }
+
+    // ///< documents the member before it, and //// is a plain comment
+    let (prefix, rest) = language
+        .line_comments
+        .iter()
+        .find_map(|p| Some((p, trimmed.strip_prefix(p.as_str())?)))?;
+    if rest.starts_with(['<', gutter(prefix)]) {
+        return None;
+    }
+    Some((rest.strip_prefix(' ').unwrap_or(rest).trim_end(), false))
+}
+
+/// The function declared at the start of `lines`,
+/// `None` if they don't start with one, e.g. a struct or a macro
+fn declaration(lines: &[&str]) -> Option<Declaration> {
+    let mut text = String::new();
+
+    for line in lines.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        if line.starts_with('#') {
+            return None;
+        }
+        // template <typename T> comes before the function it declares
+        if text.is_empty() && line.starts_with("template") {
+            continue;
+        }
+        text.push(' ');
+        text.push_str(line);
+        if find_top(&text, '{').is_some() || find_top(&text, ';').is_some() {
+            break;
+        }
+    }
+
+    let open = text.find('(')?;
+    let before = text[..open].trim_end();
+    // `int x = f(1);` is a variable, not a function
+    if before.contains('=') || before.ends_with("operator") {
+        return None;
+    }
+
+    let name_start = before
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':' || c == '~'))
+        .map_or(0, |i| i + 1);
+    let name = &before[name_start..];
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let return_type = before[..name_start]
+        .split_whitespace()
+        .filter(|w| !SPECIFIERS.contains(w))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let close = find_top(&text[open + 1..], ')')? + open + 1;
+
+    let declaration = Declaration {
+        // methods defined outside their class are documented by their own name
+        name: name.rsplit("::").next().unwrap_or(name).to_string(),
+        return_type,
+        args: text[open + 1..close].to_string(),
+    };
+    Some(declaration)
+}
+
+/// What the line after a command continues
+#[derive(Clone, Copy, PartialEq)]
+enum Continues {
+    Nothing,
+    Description,
+    Param,
+    Return,
+    Error,
+    Note,
+    Tag,
+}
+
+/// Parses the lines of a comment into a DocComment for the function it declares
+fn parse_comment(declaration: &Declaration, lines: &[(&str, Span)]) -> DocComment {
+    let mut doc = DocComment::new(&declaration.name, "");
+    doc.span = lines
+        .iter()
+        .find(|(l, _)| !l.trim().is_empty())
+        .map_or(Span::default(), |(_, span)| *span);
+
+    let mut continues = Continues::Description;
+    let mut example: Option<Example> = None;
+    let mut seen_command = false;
+
+    for &(line, span) in lines {
+        let trimmed = line.trim();
+
+        if let Some(code) = &mut example {
+            if command(trimmed).is_some_and(|(name, _)| name == "endcode") {
+                doc = doc.with_example(example.take().unwrap());
+            } else {
+                code.code.push_str(line);
+                code.code.push('\n');
+            }
+            continue;
+        }
+
+        let column = span.column + offset(line, trimmed);
+        let text = inline(trimmed);
+        let trimmed = text.as_str();
+
+        let Some((name, rest)) = command(trimmed) else {
+            // a blank line ends a command, and prose after one is a note
+            if trimmed.is_empty() {
+                if continues != Continues::Description || seen_command {
+                    continues = Continues::Nothing;
+                }
+                continue;
+            }
+            if continues == Continues::Nothing && seen_command {
+                doc = doc.with_note(trimmed);
+                continues = Continues::Note;
+                continue;
+            }
+            if continues == Continues::Nothing {
+                continues = Continues::Description;
+            }
+            doc = append(doc, continues, trimmed);
+            continue;
+        };
+
+        let span = Span { column, ..span };
+        continues = Continues::Nothing;
+        if name != "brief" && name != "short" {
+            seen_command = true;
+        }
+
+        match name {
+            "brief" | "short" => {
+                doc = append(doc, Continues::Description, rest);
+                continues = Continues::Description;
+            }
+            "param" => {
+                let mut words = rest.splitn(2, char::is_whitespace);
+                let Some(param) = words.next().filter(|n| !n.is_empty()) else {
+                    warn(format!(
+                        "{} has a \\param without a name, skipping it",
+                        doc.title
+                    ));
+                    continue;
+                };
+                // several params may share a description, e.g. "\param x,y coordinates"
+                for param in param.split(',') {
+                    if doc.params.iter().any(|existing| existing.name == param) {
+                        warn(format!(
+                            "{} documents \\param {param} more than once, keeping the first",
+                            doc.title
+                        ));
+                        continue;
+                    }
+                    doc = doc.with_param(Param {
+                        name: param.to_string(),
+                        description: words.clone().next().unwrap_or_default().trim().to_string(),
+                        span: Some(span),
+                        ..Default::default()
+                    });
+                }
+                continues = Continues::Param;
+            }
+            "return" | "returns" | "result" | "retval" => {
+                // \retval lists one value the function may return
+                let text = match name {
+                    "retval" => match rest.split_once(char::is_whitespace) {
+                        Some((value, desc)) => format!("`{value}` {}", desc.trim()),
+                        None => format!("`{rest}`"),
+                    },
+                    _ => rest.to_string(),
+                };
+                doc = match doc.returns.last_mut() {
+                    Some(ret) => {
+                        ret.description = [ret.description.as_str(), &text].join("; ");
+                        doc
+                    }
+                    None => {
+                        let mut ret = Return::new(&declaration.return_type).with_description(text);
+                        ret.span = Some(span);
+                        doc.with_return(ret)
+                    }
+                };
+                continues = Continues::Return;
+            }
+            "throw" | "throws" | "exception" if !rest.is_empty() => {
+                doc = doc.with_error(rest);
+                continues = Continues::Error;
+            }
+            "see" | "sa" => {
+                for title in rest.split([',', ' ']).filter(|t| !t.is_empty()) {
+                    doc = doc.with_see(title.trim_end_matches("()"));
+                }
+            }
+            "deprecated" => doc = doc.with_deprecated(rest),
+            "note" | "warning" | "attention" | "remark" | "remarks" if !rest.is_empty() => {
+                doc = doc.with_note(rest);
+                continues = Continues::Note;
+            }
+            "since" if !rest.is_empty() => doc = doc.with_since(rest),
+            "version" if !rest.is_empty() => doc = doc.with_version(rest),
+            "author" | "authors" if !rest.is_empty() => doc = doc.with_author(rest),
+            // the language may follow in braces, e.g. \code{.cpp}
+            "code" => {
+                let language = rest
+                    .strip_prefix("{.")
+                    .and_then(|l| l.strip_suffix('}'))
+                    .unwrap_or("c");
+                example = Some(Example::default().with_language(language));
+            }
+            "tparam" | "endcode" | "throw" | "throws" | "exception" | "note" | "warning"
+            | "attention" | "remark" | "remarks" | "since" | "version" | "author" | "authors" => {}
+            name => {
+                doc = doc.with_tag(Tag::new(name, rest));
+                continues = Continues::Tag;
+            }
+        }
+    }
+    // an unclosed \code runs to the end of the comment
+    if let Some(example) = example {
+        doc = doc.with_example(example);
+    }
+
+    doc.examples
+        .iter_mut()
+        .for_each(|e| e.code = dedent(&e.code));
+
+    doc.with_definition(definition(declaration))
+}
+
+/// A command at the start of a line, as its name and the rest of the line,
+/// e.g. "\param[in] x the x" -> ("param", "x the x")
+fn command(line: &str) -> Option<(&str, &str)> {
+    let line = line.strip_prefix(['@', '\\'])?;
+    let end = line
+        .find(|c: char| !c.is_alphanumeric())
+        .unwrap_or(line.len());
+    if end == 0 {
+        return None;
+    }
+
+    let (name, rest) = line.split_at(end);
+    // the direction of a param isn't documented, e.g. [in,out]
+    let rest = match rest.strip_prefix('[') {
+        Some(direction) => direction.split_once(']').map_or(rest, |(_, rest)| rest),
+        None => rest,
+    };
+    Some((name, rest.trim()))
+}
+
+/// Replace the inline commands of `text` with markdown,
+/// e.g. "the sum of @p a" -> "the sum of `a`"
+fn inline(text: &str) -> String {
+    let inline_re =
+        Regex::new(r"(?P<pre>^|\s)[@\\](?P<cmd>p|c|a|e|em|b)\s+(?P<word>[^\s,.;:]+)").unwrap();
+
+    inline_re
+        .replace_all(text, |caps: &Captures| {
+            let word = &caps["word"];
+            let word = match &caps["cmd"] {
+                "p" | "c" => format!("`{word}`"),
+                "b" => format!("**{word}**"),
+                _ => format!("*{word}*"),
+            };
+            format!("{}{word}", &caps["pre"])
+        })
+        .into_owned()
+}
+
+/// Add `text` to the end of the part of `doc` a line continues
+fn append(mut doc: DocComment, continues: Continues, text: &str) -> DocComment {
+    let target = match continues {
+        Continues::Nothing => None,
+        Continues::Description => Some(&mut doc.description),
+        Continues::Param => doc.params.last_mut().map(|p| &mut p.description),
+        Continues::Return => doc.returns.last_mut().map(|r| &mut r.description),
+        Continues::Error => doc.errors.last_mut(),
+        Continues::Note => doc.notes.last_mut(),
+        Continues::Tag => doc.tags.last_mut().map(|t| &mut t.value),
+    };
+
+    if let Some(target) = target {
+        if !target.is_empty() && !text.is_empty() {
+            target.push(' ');
+        }
+        target.push_str(text);
+    }
+    doc
+}
+
+/// The definition of a declared function, with the types and defaults of its arguments
+fn definition(declaration: &Declaration) -> Definition {
+    let params = split_top(&declaration.args)
+        .into_iter()
+        .map(str::trim)
+        .filter(|a| *a != "void")
+        .filter_map(|a| {
+            let (a, default) = match find_top(a, '=') {
+                Some(eq) => (a[..eq].trim(), Some(a[eq + 1..].trim().to_string())),
+                None => (a, None),
+            };
+            let (name, data_type) = argument(a)?;
+            Some(Param {
+                name: name.to_string(),
+                data_type: vec![data_type]
+                    .into_iter()
+                    .filter(|t| !t.is_empty())
+                    .collect(),
+                default,
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    Definition {
+        name: declaration.name.clone(),
+        params,
+        ..Default::default()
+    }
+}
+
+/// The name and type of a declared argument, e.g. "const char *name" -> ("name", "const char *")
+/// `None` for arguments without a name, e.g. "int"
+fn argument(arg: &str) -> Option<(&str, String)> {
+    if arg == "..." {
+        return Some((arg, String::new()));
+    }
+
+    // a function pointer is named inside its parentheses, e.g. "int (*callback)(int)"
+    if let Some((_, after)) = arg.split_once("(*") {
+        let name = after.split(')').next()?.trim();
+        return Some((name, arg.replacen(name, "", 1).replace("( *)", "(*)")));
+    }
+
+    // arrays keep their size after the name, e.g. "int values[4]"
+    let (arg, array) = match arg.find('[') {
+        Some(i) => (arg[..i].trim_end(), &arg[i..]),
+        None => (arg, ""),
+    };
+    let start = arg
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .map_or(0, |i| i + 1);
+    let name = &arg[start..];
+    let data_type = arg[..start].trim();
+    if name.is_empty() || data_type.is_empty() {
+        return None;
+    }
+
+    Some((name, format!("{data_type}{array}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comment_with_params_and_return() {
+        let docs = parse_doxygen(
+            "/**\n * @brief Add two numbers\n * @param a the first\n * @param b the second\n * @return the sum\n */\nint add(int a, int b);\n",
+        );
+
+        assert_eq!(docs[0].title, "add");
+        assert_eq!(docs[0].description, "Add two numbers");
+        assert_eq!(docs[0].params.len(), 2);
+        assert_eq!(docs[0].params[0].description, "the first");
+        // types come from the declaration
+        assert_eq!(docs[0].params[1].data_type, ["int"]);
+        assert_eq!(docs[0].returns[0].data_type, ["int"]);
+        assert_eq!(docs[0].returns[0].description, "the sum");
+    }
+
+    #[test]
+    fn backslash_commands_in_line_comments() {
+        let docs = parse_doxygen(
+            "/// \\brief Scale a value\n/// \\param x the value\ndouble scale(double x);\n",
+        );
+
+        assert_eq!(docs[0].description, "Scale a value");
+        assert_eq!(docs[0].params[0].name, "x");
+        assert_eq!(docs[0].params[0].description, "the value");
+    }
+
+    #[test]
+    fn comment_without_a_description() {
+        let docs = parse_doxygen("/**\n * @param a the value\n */\nvoid bare(int a);\n");
+
+        assert_eq!(docs[0].title, "bare");
+        assert_eq!(docs[0].description, "");
+        assert_eq!(docs[0].params[0].description, "the value");
+    }
+
+    #[test]
+    fn comment_without_a_declaration_is_skipped() {
+        let docs = parse_doxygen("/** A loose comment */\nstruct s;\n");
+
+        assert!(docs.is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+use std::{fmt, io};
+
+use serde::Serialize;
+
+/// A position in a typst file, both 1 based
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Everything that can go wrong while documenting a typst file
+#[derive(Debug)]
+pub enum Error {
+    /// The header of a doc comment is missing the ": " between title and description
+    MissingSeparator { header: String, span: Span },
+    /// A tag is missing one of its required pieces, e.g. "@param" with no type
+    MalformedTag {
+        tag: &'static str,
+        missing: &'static str,
+        span: Span,
+    },
+    /// A python docstring has no closing quotes
+    UnclosedDocstring { span: Span },
+    /// A file could not be read or written
+    Io { path: String, source: io::Error },
+}
+
+impl Error {
+    /// Where in the source the error is
+    /// From `parse_block` this is relative to the block, from `parse_document` it is relative to the file
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Error::MissingSeparator { span, .. }
+            | Error::MalformedTag { span, .. }
+            | Error::UnclosedDocstring { span } => Some(*span),
+            Error::Io { .. } => None,
+        }
+    }
+
+    /// Mutable access to the span, so it can be moved from a block into its file
+    pub(crate) fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            Error::MissingSeparator { span, .. }
+            | Error::MalformedTag { span, .. }
+            | Error::UnclosedDocstring { span } => Some(span),
+            Error::Io { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingSeparator { header, .. } => write!(
+                f,
+                "could not parse doc header `{header}`, ensure it follows the `title: description` format"
+            ),
+            Error::MalformedTag { tag, missing, .. } => write!(f, "@{tag} missing {missing}"),
+            Error::UnclosedDocstring { .. } => write!(f, "docstring is never closed"),
+            Error::Io { path, source } => write!(f, "{path}: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Result type used throughout `marker`
+pub type Result<T> = std::result::Result<T, Error>;
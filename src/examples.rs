@@ -0,0 +1,88 @@
+use std::{fs, path::Path, process::Command};
+
+use marker_typ::DocComment;
+
+/// Set before every example so the image is cropped to its content
+const PREAMBLE: &str = "#set page(width: auto, height: auto, margin: 8pt)\n";
+
+/// Compile every typst example of `docs` to an image in `dir` with the `typst` CLI,
+/// and attach the image to the example
+/// Examples import everything from `file`, so they can call the documented functions
+/// Returns whether any example failed to compile
+pub fn compile_examples(file: &str, docs: &mut [DocComment], dir: &str, format: &str) -> bool {
+    let mut failed = false;
+
+    if let Err(e) = fs::create_dir_all(dir) {
+        eprintln!("error: {dir}: {e}");
+        return true;
+    }
+
+    // typst only imports from inside its root, so the root is the whole filesystem
+    let source = match fs::canonicalize(file) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("error: {file}: {e}");
+            return true;
+        }
+    };
+    let stem = Path::new(file)
+        .file_stem()
+        .map_or(String::new(), |s| s.to_string_lossy().to_string());
+
+    for doc in docs.iter_mut() {
+        let typst_examples = doc
+            .examples
+            .iter_mut()
+            .filter(|e| e.language == "typst" || e.language == "typ");
+
+        for (i, example) in typst_examples.enumerate() {
+            let name = format!("{stem}-{}-{}", doc.title, i + 1);
+            let input = Path::new(dir).join(format!(".{name}.typ"));
+            let output = Path::new(dir).join(format!("{name}.{format}"));
+
+            let wrapped = format!(
+                "{PREAMBLE}#import \"{}\": *\n{}",
+                source.display(),
+                example.code
+            );
+
+            if let Err(e) = fs::write(&input, wrapped) {
+                eprintln!("error: {}: {e}", input.display());
+                failed = true;
+                continue;
+            }
+
+            let compiled = Command::new("typst")
+                .arg("compile")
+                .arg("--root")
+                .arg("/")
+                .arg(&input)
+                .arg(&output)
+                .output();
+
+            let _ = fs::remove_file(&input);
+
+            match compiled {
+                Ok(result) if result.status.success() => {
+                    example.image = Some(output.display().to_string());
+                }
+                Ok(result) => {
+                    eprintln!(
+                        "warning: {file}: example {} of {} did not compile\n{}",
+                        i + 1,
+                        doc.title,
+                        String::from_utf8_lossy(&result.stderr).trim_end()
+                    );
+                    failed = true;
+                }
+                // without typst none of the examples can compile
+                Err(e) => {
+                    eprintln!("error: could not run typst: {e}");
+                    return true;
+                }
+            }
+        }
+    }
+
+    failed
+}
@@ -0,0 +1,92 @@
+use std::env;
+
+/// Expand a leading `~` to the home directory, and `$VAR` or `${VAR}`
+/// to the value of that environment variable
+/// Unknown variables expand to nothing, with a warning
+pub fn expand_path(path: &str) -> String {
+    let path = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            format!("{}{rest}", lookup("HOME"))
+        }
+        _ => path.to_string(),
+    };
+
+    let mut out = String::new();
+    let mut chars = path.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            out.push(c);
+            continue;
+        }
+
+        let mut name = String::new();
+        if chars.peek() == Some(&'{') {
+            chars.next();
+
+            for c in chars.by_ref() {
+                if c == '}' {
+                    break;
+                }
+                name.push(c);
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                name.push(c);
+                chars.next();
+            }
+        }
+
+        // a lone $ is not a variable
+        if name.is_empty() {
+            out.push('$');
+        } else {
+            out.push_str(&lookup(&name));
+        }
+    }
+
+    out
+}
+
+/// Read an environment variable, warning and defaulting to empty if unset
+fn lookup(name: &str) -> String {
+    env::var(name).unwrap_or_else(|_| {
+        eprintln!("warning: ${name} is not set, expanding to nothing");
+        String::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tilde_is_home() {
+        let home = env::var("HOME").unwrap();
+        assert_eq!(
+            expand_path("~/docs/exam.md"),
+            format!("{home}/docs/exam.md")
+        );
+        // only a leading ~ on its own is the home directory
+        assert_eq!(expand_path("~user/docs"), "~user/docs");
+    }
+
+    #[test]
+    fn defined_variables_expand() {
+        let home = env::var("HOME").unwrap();
+        assert_eq!(expand_path("$HOME/docs"), format!("{home}/docs"));
+        assert_eq!(expand_path("${HOME}.md"), format!("{home}.md"));
+    }
+
+    #[test]
+    fn undefined_variables_expand_to_nothing() {
+        assert_eq!(
+            expand_path("docs/$MARKER_UNSET_VARIABLE/exam.md"),
+            "docs//exam.md"
+        );
+        assert_eq!(expand_path("cost$"), "cost$");
+    }
+}
@@ -0,0 +1,415 @@
+use crate::{
+    Language,
+    directive::{directive, ignores_block, ignores_file},
+    grammar,
+    parse::{definition, near_miss, signature},
+    wrap,
+};
+
+/// Prefix of the doc comment lines `fix` rewrites
+const PREFIX: &str = "///";
+
+/// Order tags are put in, with custom tags after all of them
+const TAG_ORDER: [&str; 14] = [
+    "module",
+    "inheritdoc",
+    "param",
+    "return",
+    "panics",
+    "example",
+    "see",
+    "alias",
+    "deprecated",
+    "since",
+    "version",
+    "author",
+    "public",
+    "internal",
+];
+
+/// Tags whose description goes on over the lines after them
+const CONTINUED_TAGS: [&str; 2] = ["param", "return"];
+
+/// Rewrite every `///` doc comment of a typst file in canonical form:
+/// - tags are lowercase with no trailing colon, and a near miss of a built in tag
+///   is spelled like it, as are @returns, @throws and @copydoc as @return, @panics
+///   and @inheritdoc
+/// - params and returns take the `name type = default` form, with unions in brackets,
+///   e.g. `{int|none} [x=1]` becomes `x [int | none] = 1`
+/// - a header without `: ` is titled with the name of the #let below it
+/// - tags go in the order module, inheritdoc, params, return, panics, examples, see, alias, deprecated, since,
+///   version, author, public, internal and custom tags, each with the lines after it
+/// - prose lines longer than `width` columns are wrapped, leaving examples alone
+///
+/// Lines that need no change are kept as they are
+/// Files with `marker:ignore-file` and doc comments with `marker:ignore` are left alone
+pub fn fix(input: &str, width: usize) -> String {
+    if ignores_file(input) {
+        return input.to_string();
+    }
+
+    let signature = signature(&Language::typst());
+    let lines: Vec<&str> = input.lines().collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        if content(lines[i]).is_none() {
+            out.push(lines[i].to_string());
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < lines.len() && content(lines[i]).is_some() {
+            i += 1;
+        }
+        let block = &lines[start..i];
+
+        let text: Vec<&str> = block.iter().filter_map(|l| content(l)).collect();
+        if ignores_block(&text.join("\n")) {
+            out.extend(block.iter().map(|l| l.to_string()));
+            continue;
+        }
+
+        let indent = &lines[start][..lines[start].len() - lines[start].trim_start().len()];
+        let name = signature
+            .as_ref()
+            .and_then(|s| definition(&lines[i..], s))
+            .map(|d| d.name);
+        let width = width.saturating_sub(indent.chars().count() + PREFIX.len() + 1);
+
+        let block: Vec<Line> = block.iter().map(|l| Line::new(l)).collect();
+        out.extend(
+            fix_block(block, name.as_deref(), width)
+                .into_iter()
+                .map(|l| l.render(indent)),
+        );
+    }
+
+    let mut fixed = out.join("\n");
+    if input.ends_with('\n') {
+        fixed.push('\n');
+    }
+    fixed
+}
+
+/// A line of a doc comment, with what it says and the line it was in the file
+#[derive(Debug, Clone)]
+struct Line {
+    text: String,
+    /// The line as it was in the file, `None` for new lines
+    original: Option<String>,
+}
+
+impl Line {
+    /// A line of the file
+    fn new(line: &str) -> Line {
+        Line {
+            text: content(line).unwrap_or_default().to_string(),
+            original: Some(line.to_string()),
+        }
+    }
+
+    /// A line to add to the file
+    fn added(text: String) -> Line {
+        Line {
+            text,
+            original: None,
+        }
+    }
+
+    /// The line to write, as it was in the file if it says the same
+    fn render(&self, indent: &str) -> String {
+        match &self.original {
+            Some(original) if content(original) == Some(self.text.as_str()) => original.clone(),
+            _ if self.text.is_empty() => format!("{indent}{PREFIX}"),
+            _ => format!("{indent}{PREFIX} {}", self.text),
+        }
+    }
+
+    /// Whether the line starts a tag, including lines starting with @ that aren't one,
+    /// which end a tag like one does
+    fn starts_tag(&self) -> bool {
+        self.text.trim_start().starts_with('@')
+    }
+}
+
+/// What a `///` line says, without the slashes and the one space after them
+/// `None` for any other line
+fn content(line: &str) -> Option<&str> {
+    let rest = line
+        .trim_start()
+        .strip_prefix(PREFIX)?
+        .trim_start_matches('/');
+    Some(rest.strip_prefix(' ').unwrap_or(rest).trim_end())
+}
+
+/// Rewrite the lines of a doc comment above the #let called `name`,
+/// wrapping prose at `width` columns
+fn fix_block(block: Vec<Line>, name: Option<&str>, width: usize) -> Vec<Line> {
+    // the header, then a chunk per tag with the lines after it
+    let split = block
+        .iter()
+        .position(Line::starts_tag)
+        .unwrap_or(block.len());
+    let mut header = block[..split].to_vec();
+    let mut chunks: Vec<Vec<Line>> = Vec::new();
+    for line in &block[split..] {
+        match line.starts_tag() {
+            true => chunks.push(vec![line.clone()]),
+            false => chunks
+                .last_mut()
+                .expect("chunks start at a tag")
+                .push(line.clone()),
+        }
+    }
+
+    if let Some(name) = name {
+        add_title(&mut header, name);
+    }
+    for chunk in chunks.iter_mut() {
+        chunk[0].text = fix_tag(&chunk[0].text);
+
+        // blank lines at the end only separate it from the next tag
+        if tag_name(&chunk[0]).as_deref() != Some("example") {
+            while chunk.len() > 1 && chunk.last().is_some_and(|l| l.text.trim().is_empty()) {
+                chunk.pop();
+            }
+        }
+    }
+    chunks.sort_by_key(|chunk| rank(&chunk[0]));
+
+    let mut fixed = wrap_lines(header, width, false);
+    for chunk in chunks {
+        match tag_name(&chunk[0]).as_deref() {
+            Some("example") => fixed.extend(chunk),
+            Some(name) => {
+                let continued = CONTINUED_TAGS.contains(&name) || !TAG_ORDER.contains(&name);
+                let mut lines = chunk.into_iter();
+                let tag = lines.next().expect("chunks start at a tag");
+                fixed.extend(wrap_lines(vec![tag], width, continued));
+                fixed.extend(wrap_lines(lines.collect(), width, false));
+            }
+            None => fixed.extend(chunk),
+        }
+    }
+
+    fixed
+}
+
+/// Title a header without the `title: description` separator with `name`,
+/// dropping the name from the start of the description if it is there
+/// e.g. "add - Adds two numbers" -> "add: Adds two numbers"
+fn add_title(header: &mut [Line], name: &str) {
+    let text: Vec<&str> = header
+        .iter()
+        .map(|l| l.text.trim())
+        .filter(|t| directive(t).is_none())
+        .collect();
+    if text.join(" ").contains(": ") {
+        return;
+    }
+
+    let Some(first) = header
+        .iter_mut()
+        .find(|l| !l.text.trim().is_empty() && directive(&l.text).is_none())
+    else {
+        return;
+    };
+
+    let trimmed = first.text.trim();
+    let description = match trimmed.strip_prefix(name) {
+        Some(rest) if rest.is_empty() || rest.starts_with([' ', '(', '-', ':']) => rest
+            .trim_start_matches("()")
+            .trim_start()
+            .trim_start_matches(['-', ':'])
+            .trim_start(),
+        _ => trimmed,
+    };
+    // the description can't be made up
+    if !description.is_empty() {
+        first.text = format!("{name}: {description}");
+    }
+}
+
+/// The name of the tag a line starts, as it is spelled after fixing
+fn tag_name(line: &Line) -> Option<String> {
+    grammar::tag(&line.text).map(|(name, _)| name)
+}
+
+/// Where a chunk starting with `line` goes among the tags
+fn rank(line: &Line) -> usize {
+    tag_name(line)
+        .and_then(|name| TAG_ORDER.iter().position(|t| *t == name))
+        .unwrap_or(TAG_ORDER.len())
+}
+
+/// Rewrite a tag line in canonical form, leaving lines that don't parse as they are
+fn fix_tag(line: &str) -> String {
+    let Some((name, rest)) = grammar::tag(line) else {
+        return line.to_string();
+    };
+
+    let name = match name.as_str() {
+        "returns" => "return",
+        "throws" => "panics",
+        "copydoc" => "inheritdoc",
+        other => near_miss(other).unwrap_or(other),
+    };
+    let fixed = match name {
+        "param" => grammar::param(rest).ok().and_then(|p| {
+            let data_type = union_type(&p.types())?;
+            let default = p
+                .default
+                .as_ref()
+                .map(|d| format!(" = {d}"))
+                .unwrap_or_default();
+            Some(join(
+                &format!("{} {data_type}{default}", p.source_name()),
+                &p.description,
+            ))
+        }),
+        "return" => grammar::returns(rest).ok().and_then(|r| {
+            let data_type = union_type(&r.data_type)?;
+            let field = r.name.map(|n| format!("{n}: ")).unwrap_or_default();
+            Some(join(&format!("{field}{data_type}"), &r.description))
+        }),
+        _ => None,
+    };
+
+    join(
+        &format!("@{name}"),
+        &fixed.unwrap_or_else(|| rest.to_string()),
+    )
+}
+
+/// A type in canonical form, a union in brackets, `None` for no type
+fn union_type(types: &[String]) -> Option<String> {
+    match types {
+        [] => None,
+        [single] => Some(single.clone()),
+        _ => Some(format!("[{}]", types.join(" | "))),
+    }
+}
+
+/// `a` and `b` with a space between them, or `a` alone when `b` is empty
+fn join(a: &str, b: &str) -> String {
+    match b.is_empty() {
+        true => a.to_string(),
+        false => format!("{a} {b}"),
+    }
+}
+
+/// Wrap every line longer than `width` columns, keeping its indentation
+/// `continued` lines go on indented under the first, like the description of an @param
+/// A line may not start with an @ after wrapping, since it would become a tag
+fn wrap_lines(lines: Vec<Line>, width: usize, continued: bool) -> Vec<Line> {
+    let mut wrapped = Vec::new();
+
+    for line in lines {
+        if width == 0 || line.text.chars().count() <= width || directive(&line.text).is_some() {
+            wrapped.push(line);
+            continue;
+        }
+
+        let indent = &line.text[..line.text.len() - line.text.trim_start().len()];
+        let next_indent = match continued {
+            true => format!("{indent}    "),
+            false => indent.to_string(),
+        };
+
+        // every piece is wrapped to fit under the deeper indentation,
+        // so fixing a fixed comment leaves it as it is
+        let mut pieces: Vec<String> = Vec::new();
+        for (i, piece) in wrap(line.text.trim(), width - next_indent.len().min(width))
+            .lines()
+            .enumerate()
+        {
+            match pieces.last_mut() {
+                Some(last) if piece.starts_with('@') => {
+                    last.push(' ');
+                    last.push_str(piece);
+                }
+                _ if i == 0 => pieces.push(format!("{indent}{piece}")),
+                _ => pieces.push(format!("{next_indent}{piece}")),
+            }
+        }
+
+        let mut pieces = pieces.into_iter();
+        let first = pieces.next().unwrap_or_default();
+        wrapped.push(Line {
+            text: first,
+            original: line.original,
+        });
+        wrapped.extend(pieces.map(Line::added));
+    }
+
+    wrapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = "\
+/// add: Add two numbers together, taking care to handle every case the caller could pass in
+/// @Return: int the sum
+/// @example
+///   #add(1,   2)
+///      // odd   spacing, kept as it is even past the width of the rest of the comment
+/// @param a int the first number, which is also a very long description that goes on
+/// @Param: b int the second
+#let add(a, b) = a + b
+";
+
+    #[test]
+    fn tags_are_reordered_and_spelled_canonically() {
+        let fixed = fix(MESSY, 200);
+        let tags: Vec<&str> = fixed
+            .lines()
+            .filter_map(|l| l.strip_prefix("/// @"))
+            .collect();
+
+        assert_eq!(
+            tags,
+            [
+                "param a int the first number, which is also a very long description that goes on",
+                "param b int the second",
+                "return int the sum",
+                "example",
+            ]
+        );
+    }
+
+    #[test]
+    fn example_bodies_are_left_byte_identical() {
+        let example = "/// @example\n///   #add(1,   2)\n///      // odd   spacing, kept as it is even past the width of the rest of the comment\n";
+
+        assert!(MESSY.contains(example));
+        assert!(fix(MESSY, 40).contains(example));
+    }
+
+    #[test]
+    fn prose_is_wrapped_at_the_width() {
+        let fixed = fix(MESSY, 40);
+
+        // examples come last, and aren't wrapped
+        assert!(
+            fixed
+                .lines()
+                .take_while(|l| *l != "/// @example")
+                .all(|l| l.chars().count() <= 40)
+        );
+        assert!(fixed.contains("/// @param a int the first number,\n///     which"));
+    }
+
+    #[test]
+    fn fixing_twice_changes_nothing() {
+        for width in [40, 80, 200] {
+            let fixed = fix(MESSY, width);
+
+            assert_eq!(fix(&fixed, width), fixed);
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use std::{io, process::Command};
+
+/// Run git with `args`, returning what it printed
+/// A failing git is an error holding what it printed to standard error
+fn git(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(stderr.trim().to_string()));
+    }
+
+    String::from_utf8(output.stdout).map_err(io::Error::other)
+}
+
+/// Every file under `paths` at the revision `rev`, relative to the current directory
+pub fn files(rev: &str, paths: &[String]) -> io::Result<Vec<String>> {
+    let mut args = vec!["ls-tree", "-r", "--name-only", rev, "--"];
+    args.extend(paths.iter().map(String::as_str));
+
+    Ok(git(&args)?.lines().map(str::to_string).collect())
+}
+
+/// The contents of `path`, relative to the current directory, at the revision `rev`
+pub fn show(rev: &str, path: &str) -> io::Result<String> {
+    git(&["show", &format!("{rev}:./{path}")])
+}
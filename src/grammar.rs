@@ -0,0 +1,505 @@
+use crate::{Param, Return};
+
+// The pieces of a tag line, each parsed by a function of the same name:
+//
+// tag       = "@" name ":"? rest
+// param     = name type ("=" value)? description
+//           | "{" type "}" (name | "[" name ("=" value)? "]") "-"? description
+// return    = (name ":")? (type | "{" type "}" "-"?) description
+// type      = token ("|" token)*
+// token     = a run of characters up to whitespace or "=", where brackets nest
+//             and strings are quoted, e.g. int, [int | none], dictionary(a: int)
+// value     = a token that may also contain "=", e.g. "two words", (x: 1)
+// a param name may start with .. for a variadic param, e.g. ..answers
+
+/// A tag line split into its lowercase name and the rest of the line,
+/// e.g. "  @Param: x int" -> ("param", "x int")
+/// `None` for lines that aren't a tag
+pub fn tag(line: &str) -> Option<(String, &str)> {
+    let line = line.trim_start().strip_prefix('@')?;
+    let end = line.find(|c| !is_name_char(c)).unwrap_or(line.len());
+    if end == 0 {
+        return None;
+    }
+
+    let (name, rest) = line.split_at(end);
+    let rest = rest.strip_prefix(':').unwrap_or(rest);
+    Some((name.to_lowercase(), rest.trim()))
+}
+
+/// An @param after the tag, e.g. `points int = 1 number of points`,
+/// or in JSDoc style, e.g. `{int} [points=1] number of points`
+/// Fails with the piece that is missing
+pub fn param(rest: &str) -> Result<Param, &'static str> {
+    let mut scanner = Scanner::new(rest);
+
+    if let Some(data_type) = scanner.braced() {
+        // optional params are bracketed, with their default inside
+        let (name, default) = match scanner.braced_by('[') {
+            Some(optional) => match optional.split_once('=') {
+                Some((name, default)) => (name.trim(), Some(default.trim())),
+                None => (optional.trim(), None),
+            },
+            None => (scanner.token(true).ok_or("name")?, None),
+        };
+        if !is_param_name(name) {
+            return Err("name");
+        }
+        if default.is_some_and(str::is_empty) {
+            return Err("default");
+        }
+
+        let (data_type, choices) = choices(union(data_type));
+        return Ok(Param {
+            name: name.trim_start_matches("..").to_string(),
+            variadic: name.starts_with(".."),
+            data_type,
+            choices,
+            named: default.is_some(),
+            default: default.map(str::to_string),
+            description: scanner.description().to_string(),
+            span: None,
+        });
+    }
+
+    let name = scanner
+        .token(true)
+        .filter(|n| is_param_name(n))
+        .ok_or("name")?;
+    let data_type = scanner.data_type().ok_or("type")?;
+    let default = match scanner.eat('=') {
+        true => Some(scanner.token(false).ok_or("default")?),
+        false => None,
+    };
+
+    let (data_type, choices) = choices(union(data_type));
+    Ok(Param {
+        name: name.trim_start_matches("..").to_string(),
+        variadic: name.starts_with(".."),
+        data_type,
+        choices,
+        named: default.is_some(),
+        default: default.map(str::to_string),
+        description: scanner.rest().to_string(),
+        span: None,
+    })
+}
+
+/// An @return after the tag, e.g. `[str | none] the answer` or `{str | none} the answer`,
+/// possibly naming a field of the result, e.g. `width: length how wide it is`
+/// Fails with the piece that is missing
+pub fn returns(rest: &str) -> Result<Return, &'static str> {
+    let field = rest
+        .split_once(char::is_whitespace)
+        .and_then(|(field, after)| Some((field.strip_suffix(':')?, after.trim_start())))
+        .filter(|(name, _)| is_param_name(name));
+    let (name, rest) = match field {
+        Some((name, after)) => (Some(name), after),
+        None => (None, rest),
+    };
+
+    let mut scanner = Scanner::new(rest);
+    let ret = match scanner.braced() {
+        Some(data_type) => Return::new(data_type.trim()).with_description(scanner.description()),
+        None => Return::new(scanner.data_type().ok_or("type")?).with_description(scanner.rest()),
+    };
+
+    Ok(match name {
+        Some(name) => ret.with_name(name),
+        None => ret,
+    })
+}
+
+/// The types of a union, without the brackets around it
+/// e.g. "[int | array(int | str)]" -> ["int", "array(int | str)"]
+pub fn union(data_type: &str) -> Vec<String> {
+    let data_type = data_type.trim();
+    let inner = match data_type.strip_prefix('[') {
+        Some(inner) if closing(data_type) == Some(data_type.len() - 1) => &inner[..inner.len() - 1],
+        _ => data_type,
+    };
+
+    let mut types = Vec::new();
+    let mut start = 0;
+    let mut nesting = Nesting::default();
+    for (i, c) in inner.char_indices() {
+        if c == '|' && nesting.is_top() {
+            types.push(inner[start..i].trim().to_string());
+            start = i + 1;
+        }
+        nesting.push(c);
+    }
+    types.push(inner[start..].trim().to_string());
+
+    types.retain(|t| !t.is_empty());
+    types
+}
+
+/// The types of a union split from the string literals among them, which become a single str,
+/// e.g. ["\"left\"", "\"right\"", "none"] -> (["str", "none"], ["\"left\"", "\"right\""])
+fn choices(types: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut data_type = Vec::new();
+    let mut choices = Vec::new();
+    for t in types {
+        match literal_type(&t) {
+            Some("str") => {
+                if !data_type.iter().any(|t| t == "str") {
+                    data_type.push("str".to_string());
+                }
+                choices.push(t);
+            }
+            _ => data_type.push(t),
+        }
+    }
+    (data_type, choices)
+}
+
+/// The typst type of a literal default, e.g. "1" -> int, "2pt" -> length, "(a: 1)" -> dictionary
+/// `None` for anything but a literal, like a variable or a call
+pub fn literal_type(value: &str) -> Option<&'static str> {
+    let value = value.trim();
+    match value {
+        "true" | "false" => return Some("bool"),
+        "none" => return Some("none"),
+        "auto" => return Some("auto"),
+        _ => {}
+    }
+
+    let whole = closing(value).is_some_and(|end| end == value.len() - 1);
+    match value.chars().next()? {
+        '"' if whole => return Some("str"),
+        '[' if whole => return Some("content"),
+        '(' if whole => {
+            let inner = &value[1..value.len() - 1];
+            let mut nesting = Nesting::default();
+            let keyed = inner.chars().any(|c| {
+                let top = nesting.is_top();
+                nesting.push(c);
+                top && c == ':'
+            });
+            return Some(if keyed { "dictionary" } else { "array" });
+        }
+        _ => {}
+    }
+
+    let unsigned = value.strip_prefix(['-', '+']).unwrap_or(value);
+    let end = unsigned
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(unsigned.len());
+    let (number, unit) = unsigned.split_at(end);
+    number.parse::<f64>().ok()?;
+    match unit {
+        "" if number.contains('.') => Some("float"),
+        "" => Some("int"),
+        "pt" | "mm" | "cm" | "in" | "em" => Some("length"),
+        "%" => Some("ratio"),
+        "fr" => Some("fraction"),
+        "deg" | "rad" => Some("angle"),
+        _ => None,
+    }
+}
+
+/// Whether a param documented as `documented` may default to a literal of type `literal`,
+/// allowing for the types typst converts it to
+pub fn accepts(documented: &[String], literal: &str) -> bool {
+    let widened: &[&str] = match literal {
+        "int" => &["float"],
+        "length" | "ratio" => &["relative"],
+        "str" => &["content"],
+        _ => &[],
+    };
+    documented.iter().any(|t| {
+        // "array(int)" is still an array, and "left" a str
+        let base = t.split('(').next().unwrap_or(t);
+        base == "any"
+            || base == literal
+            || widened.contains(&base)
+            || literal_type(t) == Some(literal)
+    })
+}
+
+/// The name of every type in a type, those inside brackets too, leaving out literals
+/// e.g. "[array(int | str) | none]" -> ["array", "int", "str", "none"],
+/// "dictionary(a: int)" -> ["dictionary", "int"]
+pub fn type_names(data_type: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for t in union(data_type) {
+        if literal_type(&t).is_some_and(|l| !matches!(l, "none" | "auto")) {
+            continue;
+        }
+
+        let Some(open) = t.find('(') else {
+            names.push(t);
+            continue;
+        };
+        names.push(t[..open].trim().to_string());
+        let inner = t[open + 1..].strip_suffix(')').unwrap_or(&t[open + 1..]);
+        for piece in split_top(inner, ',') {
+            // a dictionary names its keys before their types
+            let piece = match split_top(piece, ':').as_slice() {
+                [_, value, ..] => *value,
+                _ => piece,
+            };
+            names.extend(type_names(piece));
+        }
+    }
+    names.retain(|n| !n.is_empty());
+    names
+}
+
+/// `text` split at every `c` outside of brackets and strings
+fn split_top(text: &str, c: char) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut nesting = Nesting::default();
+    for (i, ch) in text.char_indices() {
+        if ch == c && nesting.is_top() {
+            pieces.push(&text[start..i]);
+            start = i + 1;
+        }
+        nesting.push(ch);
+    }
+    pieces.push(&text[start..]);
+    pieces
+}
+
+/// Index of the bracket closing the one `text` starts with
+fn closing(text: &str) -> Option<usize> {
+    let mut nesting = Nesting::default();
+    for (i, c) in text.char_indices() {
+        nesting.push(c);
+        if nesting.is_top() {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Whether `c` can be part of a tag name
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Whether `name` is a parameter name, optionally prefixed with .. for variadics
+fn is_param_name(name: &str) -> bool {
+    let name = name.strip_prefix("..").unwrap_or(name);
+    !name.is_empty() && name.chars().all(is_name_char)
+}
+
+/// How deep into brackets and strings a scan is
+#[derive(Default)]
+struct Nesting {
+    depth: usize,
+    quoted: bool,
+    escaped: bool,
+}
+
+impl Nesting {
+    /// Whether the scan is outside every bracket and string
+    fn is_top(&self) -> bool {
+        self.depth == 0 && !self.quoted
+    }
+
+    /// Move the scan past `c`
+    fn push(&mut self, c: char) {
+        if self.escaped {
+            self.escaped = false;
+            return;
+        }
+
+        match c {
+            '\\' if self.quoted => self.escaped = true,
+            '"' => self.quoted = !self.quoted,
+            _ if self.quoted => {}
+            '(' | '[' | '{' => self.depth += 1,
+            ')' | ']' | '}' => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+}
+
+/// A cursor over the rest of a tag line, consuming one piece at a time
+struct Scanner<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Scanner<'a> {
+        Scanner { rest: text.trim() }
+    }
+
+    /// Consume `c` after any whitespace, if it is next
+    fn eat(&mut self, c: char) -> bool {
+        match self.rest.trim_start().strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest.trim_start();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Consume a token, stopping at whitespace or, if `stop_at_eq`, an = outside brackets
+    /// `None` if there is none, or it leaves a bracket or string open
+    fn token(&mut self, stop_at_eq: bool) -> Option<&'a str> {
+        let text = self.rest.trim_start();
+        let mut nesting = Nesting::default();
+        let mut end = text.len();
+
+        for (i, c) in text.char_indices() {
+            if nesting.is_top() && (c.is_whitespace() || (stop_at_eq && c == '=')) {
+                end = i;
+                break;
+            }
+            nesting.push(c);
+        }
+
+        if end == 0 || (end == text.len() && !nesting.is_top()) {
+            return None;
+        }
+
+        self.rest = text[end..].trim_start();
+        Some(&text[..end])
+    }
+
+    /// Consume a type, a token or a union of them separated by |
+    fn data_type(&mut self) -> Option<&'a str> {
+        let text = self.rest.trim_start();
+        self.token(true)?;
+
+        // a | continues the union, even with whitespace around it
+        while self.rest.starts_with('|') {
+            let before = self.rest;
+            self.eat('|');
+            if self.token(true).is_none() {
+                self.rest = before;
+                break;
+            }
+        }
+
+        let end = text.len() - self.rest.len();
+        Some(text[..end].trim_end())
+    }
+
+    /// Consume a JSDoc type in braces, giving what is inside them
+    fn braced(&mut self) -> Option<&'a str> {
+        self.braced_by('{')
+    }
+
+    /// Consume a bracketed group opened by `open`, giving what is inside it
+    fn braced_by(&mut self, open: char) -> Option<&'a str> {
+        let text = self.rest.trim_start();
+        if !text.starts_with(open) {
+            return None;
+        }
+
+        let end = closing(text)?;
+        self.rest = text[end + 1..].trim_start();
+        Some(&text[1..end])
+    }
+
+    /// Everything not consumed yet, less the - JSDoc puts before descriptions
+    fn description(&self) -> &'a str {
+        let rest = self.rest();
+        rest.strip_prefix("- ").unwrap_or(rest).trim()
+    }
+
+    /// Everything not consumed yet
+    fn rest(&self) -> &'a str {
+        self.rest.trim()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_name_is_lowercase_and_the_colon_optional() {
+        assert_eq!(tag("  @Param: x int"), Some(("param".to_string(), "x int")));
+        assert_eq!(tag("@return"), Some(("return".to_string(), "")));
+        assert_eq!(tag("@ param x"), None);
+        assert_eq!(tag("user@example.com"), None);
+    }
+
+    #[test]
+    fn param_type_with_nested_brackets() {
+        let param = param("cells [array(array(int | str)) | none] the cells").unwrap();
+
+        assert_eq!(param.name, "cells");
+        assert_eq!(param.data_type, ["array(array(int | str))", "none"]);
+        assert_eq!(param.description, "the cells");
+    }
+
+    #[test]
+    fn param_type_containing_equals() {
+        let param = param("style dictionary(fill: color = red) = (:) how it looks").unwrap();
+
+        assert_eq!(param.data_type, ["dictionary(fill: color = red)"]);
+        assert_eq!(param.default.as_deref(), Some("(:)"));
+        assert_eq!(param.description, "how it looks");
+    }
+
+    #[test]
+    fn param_default_quoted_with_spaces() {
+        let plain = param("label str = \"two words\" the label").unwrap();
+
+        assert_eq!(plain.default.as_deref(), Some("\"two words\""));
+        assert_eq!(plain.description, "the label");
+
+        let jsdoc = param("{str} [label=\"two words\"] the label").unwrap();
+        assert_eq!(jsdoc.name, "label");
+        assert_eq!(jsdoc.default.as_deref(), Some("\"two words\""));
+    }
+
+    #[test]
+    fn param_union_of_choices() {
+        let param = param("align [\"left\" | \"right\" | none] = none where it goes").unwrap();
+
+        assert_eq!(param.data_type, ["str", "none"]);
+        assert_eq!(param.choices, ["\"left\"", "\"right\""]);
+    }
+
+    #[test]
+    fn param_missing_pieces() {
+        assert_eq!(param("").unwrap_err(), "name");
+        assert_eq!(param("points").unwrap_err(), "type");
+        assert_eq!(param("points int =").unwrap_err(), "default");
+    }
+
+    #[test]
+    fn union_splits_only_at_the_top() {
+        assert_eq!(
+            union("[int | array(int | str)]"),
+            ["int", "array(int | str)"]
+        );
+        assert_eq!(union("str | \"a | b\""), ["str", "\"a | b\""]);
+        assert_eq!(union("int"), ["int"]);
+    }
+
+    #[test]
+    fn return_of_a_union() {
+        let ret = returns("[str | none] the answer").unwrap();
+
+        assert_eq!(ret.data_type, ["str", "none"]);
+        assert_eq!(ret.description, "the answer");
+    }
+
+    #[test]
+    fn return_with_a_field_name() {
+        let ret = returns("width: length how wide it is").unwrap();
+
+        assert_eq!(ret.name.as_deref(), Some("width"));
+        assert_eq!(ret.data_type, ["length"]);
+        assert_eq!(ret.description, "how wide it is");
+    }
+
+    #[test]
+    fn type_names_reach_inside_brackets() {
+        assert_eq!(
+            type_names("[array(int | str) | none]"),
+            ["array", "int", "str", "none"]
+        );
+        assert_eq!(type_names("dictionary(a: int)"), ["dictionary", "int"]);
+    }
+}
@@ -0,0 +1,404 @@
+use tera::Context;
+
+use crate::{
+    Config, DocComment, Example, Param, Return, Section, TagStyle, anchor, template::fill, types,
+    warn,
+};
+
+/// Trait representing the ability to be converted into html
+pub trait Htmlable {
+    fn html(&self, config: &Config) -> String;
+}
+
+impl Htmlable for DocComment {
+    /// Convert a DocComment into a `<section>` anchored by its slug
+    fn html(&self, config: &Config) -> String {
+        let mut html = String::new();
+
+        html.push_str(&format!("<section id=\"{}\">\n", escape(&self.slug)));
+        html.push_str(&format!("<h2>{}</h2>\n", escape(&self.title)));
+        html.push_str(&format!(
+            "<pre><code>{}</code></pre>\n",
+            escape(&self.signature())
+        ));
+
+        if let Some(metadata) = config.metadata(self) {
+            html.push_str(&format!(
+                "<p class=\"metadata\"><small>{}</small></p>\n",
+                escape(&metadata)
+            ));
+        }
+
+        let mut badges: Vec<String> = config
+            .tags_in(TagStyle::Badge)
+            .flat_map(|t| {
+                self.tag_values(&t.name).map(move |v| match v {
+                    "" => format!("<span class=\"badge\">{}</span>", escape(&t.label())),
+                    v => format!(
+                        "<span class=\"badge\">{}: {}</span>",
+                        escape(&t.label()),
+                        escape(v)
+                    ),
+                })
+            })
+            .collect();
+        let marks = config.marks(self).into_iter();
+        badges.splice(
+            0..0,
+            marks.map(|label| format!("<span class=\"badge\">{}</span>", escape(label))),
+        );
+        if !badges.is_empty() {
+            html.push_str(&format!("<p>{}</p>\n", badges.join(" ")));
+        }
+
+        for tag in config.tags_in(TagStyle::KeyValue) {
+            self.tag_values(&tag.name).for_each(|v| {
+                html.push_str(&format!(
+                    "<p><strong>{}:</strong> {}</p>\n",
+                    escape(&tag.label()),
+                    escape(v)
+                ));
+            });
+        }
+
+        if let Some(msg) = &self.deprecated {
+            html.push_str(&format!(
+                "<p class=\"deprecated\"><strong>Deprecated</strong> {}</p>\n",
+                escape(msg)
+            ));
+        }
+
+        for section in config.sections() {
+            let start = html.len();
+
+            match section {
+                Section::Description => {
+                    html.push_str(&format!("<p>{}</p>\n", prose(&self.description, config)));
+                }
+                Section::Params if !self.params.is_empty() => {
+                    html.push_str(&format!(
+                        "<h3>{}</h3>\n",
+                        escape(config.label(Section::Params))
+                    ));
+                    let columns: String = config
+                        .locale
+                        .param_columns()
+                        .iter()
+                        .map(|c| format!("<th>{c}</th>"))
+                        .collect();
+
+                    for (group, params) in config.param_groups(self) {
+                        if let Some(group) = group {
+                            html.push_str(&format!("<h4>{}</h4>\n", escape(group)));
+                        }
+                        html.push_str("<table>\n");
+                        html.push_str(&format!("<thead><tr>{columns}</tr></thead>\n"));
+                        html.push_str("<tbody>\n");
+
+                        params.iter().for_each(|p| {
+                            html.push_str(&p.html(config));
+                        });
+
+                        html.push_str("</tbody>\n");
+                        html.push_str("</table>\n");
+                    }
+                }
+                Section::Returns if !self.returns.is_empty() => {
+                    html.push_str(&format!(
+                        "<h3>{}</h3>\n",
+                        escape(config.label(Section::Returns))
+                    ));
+                    match self.returns.as_slice() {
+                        [ret] => html.push_str(&ret.html(config)),
+                        returns => {
+                            html.push_str("<ul>\n");
+                            returns.iter().for_each(|r| {
+                                let item = r.html(config);
+                                let item = item.trim_end().trim_start_matches("<p>");
+                                html.push_str(&format!(
+                                    "<li>{}</li>\n",
+                                    item.trim_end_matches("</p>")
+                                ));
+                            });
+                            html.push_str("</ul>\n");
+                        }
+                    }
+                }
+                Section::Examples if !self.examples.is_empty() => {
+                    html.push_str(&format!(
+                        "<h3>{}</h3>\n",
+                        escape(config.label(Section::Examples))
+                    ));
+
+                    self.examples.iter().for_each(|e| {
+                        html.push_str(&e.html(config));
+                    });
+                }
+                Section::Errors if !self.errors.is_empty() => {
+                    html.push_str(&format!(
+                        "<h3>{}</h3>\n",
+                        escape(config.label(Section::Errors))
+                    ));
+                    html.push_str("<ul>\n");
+
+                    self.errors.iter().for_each(|e| {
+                        html.push_str(&format!("<li>{}</li>\n", prose(e, config)));
+                    });
+
+                    html.push_str("</ul>\n");
+                }
+                Section::SeeAlso if !self.see.is_empty() => {
+                    html.push_str(&format!(
+                        "<h3>{}</h3>\n",
+                        escape(config.label(Section::SeeAlso))
+                    ));
+                    html.push_str("<ul>\n");
+
+                    self.see.iter().for_each(|s| {
+                        html.push_str(&format!(
+                            "<li><a href=\"{}#{}\"><code>{}</code></a></li>\n",
+                            escape(config.page_of(s)),
+                            escape(&anchor(s)),
+                            escape(s)
+                        ));
+                    });
+
+                    html.push_str("</ul>\n");
+                }
+                Section::Notes if !self.notes.is_empty() => {
+                    html.push_str(&format!(
+                        "<h3>{}</h3>\n",
+                        escape(config.label(Section::Notes))
+                    ));
+                    html.push_str("<ul>\n");
+
+                    self.notes.iter().for_each(|n| {
+                        html.push_str(&format!("<li>{}</li>\n", prose(n, config)));
+                    });
+
+                    html.push_str("</ul>\n");
+                }
+                _ => {}
+            }
+
+            // the heading becomes the summary
+            if config.collapse.contains(&section) && html.len() > start {
+                let rendered = html.split_off(start);
+                let body = rendered.split_once('\n').map_or("", |(_, body)| body);
+                html.push_str(&format!(
+                    "<details>\n<summary>{}</summary>\n{body}</details>\n",
+                    escape(config.label(section))
+                ));
+            }
+        }
+
+        for tag in config.tags_in(TagStyle::Section) {
+            let mut values = self.tag_values(&tag.name).peekable();
+            if values.peek().is_some() {
+                html.push_str(&format!("<h3>{}</h3>\n", escape(&tag.label())));
+                html.push_str("<ul>\n");
+
+                values.for_each(|v| {
+                    html.push_str(&format!("<li>{}</li>\n", escape(v)));
+                });
+
+                html.push_str("</ul>\n");
+            }
+        }
+
+        html.push_str("</section>\n");
+
+        // a stub per @alias, so links to the other name lead here
+        for alias in &self.aliases {
+            html.push_str(&format!(
+                "<section id=\"{}\">\n<h2>{}</h2>\n<p>{} <a href=\"#{}\">{}</a>.</p>\n</section>\n",
+                escape(&anchor(alias)),
+                escape(alias),
+                config.locale.alias_of(),
+                escape(&self.slug),
+                escape(&self.title)
+            ));
+        }
+
+        html
+    }
+}
+
+impl Htmlable for Param {
+    /// Convert a parameter into a row of the parameter table
+    fn html(&self, config: &Config) -> String {
+        format!(
+            "<tr><td><code>{}</code> <small>{}</small></td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape(&self.label()),
+            config.locale.requirement(self.is_required()),
+            // params only found in the definition may have no type
+            if self.data_type.is_empty() {
+                String::new()
+            } else {
+                type_code(&self.types(), config)
+            },
+            self.default
+                .as_ref()
+                .map_or(String::new(), |d| format!("<code>{}</code>", escape(d))),
+            prose(&self.description, config)
+        )
+    }
+}
+
+impl Htmlable for Return {
+    /// Convert a Return into the form "<code>type</code> description",
+    /// after "name: " for a field of the result
+    fn html(&self, config: &Config) -> String {
+        let field = self
+            .name
+            .as_ref()
+            .map(|n| format!("{}: ", escape(n)))
+            .unwrap_or_default();
+        format!(
+            "<p>{field}{} {}</p>\n",
+            type_code(&self.data_type, config),
+            prose(&self.description, config)
+        )
+    }
+}
+
+impl Htmlable for Example {
+    /// Convert an example into a code block tagged with its language
+    fn html(&self, _config: &Config) -> String {
+        let mut html = format!(
+            "<pre><code class=\"language-{}\">{}</code></pre>\n",
+            escape(&self.language),
+            escape(&self.code)
+        );
+
+        if let Some(image) = &self.image {
+            html.push_str(&format!(
+                "<img src=\"{}\" alt=\"Output of the example\">\n",
+                escape(image)
+            ));
+        }
+
+        html
+    }
+}
+
+/// The introduction to a file from its doc comment, its description and examples
+pub(crate) fn intro(doc: &DocComment, config: &Config) -> String {
+    let mut html = format!("<p>{}</p>\n", prose(&doc.description, config));
+    doc.examples
+        .iter()
+        .for_each(|e| html.push_str(&e.html(config)));
+    html
+}
+
+/// Types as code, e.g. "<code>int | none</code>", or with `link_types`, each typst type
+/// as code linked to its page of the typst reference
+fn type_code(data_type: &[String], config: &Config) -> String {
+    if !config.link_types {
+        return format!("<code>{}</code>", escape(&data_type.join(" | ")));
+    }
+    let linked: Vec<String> = data_type
+        .iter()
+        .map(|t| match types::reference(t) {
+            Some(href) => format!("<a href=\"{href}\"><code>{}</code></a>", escape(t)),
+            None => format!("<code>{}</code>", escape(t)),
+        })
+        .collect();
+    linked.join(" | ")
+}
+
+/// Escaped prose with mentions of documented functions linked to their docs
+fn prose(text: &str, config: &Config) -> String {
+    config.autolink(&escape(text), |name, href| {
+        format!("<a href=\"{}\"><code>{name}</code></a>", escape(href))
+    })
+}
+
+/// Stylesheet of pages without a theme of their own
+const STYLE: &str = include_str!("style.css");
+
+/// Script of the search box, looking names and summaries up in the search index
+const SEARCH: &str = include_str!("search.js");
+
+/// Wrap rendered sections into a standalone html page styled by the theme
+/// `depth` is how many directories under the root of the docs the page is,
+/// which the link to the search index has to climb out of
+pub fn page(title: &str, body: &str, config: &Config, depth: usize) -> String {
+    let theme = &config.theme;
+    let title = escape(title);
+    let default_header = || format!("<header><h1>{title}</h1></header>\n");
+    let header = match &theme.header {
+        Some(header) => {
+            let mut context = Context::new();
+            context.insert("title", &title);
+            fill("header.html", header, &context).unwrap_or_else(|e| {
+                warn(format!("ignoring the theme's header, {e}"));
+                default_header()
+            })
+        }
+        None => default_header(),
+    };
+    let search = match &config.search {
+        Some(index) => format!(
+            "<form class=\"search\" role=\"search\">\n\
+             <input type=\"search\" placeholder=\"Search\" aria-label=\"Search\" data-index=\"{}{}\">\n\
+             <ul></ul>\n\
+             </form>\n\
+             <script>\n{SEARCH}</script>\n",
+            "../".repeat(depth),
+            escape(index)
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        "<!DOCTYPE html>\n\
+         <html lang=\"en\">\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n\
+         <style>\n{style}</style>\n\
+         </head>\n\
+         <body>\n\
+         {header}\
+         {search}\
+         <main>\n\
+         {body}\
+         </main>\n\
+         {footer}\
+         </body>\n\
+         </html>\n",
+        style = theme.css.as_deref().unwrap_or(STYLE),
+        footer = theme.footer.as_deref().unwrap_or_default(),
+    )
+}
+
+/// Escape text for use in html content and attributes
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_block;
+
+    #[test]
+    fn prose_is_escaped() {
+        let doc = parse_block(
+            "f: Pick <b> or *a* | b\n@param a int the <first> * | one\n@return int the \"sum\"\n",
+        )
+        .unwrap();
+        let html = doc.html(&Config::default());
+
+        assert!(html.contains("<p>Pick &lt;b&gt; or *a* | b</p>"));
+        assert!(html.contains("<td>the &lt;first&gt; * | one</td>"));
+        assert!(html.contains("the &quot;sum&quot;"));
+        assert!(!html.contains("<b>") && !html.contains("<first>"));
+    }
+}
@@ -0,0 +1,214 @@
+use std::path::Path;
+
+use crate::{DocComment, Result, directive::ignores_file, doxygen, parse, python, rust};
+
+/// How the contents of a doc comment are read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Syntax {
+    /// A `title: description` header followed by @tags, as in typst
+    Tags,
+    /// A docstring after the definition with Google or NumPy style sections, as in python
+    Docstring,
+    /// Markdown with `# Arguments` and `# Returns` headings, as in rust
+    Rustdoc,
+    /// Commands starting with @ or \, as in C and C++
+    Doxygen,
+}
+
+/// A language `marker` can document, and how its doc comments are written
+/// Languages of the `Tags` syntax need nothing more than this,
+/// so a custom DSL can be documented by describing its comments
+#[derive(Debug, Clone, PartialEq)]
+pub struct Language {
+    pub name: String,
+    /// Extensions of its files, without the dot
+    pub extensions: Vec<String>,
+    /// Prefixes of line doc comments, e.g. `///`
+    pub line_comments: Vec<String>,
+    /// Opening and closing delimiters of block doc comments, e.g. `/**` and `*/`
+    pub block_comments: Vec<(String, String)>,
+    /// Prefixes of the line comments a file may start with to document itself, e.g. `//!`
+    pub module_comments: Vec<String>,
+    /// Pattern of the start of a definition, with its name in a `name` group
+    /// For the `Tags` syntax, arguments are read when it ends at their opening parenthesis,
+    /// and the value of a binding that isn't a function when it ends at its `=`
+    /// A `rule` group instead of a name matches `show` or `set` rules
+    /// `None` for languages whose definitions are found another way
+    pub signature: Option<String>,
+    pub syntax: Syntax,
+}
+
+impl Language {
+    /// Typst, with `///` and `/** */` doc comments above `#let`
+    pub fn typst() -> Language {
+        Language {
+            name: "typst".to_string(),
+            extensions: strings(&["typ"]),
+            line_comments: strings(&["///"]),
+            block_comments: vec![("/**".to_string(), "*/".to_string())],
+            module_comments: strings(&["//!"]),
+            signature: Some(
+                r"^\s*#(?:let\s+(?P<name>[\w-]+)\s*(?:\(|=)|(?P<rule>show|set)\b)".to_string(),
+            ),
+            syntax: Syntax::Tags,
+        }
+    }
+
+    /// Python, with docstrings in `def` and `class`
+    pub fn python() -> Language {
+        Language {
+            name: "python".to_string(),
+            extensions: strings(&["py"]),
+            line_comments: Vec::new(),
+            block_comments: Vec::new(),
+            module_comments: Vec::new(),
+            signature: Some(r"^\s*(?:async\s+)?(?P<kind>def|class)\s+(?P<name>\w+)".to_string()),
+            syntax: Syntax::Docstring,
+        }
+    }
+
+    /// Rust, with `///` doc comments above `pub fn`
+    pub fn rust() -> Language {
+        Language {
+            name: "rust".to_string(),
+            extensions: strings(&["rs"]),
+            line_comments: strings(&["///"]),
+            block_comments: Vec::new(),
+            module_comments: Vec::new(),
+            signature: Some(
+                r#"^\s*pub\s+(?:const\s+)?(?:async\s+)?(?:unsafe\s+)?(?:extern\s+"[^"]*"\s+)?fn\s+(?P<name>\w+)"#
+                    .to_string(),
+            ),
+            syntax: Syntax::Rustdoc,
+        }
+    }
+
+    /// C and C++, with Doxygen comments above function declarations
+    pub fn c() -> Language {
+        Language {
+            name: "c".to_string(),
+            extensions: strings(&["c", "h", "cc", "cpp", "cxx", "hh", "hpp", "hxx"]),
+            line_comments: strings(&["///", "//!"]),
+            block_comments: vec![
+                ("/**".to_string(), "*/".to_string()),
+                ("/*!".to_string(), "*/".to_string()),
+            ],
+            module_comments: Vec::new(),
+            // declarations are too varied for a pattern, so they are read as a whole
+            signature: None,
+            syntax: Syntax::Doxygen,
+        }
+    }
+
+    /// Every language `marker` knows without configuration
+    pub fn builtin() -> Vec<Language> {
+        vec![
+            Language::typst(),
+            Language::python(),
+            Language::rust(),
+            Language::c(),
+        ]
+    }
+
+    /// Find a built in language from its command line name
+    pub fn from_name(name: &str) -> Option<Language> {
+        match name {
+            "typst" | "typ" => Some(Language::typst()),
+            "python" | "py" => Some(Language::python()),
+            "rust" | "rs" => Some(Language::rust()),
+            "c" | "cpp" | "c++" | "doxygen" => Some(Language::c()),
+            _ => None,
+        }
+    }
+
+    /// Find the built in language of a file from its extension
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Language> {
+        let extension = path.as_ref().extension()?.to_str()?;
+        Language::builtin()
+            .into_iter()
+            .find(|l| l.extensions.iter().any(|e| e == extension))
+    }
+
+    /// Parses a file of this language into a DocComment per doc comment
+    /// A doc comment that fails to parse does not stop the rest of the file
+    /// A file with a `marker:ignore-file` comment has none
+    pub fn parse(&self, input: &str) -> Vec<Result<DocComment>> {
+        if ignores_file(input) {
+            return Vec::new();
+        }
+
+        match self.syntax {
+            Syntax::Tags => parse::parse_with(input, self),
+            Syntax::Docstring => python::parse(input, self),
+            Syntax::Rustdoc => rust::parse(input, self).into_iter().map(Ok).collect(),
+            Syntax::Doxygen => doxygen::parse(input, self).into_iter().map(Ok).collect(),
+        }
+    }
+
+    /// Titles of every doc comment in a file of this language, without parsing their contents
+    pub fn titles(&self, input: &str) -> Vec<String> {
+        if ignores_file(input) {
+            return Vec::new();
+        }
+
+        match self.syntax {
+            Syntax::Tags => parse::titles_with(input, self),
+            Syntax::Docstring => python::titles(input, self),
+            Syntax::Rustdoc => rust::titles(input, self),
+            Syntax::Doxygen => doxygen::titles(input, self),
+        }
+    }
+}
+
+/// Owned copies of `strs`
+fn strings(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn jsdoc_comment_documents_the_let_after_it() {
+        let docs = Language::typst().parse(
+            "/**\n * add: Add two numbers\n * @param a int the first\n * @param b int = 1 the second\n * @return int the sum\n */\n#let add(a, b: 1) = a + b\n",
+        );
+        let doc = docs[0].as_ref().unwrap();
+
+        assert_eq!(doc.title, "add");
+        assert_eq!(doc.description, "Add two numbers");
+        assert_eq!(doc.params.len(), 2);
+        assert_eq!(doc.params[0].data_type, ["int"]);
+        assert_eq!(doc.params[1].default.as_deref(), Some("1"));
+        assert_eq!(doc.returns[0].data_type, ["int"]);
+        assert_eq!(doc.returns[0].description, "the sum");
+        assert_eq!(doc.definition.as_ref().unwrap().name, "add");
+    }
+
+    #[test]
+    fn jsdoc_comment_without_a_header_fails() {
+        let docs =
+            Language::typst().parse("/**\n * @param a int the first\n */\n#let add(a) = a\n");
+
+        assert!(matches!(docs[0], Err(Error::MissingSeparator { .. })));
+    }
+
+    #[test]
+    fn jsdoc_comment_without_a_let_has_no_definition() {
+        let docs = Language::typst().parse("/**\n * add: Add two numbers\n */\n\nsome text\n");
+        let doc = docs[0].as_ref().unwrap();
+
+        assert_eq!(doc.title, "add");
+        assert!(doc.definition.is_none());
+    }
+
+    #[test]
+    fn language_from_extension_or_name() {
+        assert_eq!(Language::from_path("lib/exam.typ"), Some(Language::typst()));
+        assert_eq!(Language::from_path("src/main.hpp"), Some(Language::c()));
+        assert_eq!(Language::from_path("notes.txt"), None);
+        assert_eq!(Language::from_name("c++"), Some(Language::c()));
+    }
+}
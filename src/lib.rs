@@ -0,0 +1,143 @@
+//! Generate markdown documentation from typst doc comments
+//!
+//! `marker` parses `///` doc comments above typst functions into [`DocComment`]s,
+//! which can then be rendered to markdown, plain text, JSON, html and more
+//!
+//! ```
+//! use marker_typ::{Config, Markdownable, parse_document};
+//!
+//! let source = "/// double: Double a number\n/// @param x int number to double\n#let double(x) = x * 2";
+//! for doc in parse_document(source) {
+//!     let doc = doc?;
+//!     assert_eq!(doc.signature(), "double(x)");
+//!     println!("{}", doc.markdown(&Config::default()));
+//! }
+//! # Ok::<(), marker_typ::Error>(())
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Display,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+pub use asciidoc::Asciidocable;
+pub use config::{
+    Config, CustomTag, Format, Locale, NamePatterns, Package, Section, Sort, TagStyle, Theme,
+};
+pub use diff::{ApiDiff, Function, FunctionChange, ParamChange, Severity, bump, diff};
+pub use directive::{RULES, ignores_file};
+pub use doc::{Definition, DocComment, Example, Kind, Param, Return, Tag, Visibility};
+pub use doxygen::{doxygen_titles, parse_doxygen};
+pub use error::{Error, Result, Span};
+pub use fix::fix;
+pub use html::Htmlable;
+pub use language::{Language, Syntax};
+pub use lint::{Finding, lint, lint_choices, lint_see, lint_since, version_numbers};
+pub use man::Manable;
+pub use markdown::{Markdownable, anchor, slugs};
+pub use parse::{parse_block, parse_definition, parse_document, parse_iter, parse_titles};
+pub use python::{parse_python, python_titles};
+pub use render::{
+    INJECT_END, INJECT_START, combine, inject, page_path, pages, render, search_index, sidebar,
+    single_file, sort, standalone, symbol_index, toc, toc_index, workspace_index,
+};
+pub use rst::Rstable;
+pub use rust::{parse_rust, rust_titles};
+pub use scaffold::scaffold;
+pub use template::check_template;
+pub use text::Textable;
+pub use wrap::wrap;
+
+mod asciidoc;
+mod config;
+mod diff;
+mod directive;
+mod doc;
+mod doxygen;
+mod error;
+mod fix;
+mod grammar;
+mod html;
+mod language;
+mod lint;
+mod man;
+mod markdown;
+mod parse;
+mod python;
+mod render;
+mod rst;
+mod rust;
+mod scaffold;
+mod template;
+mod text;
+mod types;
+mod wrap;
+
+/// Number of warnings emitted about doc comments so far
+static WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Number of warnings emitted about doc comments on this thread so far
+    static THREAD_WARNINGS: Cell<usize> = const { Cell::new(0) };
+
+    /// Warnings about doc comments in a file collected on this thread instead of emitted,
+    /// while `collect_findings` runs
+    static COLLECTED: RefCell<Option<Vec<Finding>>> = const { RefCell::new(None) };
+
+    /// Warnings emitted on this thread while `record_warnings` runs
+    static RECORDED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Emit a warning about a doc comment on stderr
+fn warn(msg: impl Display) {
+    WARNINGS.fetch_add(1, Ordering::Relaxed);
+    THREAD_WARNINGS.set(THREAD_WARNINGS.get() + 1);
+    let msg = msg.to_string();
+    RECORDED.with_borrow_mut(|r| r.as_mut().map(|r| r.push(msg.clone())));
+    eprintln!("warning: {msg}");
+}
+
+/// Emit a warning about a doc comment in a file, or collect it while `collect_findings` runs
+fn warn_finding(finding: Finding) {
+    let collected = COLLECTED.with_borrow_mut(|c| c.as_mut().map(|c| c.push(finding.clone())));
+    if collected.is_none() {
+        let span = finding.span;
+        warn(format!("line {}:{}: {finding}", span.line, span.column));
+    }
+}
+
+/// Run `f`, collecting the warnings about doc comments in a file it runs into,
+/// like an unknown tag, instead of emitting them
+/// Collected warnings aren't counted by `warning_count`
+pub fn collect_findings<T>(f: impl FnOnce() -> T) -> (T, Vec<Finding>) {
+    let previous = COLLECTED.replace(Some(Vec::new()));
+    let value = f();
+    let findings = COLLECTED.replace(previous).unwrap_or_default();
+    (value, findings)
+}
+
+/// Run `f`, recording the warnings it emits so they can be replayed later
+/// Unlike `collect_findings`, the warnings are still emitted and counted
+pub fn record_warnings<T>(f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let previous = RECORDED.replace(Some(Vec::new()));
+    let value = f();
+    let recorded = RECORDED.replace(previous).unwrap_or_default();
+    (value, recorded)
+}
+
+/// Emit warnings recorded by `record_warnings` again, counting them like new ones
+pub fn replay_warnings(warnings: &[String]) {
+    warnings.iter().for_each(warn);
+}
+
+/// Number of warnings emitted about doc comments since the program started
+pub fn warning_count() -> usize {
+    WARNINGS.load(Ordering::Relaxed)
+}
+
+/// Number of warnings emitted about doc comments on the current thread,
+/// for counting the warnings of work done in parallel
+pub fn thread_warning_count() -> usize {
+    THREAD_WARNINGS.get()
+}
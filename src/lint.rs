@@ -0,0 +1,182 @@
+use std::fmt::{self, Display};
+
+use crate::{DocComment, Span, grammar, types};
+
+/// A problem with a doc comment that doesn't stop it from rendering
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// Where the problem is, in the file of the doc comment
+    pub span: Span,
+    /// Name of the rule `marker:allow` turns it off with, one of `RULES`
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl Finding {
+    pub(crate) fn new(span: Span, rule: &'static str, message: String) -> Finding {
+        Finding {
+            span,
+            rule,
+            message,
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}]", self.message, self.rule)
+    }
+}
+
+/// Find @see tags pointing at functions that are not in `titles`
+/// Returns a finding for every missing function, unless the doc comment allows `unknown-see`
+pub fn lint_see(doc: &DocComment, titles: &[&str]) -> Vec<Finding> {
+    doc.see
+        .iter()
+        .filter(|s| !titles.contains(&s.as_str()))
+        .filter(|_| !doc.allows("unknown-see"))
+        .map(|s| {
+            Finding::new(
+                doc.span,
+                "unknown-see",
+                format!(
+                    "{} refers to {s} with @see, but it is not documented",
+                    doc.title
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Find params defaulting to a string that isn't one of the choices their type allows,
+/// whether the default is documented or comes from the code
+pub fn lint_choices(doc: &DocComment) -> Vec<Finding> {
+    doc.params
+        .iter()
+        .filter(|_| !doc.allows("unknown-choice"))
+        .filter_map(|p| {
+            let default = p.default.as_ref().filter(|d| !p.is_choice(d))?;
+            Some(Finding::new(
+                p.span.unwrap_or(doc.span),
+                "unknown-choice",
+                format!(
+                    "{} documents {} as one of {}, but it defaults to {default}",
+                    doc.title,
+                    p.name,
+                    p.choices.join(", ")
+                ),
+            ))
+        })
+        .collect()
+}
+
+/// Find an @since naming a version after `version`, the package's current one,
+/// which can't be when the function is already in the package
+pub fn lint_since(doc: &DocComment, version: &str) -> Vec<Finding> {
+    let (Some(since), Some(current)) = (doc.since.as_deref(), version_numbers(version)) else {
+        return Vec::new();
+    };
+    if doc.allows("future-since") || version_numbers(since).is_none_or(|s| s <= current) {
+        return Vec::new();
+    }
+
+    vec![Finding::new(
+        doc.span,
+        "future-since",
+        format!(
+            "{} is @since {since}, after the package's version {version}",
+            doc.title
+        ),
+    )]
+}
+
+/// The numbers of a version to compare it by, padded to major, minor and patch,
+/// e.g. "v0.2" -> [0, 2, 0], ignoring any pre-release or build after them
+/// `None` if it isn't numbers separated by dots
+pub fn version_numbers(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim().trim_start_matches('v');
+    let release = version.split(['-', '+']).next().unwrap_or(version);
+
+    let mut numbers = release
+        .split('.')
+        .map(|n| n.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    if numbers.len() < 3 {
+        numbers.resize(3, 0);
+    }
+    Some(numbers)
+}
+
+/// Compare the `@param` tags of a doc comment with its definition
+/// Returns a finding for every mismatch, empty when there is no definition
+/// Findings point at the @param, or the doc comment for arguments without one
+/// Rules the doc comment allows are left out
+pub fn lint(doc: &DocComment) -> Vec<Finding> {
+    let Some(definition) = &doc.definition else {
+        return Vec::new();
+    };
+
+    let mut findings = Vec::new();
+
+    for param in &doc.params {
+        let in_code = definition.params.iter().find(|p| p.name == param.name);
+        let span = param.span.unwrap_or(doc.span);
+
+        let (rule, message) = match in_code {
+            None => (
+                "unknown-param",
+                format!(
+                    "{} documents @param {} which is not an argument of {}",
+                    doc.title, param.name, definition.name
+                ),
+            ),
+            // params only found in the definition have no span
+            Some(_) if param.span.is_none() => (
+                "missing-param",
+                format!("{} has no @param for argument {}", doc.title, param.name),
+            ),
+            Some(code) => match (&param.default, &code.default) {
+                (Some(documented), Some(actual)) if documented != actual => (
+                    "wrong-default",
+                    format!(
+                        "{} documents {} as defaulting to {documented}, but it defaults to {actual}",
+                        doc.title, param.name
+                    ),
+                ),
+                (Some(documented), None) => (
+                    "wrong-default",
+                    format!(
+                        "{} documents {} as defaulting to {documented}, but it is required",
+                        doc.title, param.name
+                    ),
+                ),
+                // a literal default has a type the documented one should accept
+                _ => match code.default.as_deref().and_then(grammar::literal_type) {
+                    Some(literal)
+                        if param.span.is_some()
+                            && !param.data_type.is_empty()
+                            // an unknown type is already warned about
+                            && param.data_type.iter().all(|t| types::unknown(t).is_empty())
+                            && !grammar::accepts(&param.data_type, literal) =>
+                    {
+                        (
+                            "wrong-type",
+                            format!(
+                                "{} documents {} as {}, but it defaults to {} which is {literal}",
+                                doc.title,
+                                param.name,
+                                param.data_type.join(" | "),
+                                code.default.as_deref().unwrap_or_default()
+                            ),
+                        )
+                    }
+                    _ => continue,
+                },
+            },
+        };
+        findings.push(Finding::new(span, rule, message));
+    }
+
+    findings.retain(|f| !doc.allows(f.rule));
+    findings
+}
@@ -1,241 +1,1388 @@
 use std::{
-    env::{self},
-    fs::File,
-    io::Read,
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
     process::exit,
+    sync::{Arc, OnceLock, mpsc},
+    time::{Duration, SystemTime},
 };
 
-use regex::Regex;
+use clap::Parser;
+use marker_typ::{
+    Config, DocComment, Error, Format, INJECT_END, INJECT_START, Language, Package, Result, bump,
+    collect_findings, combine, inject, lint, lint_choices, lint_see, lint_since, page_path, pages,
+    record_warnings, render, replay_warnings, search_index, sidebar, single_file, sort, standalone,
+    symbol_index, thread_warning_count, toc_index, version_numbers, warning_count, workspace_index,
+};
+use notify::{RecursiveMode, Watcher};
+use rayon::{ThreadPoolBuilder, prelude::*};
+
+use baseline::{Baseline, Known};
+use cache::{CACHE_FILE, Cache, hash_contents};
+use cli::{
+    ChangelogArgs, CheckArgs, Cli, Command, CoverageArgs, DiffArgs, FixArgs, GenArgs, InputArgs,
+    ScaffoldArgs, SemverArgs, ServeArgs, WatchArgs, WorkspaceArgs,
+};
+use examples::compile_examples;
+use manifest::{MANIFEST_FILE, MANIFEST_GLOB, load_package, parse_package, read_package};
+use outcome::Outcome;
+use project::Project;
+use sarif::{PARSE_ERROR, Sarif};
+use serve::{Builds, SERVE_DIR};
+use stats::{FileStats, Stats};
+use walk::{glob_set, walk};
 
-/// Trait representing the ability to be converted into markdown
-trait Markdownable {
-    fn markdown(&self) -> String;
+mod baseline;
+mod cache;
+mod cli;
+mod examples;
+mod expand;
+mod git;
+mod manifest;
+mod outcome;
+mod project;
+mod report;
+mod sarif;
+mod serve;
+mod stats;
+mod versions;
+mod walk;
+
+/// Every file named by the inputs
+/// Directories are walked for every file matching the glob
+fn input_files(input: &InputArgs) -> Vec<String> {
+    input
+        .inputs
+        .iter()
+        .flat_map(|i| {
+            let path = Path::new(i);
+            if path.is_dir() {
+                walk(path, input.glob())
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect()
+            } else {
+                vec![i.to_string()]
+            }
+        })
+        .collect()
 }
 
-/// Structure for all `marker` doc comments in the form
-/// name: Description
-/// ...
-/// @param name type description ...
-/// @param name type description ...
-/// @return type description
-#[derive(Debug)]
-struct DocComment {
-    description: String,
-    params: Vec<Param>,
-    return_type: Option<Return>,
+/// Input naming standard in, e.g. `marker gen -`
+const STDIN: &str = "-";
+
+/// Read an entire input file, or standard in for `-`
+fn read_file(f: &str) -> Result<String> {
+    let io_error = |source| Error::Io {
+        path: f.to_string(),
+        source,
+    };
+
+    if f == STDIN {
+        return read_stdin().map_err(io_error);
+    }
+
+    let mut file = File::open(f).map_err(io_error)?;
+
+    let mut contents = String::new();
+
+    file.read_to_string(&mut contents).map_err(io_error)?;
+
+    Ok(contents)
 }
 
-impl Markdownable for DocComment {
-    /// Convert a DocComment into its markdown representation
-    fn markdown(&self) -> String {
-        let mut md = String::new();
+/// Read all of standard in
+/// It can only be read once, so later calls get the same contents
+fn read_stdin() -> io::Result<String> {
+    static CONTENTS: OnceLock<std::result::Result<String, io::ErrorKind>> = OnceLock::new();
 
-        // get the title via split on ": " from descriptions
+    CONTENTS
+        .get_or_init(|| {
+            let mut contents = String::new();
+            io::stdin()
+                .read_to_string(&mut contents)
+                .map(|_| contents)
+                .map_err(|e| e.kind())
+        })
+        .clone()
+        .map_err(io::Error::from)
+}
 
-        let parts: Vec<String> = self
-            .description
-            .split(": ")
-            .map(|s| s.to_string())
-            .collect();
-        let panic_msg = "Could not parse doc header. Ensure your header follows the `title: description' format".to_string();
-        let title = parts.first().expect(&panic_msg).to_owned();
-        let real_description = parts.get(1).expect(&panic_msg).to_owned();
+/// Write an entire output file
+/// A file that already holds `contents` is left alone,
+/// so its modification time only changes along with it
+fn write_file(f: &str, contents: &str) -> Result<()> {
+    if fs::read(f).is_ok_and(|old| old == contents.as_bytes()) {
+        return Ok(());
+    }
+
+    fs::write(f, contents).map_err(|source| Error::Io {
+        path: f.to_string(),
+        source,
+    })
+}
 
-        md.push_str("## ");
-        md.push_str(&title);
-        md.push('\n');
-        md.push_str(&real_description);
-        md.push('\n');
+/// Parse a file as `language`, reporting every block that failed to parse
+/// Returns the blocks that did parse, and whether any failed
+fn parse_file(f: &str, contents: &str, language: &Language) -> (Vec<DocComment>, bool) {
+    let mut failed = false;
 
-        if !self.params.is_empty() {
-            md.push_str("### Parameters: ");
-            md.push('\n');
+    let docs = language
+        .parse(contents)
+        .into_iter()
+        .filter_map(|doc| {
+            doc.inspect_err(|e| {
+                report::error(f, contents, e);
+                failed = true;
+            })
+            .ok()
+        })
+        .collect();
+
+    (docs, failed)
+}
+
+/// The cache to start from, empty if caching is turned off
+fn load_cache(args: &GenArgs) -> Cache {
+    if args.no_cache {
+        Cache::default()
+    } else {
+        Cache::load(CACHE_FILE)
+    }
+}
+
+/// `marker gen`: render documentation for every input
+/// Errors are reported as they happen, so one bad file or doc comment
+/// does not stop the rest from rendering
+/// Returns how it went
+fn generate(args: &GenArgs) -> Outcome {
+    if args.version_dir.is_some() {
+        return generate_versioned(args);
+    }
+
+    let files = input_files(&args.input);
+    generate_files(args, &files, &files, &mut load_cache(args))
+}
+
+/// `marker gen --version-dir`: render pages into a directory named after the version
+/// inside the output directory, then update the versions beside it
+/// Returns how it went
+fn generate_versioned(args: &GenArgs) -> Outcome {
+    let Some(dir) = args.out_dir() else {
+        eprintln!("error: --version-dir needs a directory of pages, pass it with --out-dir DIR");
+        return Outcome::Failed;
+    };
+
+    let version = args
+        .version_dir
+        .clone()
+        .filter(|v| !v.is_empty())
+        .or_else(|| args.input.package.as_ref().map(|p| p.version.clone()));
+    let Some(version) = version.filter(|v| version_numbers(v).is_some()) else {
+        eprintln!(
+            "error: --version-dir needs a version like 1.2.0, from --version-dir=VERSION or typst.toml"
+        );
+        return Outcome::Failed;
+    };
+
+    let mut versioned = args.clone();
+    versioned.out_dir = Some(Path::new(dir).join(&version).display().to_string());
+    versioned.output = None;
+
+    let files = input_files(&versioned.input);
+    let mut outcome = generate_files(&versioned, &files, &files, &mut load_cache(args));
+
+    if let Err(e) = versions::update(Path::new(dir), &version) {
+        eprintln!("error: could not update the versions in {dir}: {e}");
+        outcome |= Outcome::IoError;
+    }
 
-            self.params.iter().for_each(|p| {
-                md.push_str(&p.markdown());
-            });
+    outcome
+}
+
+/// Render documentation for the `changed` files out of every input in `files`
+/// Output written beside each source is only rewritten for changed files,
+/// combined output needs every file, but unchanged ones come out of `cache`
+/// Returns how it went
+fn generate_files(
+    args: &GenArgs,
+    files: &[String],
+    changed: &[String],
+    cache: &mut Cache,
+) -> Outcome {
+    let mut config = args.config();
+
+    if config.format.is_directory() && args.out_dir().is_none() && !args.next_to_source {
+        eprintln!("error: this --format writes a directory of pages, pass it with -o DIR");
+        return Outcome::Failed;
+    }
+
+    if args.single_file.is_some()
+        && !matches!(config.format, Format::Markdown | Format::Html | Format::Rst)
+    {
+        eprintln!("error: --single-file needs --format markdown, html or rst");
+        return Outcome::Failed;
+    }
+
+    // there is no source file to put docs beside or name a page after
+    if files.iter().any(|f| f == STDIN) && (args.next_to_source || args.out_dir().is_some()) {
+        eprintln!(
+            "error: standard in can't be documented with --next-to-source or a directory of pages"
+        );
+        return Outcome::Failed;
+    }
+
+    // every title is needed up front to link mentions of functions in other files,
+    // which may be on another page, and for the contents of a single file
+    let paged = args.next_to_source || args.out_dir().is_some();
+    let titles: Vec<(String, Vec<String>)> = files
+        .iter()
+        .filter_map(|f| {
+            Some((
+                f.clone(),
+                args.input
+                    .language(f)
+                    .titles(&read_file(f).ok()?)
+                    .into_iter()
+                    // the definition isn't parsed yet, so a private function is told by its title
+                    .filter(|t| {
+                        config.includes(t) && (config.document_private || !t.starts_with('_'))
+                    })
+                    .collect(),
+            ))
+        })
+        .collect();
+
+    if let Some(path) = &args.search_index {
+        config.search = Some(relative_link(Path::new(path), &docs_root(args)));
+    }
+
+    let mut out: Vec<(String, String)> = Vec::new();
+    let mut stats = Stats::default();
+    let mut parsed: Vec<(String, Option<PathBuf>, Vec<DocComment>)> = Vec::new();
+    let indexed = args.toc_index.is_some() || args.index.is_some() || args.search_index.is_some();
+    let mut outcome = Outcome::Clean;
+
+    // 0 threads is one per core
+    let pool = match ThreadPoolBuilder::new()
+        .num_threads(args.jobs.unwrap_or(0))
+        .build()
+    {
+        Ok(pool) => pool,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return Outcome::Failed;
+        }
+    };
+
+    // files are rendered in parallel, but collected back into input order
+    let shared: &Cache = cache;
+    let outputs: Vec<FileOutput> = pool.install(|| {
+        files
+            .par_iter()
+            // the indexes list every file, so none can be skipped
+            .filter(|f| !args.next_to_source || changed.contains(*f) || indexed)
+            .map(|f| render_file(args, &config, f, &titles, paged, shared, indexed))
+            .collect()
+    });
+
+    for output in outputs {
+        outcome |= output.outcome;
+        if let Some(file_stats) = output.stats {
+            stats.files.push(file_stats);
         }
+        if let Some((location, docs)) = output.parsed {
+            parsed.push((output.file.clone(), location, docs));
+        }
+        if let (Some(rendered), Some(hash)) = (&output.rendered, output.hash) {
+            cache.insert(&output.file, hash, rendered.clone(), &output.warnings);
+        }
+        if let Some(rendered) = output.rendered
+            && !args.next_to_source
+        {
+            out.push((output.file, rendered));
+        }
+    }
 
-        if let Some(ret) = self.return_type.clone() {
-            md.push_str("### Returns: ");
-            md.push('\n');
-            md.push_str(&ret.markdown());
+    if let Some(path) = &args.stats_json
+        && let Err(e) = write_file(path, &stats.json())
+    {
+        eprintln!("error: {e}");
+        outcome |= Outcome::IoError;
+    }
+
+    if let Some(path) = &args.sidebars
+        && let Err(e) = write_file(path, &sidebar(&out))
+    {
+        eprintln!("error: {e}");
+        outcome |= Outcome::IoError;
+    }
+
+    if let Some(dir) = args.out_dir() {
+        outcome |= write_pages(dir, &pages(&out, &config));
+    } else if let Some(path) = &args.single_file {
+        let single = single_file(&out, &titles, &config).unwrap_or_default();
+        if let Err(e) = write_file(path, &single) {
+            eprintln!("error: {e}");
+            outcome |= Outcome::IoError;
         }
-        md.push('\n');
+    } else if let Some(path) = &args.inject {
+        outcome |= write_injected(path, &combine(&out, &config));
+    } else {
+        outcome |= write_output(args, &combine(&out, &config));
+    }
 
-        md
+    // after the pages, which may create their directory
+    if let Some(path) = &args.toc_index {
+        outcome |= write_index(path, &parsed, toc_index);
     }
+    if let Some(path) = &args.index {
+        outcome |= write_index(path, &parsed, symbol_index);
+    }
+    if let Some(path) = &args.search_index {
+        outcome |= write_index(path, &parsed, search_index);
+    }
+
+    if !args.no_cache {
+        cache.save(CACHE_FILE).unwrap_or_else(|e| {
+            eprintln!("warning: could not write {CACHE_FILE}: {e}");
+        });
+    }
+
+    outcome
 }
 
-/// Structure for an @return piece of a doc comment
-#[derive(Debug, Clone)]
-struct Return {
-    data_type: String,
-    description: String,
+/// What rendering a single file produced, merged in input order once every file is done
+#[derive(Debug, Default)]
+struct FileOutput {
+    file: String,
+    /// `None` if the file couldn't be read
+    rendered: Option<String>,
+    /// Hash to cache the output under, set when it was rendered without errors
+    hash: Option<u64>,
+    /// Warnings emitted while rendering, cached to be replayed with the output
+    warnings: Vec<String>,
+    stats: Option<FileStats>,
+    /// Where the docs are written and the doc comments, for the indexes
+    parsed: Option<(Option<PathBuf>, Vec<DocComment>)>,
+    outcome: Outcome,
 }
 
-impl Markdownable for Return {
-    /// Convert a Return struct into its markdown representation
-    /// Into the form: "`type` description"
-    fn markdown(&self) -> String {
-        format!("`{}`: {} \n", self.data_type, self.description)
+/// Render the docs of `file`, reusing `cache` when it hasn't changed,
+/// and write them beside it with --next-to-source
+fn render_file(
+    args: &GenArgs,
+    config: &Config,
+    file: &str,
+    titles: &[(String, Vec<String>)],
+    paged: bool,
+    cache: &Cache,
+    indexed: bool,
+) -> FileOutput {
+    let mut output = FileOutput {
+        file: file.to_string(),
+        ..Default::default()
+    };
+
+    let contents = match read_file(file) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("error: {e}");
+            output.outcome = Outcome::IoError;
+            return output;
+        }
+    };
+
+    let mut config = config.clone();
+    config.links = links(args, &config, file, titles, paged);
+
+    // only re-parse files that changed since the last run
+    // the options are hashed too, since they change the output
+    // stats and the indexes need every file parsed, so they skip the cache
+    let images = (&args.example_images, &args.example_image_format);
+    let language = args.input.lang.as_ref().map(|l| &l.name);
+    // strict runs report warnings cached by other runs
+    let strict = args.strict;
+    let hash = hash_contents(&format!(
+        "{config:?}\n{images:?}\n{language:?}\n{strict}\n{contents}"
+    ));
+    let cached = cache
+        .get(file, hash)
+        .filter(|_| args.stats_json.is_none() && !indexed)
+        .map(|(rendered, warnings)| {
+            // an unchanged file still has the warnings it had last time
+            replay_warnings(warnings);
+            rendered.to_string()
+        });
+
+    let rendered = cached.unwrap_or_else(|| {
+        // a file is parsed on a single thread, so its warnings are counted there
+        let warnings_before = thread_warning_count();
+        let ((mut docs, parse_failed), warned) =
+            record_warnings(|| parse_file(file, &contents, &args.input.language(file)));
+        let mut file_outcome = match parse_failed {
+            true => Outcome::ParseError,
+            false => Outcome::Clean,
+        };
+
+        if let Some(dir) = &args.example_images
+            && compile_examples(file, &mut docs, dir, &args.example_image_format)
+        {
+            file_outcome |= Outcome::Failed;
+        }
+
+        let warnings = thread_warning_count() - warnings_before;
+        if args.strict && warnings > 0 {
+            file_outcome |= Outcome::Failed;
+        }
+
+        let mut file_stats = FileStats {
+            path: file.to_string(),
+            // params only found in the definition have no span
+            documented_params: docs
+                .iter()
+                .flat_map(|d| &d.params)
+                .filter(|p| p.span.is_some())
+                .count(),
+            warnings,
+            ..Default::default()
+        };
+        file_stats.count_functions(&contents, &args.input.language(file));
+        output.stats = Some(file_stats);
+
+        docs.retain(|d| config.documents(d));
+        sort(&mut docs, config.sort);
+
+        let (rendered, rendering_warned) = record_warnings(|| render(&docs, &config));
+        output.warnings = [warned, rendering_warned].concat();
+        if indexed {
+            output.parsed = Some((docs_location(args, &config, file), docs));
+        }
+
+        // files with errors are left out of the cache so they get reported again
+        match file_outcome.failed() {
+            true => output.outcome = file_outcome,
+            false => output.hash = Some(hash),
+        }
+        rendered
+    });
+
+    if args.next_to_source {
+        let path = Path::new(file).with_extension(config.format.extension());
+        let written = write_file(
+            &path.display().to_string(),
+            &standalone(file, &rendered, &config),
+        );
+        if let Err(e) = written {
+            eprintln!("error: {e}");
+            output.outcome |= Outcome::IoError;
+        }
     }
+
+    output.rendered = Some(rendered);
+    output
 }
 
-/// Structure for an @param piece of a doc comment
-#[derive(Debug)]
-struct Param {
-    name: String,
-    data_type: Vec<String>,
-    default: Option<String>,
-    description: String,
+/// Where the docs of `file` are written, `None` for standard out
+fn docs_location(args: &GenArgs, config: &Config, file: &str) -> Option<PathBuf> {
+    if args.next_to_source {
+        return Some(Path::new(file).with_extension(config.format.extension()));
+    }
+
+    match args.out_dir() {
+        Some(dir) => Some(Path::new(dir).join(page_path(file, config))),
+        None => args
+            .output
+            .as_deref()
+            .or(args.single_file.as_deref())
+            .or(args.inject.as_deref())
+            .map(PathBuf::from),
+    }
+}
+
+/// The directory pages are written relative to
+/// e.g. the -o DIR of a directory of pages, or the directory of a single output file
+fn docs_root(args: &GenArgs) -> PathBuf {
+    match args.out_dir() {
+        Some(dir) => PathBuf::from(dir),
+        None => args
+            .output
+            .as_deref()
+            .or(args.single_file.as_deref())
+            .or(args.inject.as_deref())
+            .and_then(|p| Path::new(p).parent())
+            .map_or(PathBuf::new(), Path::to_path_buf),
+    }
 }
 
-impl Markdownable for Param {
-    /// Convert a parameter into its markdown representation
-    /// Into the form: "name: `type` description"
-    fn markdown(&self) -> String {
-        let data_type_str = self.data_type.join(" | ");
+/// The page every documented function is on, by title, relative to the page of `file`
+/// Without a page per file, everything is on the same page
+/// Titles documented in `file` itself stay on its page
+fn links(
+    args: &GenArgs,
+    config: &Config,
+    file: &str,
+    titles: &[(String, Vec<String>)],
+    paged: bool,
+) -> BTreeMap<String, String> {
+    let here = docs_location(args, config, file).filter(|_| paged);
+    let dir = here
+        .as_deref()
+        .and_then(Path::parent)
+        .unwrap_or(Path::new(""));
 
-        let mut default_str = String::new();
-        if let Some(def) = self.default.clone() {
-            default_str = format!("(default: {})", def);
+    // this file's own titles go first, so they win over the same title elsewhere
+    let ordered = titles
+        .iter()
+        .filter(|(f, _)| f == file)
+        .chain(titles.iter().filter(|(f, _)| f != file));
+
+    let mut links = BTreeMap::new();
+    for (f, file_titles) in ordered {
+        let page = match docs_location(args, config, f) {
+            Some(there) if f != file && here.is_some() => relative_link(&there, dir),
+            _ => String::new(),
         };
+        file_titles.iter().for_each(|t| {
+            links.entry(t.clone()).or_insert_with(|| page.clone());
+        });
+    }
+
+    links
+}
+
+/// A file's path, a link to its docs and its doc comments, as the indexes take them
+type Linked<'a> = (String, String, &'a [DocComment]);
+
+/// Write an index of the parsed files, given as (path, where its docs are, docs),
+/// to `path` with links relative to it
+/// Returns `IoError` if it failed
+fn write_index(
+    path: &str,
+    parsed: &[(String, Option<PathBuf>, Vec<DocComment>)],
+    index: fn(&[Linked]) -> String,
+) -> Outcome {
+    let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    let files: Vec<Linked> = parsed
+        .iter()
+        .map(|(f, location, docs)| {
+            let page = location
+                .as_deref()
+                .map(|p| relative_link(p, dir))
+                .unwrap_or_default();
+            (f.clone(), page, docs.as_slice())
+        })
+        .collect();
+
+    match write_file(path, &index(&files)) {
+        Ok(()) => Outcome::Clean,
+        Err(e) => {
+            eprintln!("error: {e}");
+            Outcome::IoError
+        }
+    }
+}
 
-        format!(
-            "{}: `{}` {} {} \n \n",
-            self.name, data_type_str, default_str, self.description
+/// A link from a file in `dir` to `path`, both relative to the same directory,
+/// e.g. "docs/lib/exam.md" from "docs" -> "lib/exam.md"
+fn relative_link(path: &Path, dir: &Path) -> String {
+    let path: Vec<_> = path.components().collect();
+    let dir: Vec<_> = dir.components().collect();
+    let common = path.iter().zip(&dir).take_while(|(a, b)| a == b).count();
+
+    let parts: Vec<String> = dir[common..]
+        .iter()
+        .map(|_| "..".to_string())
+        .chain(
+            path[common..]
+                .iter()
+                .map(|c| c.as_os_str().to_string_lossy().to_string()),
         )
+        .collect();
+    parts.join("/")
+}
+
+/// Write combined output to -o or standard out
+/// Returns `IoError` if it failed
+fn write_output(args: &GenArgs, out: &str) -> Outcome {
+    match &args.output {
+        _ if args.next_to_source => Outcome::Clean,
+        Some(path) => match write_file(path, out) {
+            Ok(()) => Outcome::Clean,
+            Err(e) => {
+                eprintln!("error: {e}");
+                Outcome::IoError
+            }
+        },
+        None => {
+            print!("{out}");
+            Outcome::Clean
+        }
     }
 }
 
-/// Parses an entire typst file (document) into a `Vec<DocComment>`
-fn parse_document(input: &str) -> Vec<DocComment> {
-    let mut comment_chunks = Vec::new();
-    let mut cur = String::new();
+/// Write combined output into the marked region of the file at `path`
+/// Returns `IoError` if the file couldn't be read or written, `Failed` if it has no region
+fn write_injected(path: &str, out: &str) -> Outcome {
+    let document = match read_file(path) {
+        Ok(document) => document,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return Outcome::IoError;
+        }
+    };
 
-    for line in input.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("///") {
-            // strip the /// for parsing
-            // every doc comment MUST have a /// anyway
-            cur.push_str(trimmed.trim_start_matches("///").trim());
-            cur.push('\n');
+    let Some(injected) = inject(&document, out) else {
+        eprintln!("error: {path} needs a {INJECT_START} comment followed by {INJECT_END}");
+        return Outcome::Failed;
+    };
 
-        // should handle breaks in between the doc comments
-        } else if !cur.is_empty() {
-            comment_chunks.push(cur.clone());
-            cur.clear();
+    match write_file(path, &injected) {
+        Ok(()) => Outcome::Clean,
+        Err(e) => {
+            eprintln!("error: {e}");
+            Outcome::IoError
         }
     }
+}
 
-    comment_chunks
-        .into_iter()
-        .map(|block| parse_block(&block))
-        .collect()
+/// Write pages, given as (path, contents), into `dir`
+/// Returns `IoError` if any failed
+fn write_pages(dir: &str, pages: &[(PathBuf, String)]) -> Outcome {
+    let mut outcome = Outcome::Clean;
+
+    for (path, contents) in pages {
+        let path = Path::new(dir).join(path);
+        if let Some(parent) = path.parent()
+            && let Err(e) = fs::create_dir_all(parent)
+        {
+            eprintln!("error: {}: {e}", parent.display());
+            outcome = Outcome::IoError;
+            continue;
+        }
+
+        if let Err(e) = write_file(&path.display().to_string(), contents) {
+            eprintln!("error: {e}");
+            outcome = Outcome::IoError;
+        }
+    }
+
+    outcome
+}
+
+/// `marker check`: parse every input and report problems with their doc comments
+/// Returns how it went, failing on warnings too, unless they are in the baseline
+fn check(args: &CheckArgs) -> Outcome {
+    let mut baseline = match &args.baseline {
+        Some(path) => match Baseline::load(path) {
+            Ok(baseline) => baseline,
+            Err(e) => {
+                eprintln!("error: could not read the baseline {path}: {e}");
+                return Outcome::IoError;
+            }
+        },
+        None => Baseline::default(),
+    };
+    let mut written = Baseline::default();
+
+    let files = input_files(&args.input);
+    let mut outcome = Outcome::Clean;
+
+    let mut findings = 0;
+    let mut known = 0;
+    let mut parsed = Vec::new();
+
+    let format = args.output_format.as_str();
+    let mut sarif = Sarif::default();
+
+    files.iter().for_each(|f| match read_file(f) {
+        Ok(contents) => {
+            // warnings from parsing are reported with the file they are in, like the lints
+            let (docs, problems) = collect_findings(|| args.input.language(f).parse(&contents));
+            let docs: Vec<DocComment> = docs
+                .into_iter()
+                .filter_map(|doc| {
+                    doc.inspect_err(|e| {
+                        match format {
+                            "github" => {
+                                report::annotation("error", f, e.span(), None, &e.to_string())
+                            }
+                            "sarif" => sarif.add("error", f, e.span(), PARSE_ERROR, &e.to_string()),
+                            _ => report::error(f, &contents, e),
+                        }
+                        outcome |= Outcome::ParseError;
+                    })
+                    .ok()
+                })
+                .collect();
+            parsed.push((f, docs, problems));
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            outcome |= Outcome::IoError;
+        }
+    });
+
+    // @see may point at a function in any of the files
+    let titles: Vec<&str> = parsed
+        .iter()
+        .flat_map(|(_, docs, _)| docs.iter().map(|d| d.title.as_str()))
+        .collect();
+
+    for (f, docs, problems) in &parsed {
+        // the tags should agree with the code they document
+        let version = args.input.package.as_ref().map(|p| p.version.as_str());
+        let lints = docs.iter().flat_map(|d| {
+            let since = version.map(|v| lint_since(d, v)).unwrap_or_default();
+            [lint(d), lint_choices(d), lint_see(d, &titles), since].concat()
+        });
+
+        for finding in problems.iter().cloned().chain(lints) {
+            let entry = Known::new(f, &finding);
+            if args.write_baseline.is_some() {
+                written.findings.push(entry);
+                continue;
+            }
+            if baseline.take(&entry) {
+                known += 1;
+                continue;
+            }
+
+            let span = finding.span;
+            match format {
+                "github" => report::annotation(
+                    "warning",
+                    f,
+                    Some(span),
+                    Some(finding.rule),
+                    &finding.message,
+                ),
+                "sarif" => sarif.add("warning", f, Some(span), finding.rule, &finding.message),
+                _ => eprintln!("warning: {f}:{}:{}: {finding}", span.line, span.column),
+            }
+            findings += 1;
+        }
+    }
+
+    if format == "sarif" {
+        println!("{}", sarif.json());
+    }
+
+    if let Some(path) = &args.write_baseline {
+        if let Err(e) = written.write(path) {
+            eprintln!("error: could not write the baseline {path}: {e}");
+            outcome |= Outcome::IoError;
+            return outcome;
+        }
+        eprintln!("wrote {} warning(s) to {path}", written.findings.len());
+    }
+
+    let warnings = warning_count() + findings;
+    if warnings > 0 {
+        eprintln!("{warnings} warning(s) in {} file(s)", files.len());
+    }
+    if known > 0 {
+        eprintln!("{known} warning(s) left out by the baseline");
+    }
+
+    if warnings > 0 {
+        outcome |= Outcome::Failed;
+    }
+    outcome
 }
 
-/// Parses a single doc comment block into a DocComment
-fn parse_block(block: &str) -> DocComment {
-    let lines = block.lines();
+/// `marker fix`: rewrite the doc comments of every typst input in canonical form
+/// Returns how it went, failing with `--check` when any file isn't in canonical form
+fn fix(args: &FixArgs) -> Outcome {
+    let mut outcome = Outcome::Clean;
+
+    for f in input_files(&args.input) {
+        if args.input.language(&f).name != "typst" {
+            eprintln!("warning: skipping {f}, only typst doc comments can be fixed");
+            continue;
+        }
+
+        let contents = match read_file(&f) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("error: {e}");
+                outcome |= Outcome::IoError;
+                continue;
+            }
+        };
+
+        let fixed = marker_typ::fix(&contents, args.wrap);
+        if f == STDIN {
+            print!("{fixed}");
+        } else if fixed == contents {
+            continue;
+        } else if args.check {
+            println!("{f}");
+            outcome |= Outcome::Failed;
+        } else if let Err(e) = write_file(&f, &fixed) {
+            eprintln!("error: {e}");
+            outcome |= Outcome::IoError;
+        } else {
+            eprintln!("fixed {f}");
+        }
+    }
+
+    outcome
+}
+
+/// `marker scaffold`: insert a skeleton doc comment above every public typst function
+/// of the inputs without one
+/// Returns how it went
+fn scaffold(args: &ScaffoldArgs) -> Outcome {
+    let mut outcome = Outcome::Clean;
+
+    for f in input_files(&args.input) {
+        if args.input.language(&f).name != "typst" {
+            eprintln!("warning: skipping {f}, only typst functions can be scaffolded");
+            continue;
+        }
+
+        let contents = match read_file(&f) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("error: {e}");
+                outcome |= Outcome::IoError;
+                continue;
+            }
+        };
+
+        let (scaffolded, functions) = marker_typ::scaffold(&contents);
+        if f == STDIN {
+            print!("{scaffolded}");
+        } else if functions.is_empty() {
+            continue;
+        } else if let Err(e) = write_file(&f, &scaffolded) {
+            eprintln!("error: {e}");
+            outcome |= Outcome::IoError;
+        } else {
+            eprintln!("{f}: scaffolded {}", functions.join(", "));
+        }
+    }
+
+    outcome
+}
+
+/// `marker coverage`: print how many public functions of each file have a doc comment
+/// Returns how it went, failing when total coverage is under `--fail-under`
+fn coverage(args: &CoverageArgs) -> Outcome {
+    let mut stats = Stats::default();
+    let mut outcome = Outcome::Clean;
+
+    for f in input_files(&args.input) {
+        match read_file(&f) {
+            Ok(contents) => {
+                let language = args.input.language(&f);
+                let mut file_stats = FileStats {
+                    path: f,
+                    ..Default::default()
+                };
+                file_stats.count_functions(&contents, &language);
+                stats.files.push(file_stats);
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                outcome |= Outcome::IoError;
+            }
+        }
+    }
+
+    print!("{}", stats.coverage_table());
+
+    if let Some(path) = &args.badge
+        && let Err(e) = write_file(path, &stats.badge())
+    {
+        eprintln!("error: {e}");
+        outcome |= Outcome::IoError;
+    }
 
-    // asserting that description is everything before the first @ tag
-    let description = lines
+    let total = stats.total().coverage();
+    if let Some(threshold) = args.fail_under
+        && total < threshold
+    {
+        eprintln!("coverage {total:.1}% is under {threshold}%");
+        outcome |= Outcome::Failed;
+    }
+
+    outcome
+}
+
+/// `marker diff`: print how the documented functions changed between two git revisions
+/// Returns how it went, whatever changed
+fn diff(args: &DiffArgs) -> Outcome {
+    let (old, mut outcome) = docs_at(&args.input, Some(&args.from));
+    let (new, new_outcome) = docs_at(&args.input, Some(&args.to));
+    outcome |= new_outcome;
+
+    // a side that couldn't be read would show up as every function added or removed
+    if outcome == Outcome::IoError {
+        return outcome;
+    }
+
+    let api = marker_typ::diff(&old, &new);
+    match args.json {
+        true => println!(
+            "{}",
+            serde_json::to_string_pretty(&api).expect("diffs are valid json")
+        ),
+        false => print!("{api}"),
+    }
+
+    outcome
+}
+
+/// `marker changelog`: print a markdown changelog section of how the documented functions
+/// changed since a git revision
+/// Returns how it went
+fn changelog(args: &ChangelogArgs) -> Outcome {
+    let (old, mut outcome) = docs_at(&args.input, Some(&args.since));
+    let (new, new_outcome) = docs_at(&args.input, args.until.as_deref());
+    outcome |= new_outcome;
+
+    if outcome == Outcome::IoError {
+        return outcome;
+    }
+
+    let heading = args
+        .heading
         .clone()
-        .take_while(|l| !l.starts_with('@'))
-        .collect::<Vec<_>>()
-        .join(" ")
-        .trim()
-        .to_string();
-
-    let mut params = Vec::new();
-    let mut return_type = None;
-
-    let param_re = Regex::new(
-        r"(?x)
-        @param\s+
-        (?P<name>\w+)\s+
-        (?P<type>\[[^\]]+\]|\S+)\s*   # matches [a | b] or single word
-        (?:=\s*(?P<default>\S+))?\s*  # optional default after '='
-        (?P<desc>.*)                  # everything else is description
-    ",
-    )
-    .unwrap();
-
-    let return_re = Regex::new(r"@return\s+(?P<type>\S+)\s+(?P<desc>.*)").unwrap();
-
-    for line in lines {
-        if let Some(caps) = param_re.captures(line) {
-            // strip the [ ]s from the types
-            // but leave the |s
-            let type_raw = caps["type"].trim_matches(|c| c == '[' || c == ']');
-
-            // split on |s map to string and collect
-            let data_types = type_raw.split('|').map(|s| s.trim().to_string()).collect();
-
-            let p = Param {
-                name: caps["name"].to_string(),
-                data_type: data_types,
-                default: caps.name("default").map(|m| m.as_str().to_string()),
-                description: caps["desc"].trim().to_string(),
-            };
+        .or_else(|| args.input.package.as_ref().map(|p| p.version.clone()))
+        .unwrap_or_else(|| "Unreleased".to_string());
+    print!("{}", marker_typ::diff(&old, &new).changelog(&heading));
+
+    outcome
+}
 
-            params.push(p);
-        } else if let Some(caps) = return_re.captures(line) {
-            return_type = Some(Return {
-                data_type: caps["type"].to_string(),
-                description: caps["desc"].trim().to_string(),
-            });
+/// `marker semver`: check that the version in typst.toml went up far enough for the changes
+/// to the documented functions since a git revision, reporting every breaking change
+/// Returns how it went, failing when the bump is too small
+fn semver(args: &SemverArgs) -> Outcome {
+    let (old, mut outcome) = docs_at(&args.input, Some(&args.since));
+    let (new, new_outcome) = docs_at(&args.input, args.until.as_deref());
+    outcome |= new_outcome;
+
+    let old_version = version_at(&args.manifest, Some(&args.since));
+    let new_version = version_at(&args.manifest, args.until.as_deref());
+    let (Some(old_version), Some(new_version)) = (old_version, new_version) else {
+        eprintln!(
+            "error: marker semver needs a version in {} at {} and at {}",
+            args.manifest.display(),
+            args.since,
+            args.until.as_deref().unwrap_or("the working tree")
+        );
+        outcome |= Outcome::Failed;
+        return outcome;
+    };
+
+    if outcome == Outcome::IoError {
+        return outcome;
+    }
+
+    let api = marker_typ::diff(&old, &new);
+    let needed = api.severity();
+
+    let breaking = api.breaking();
+    if !breaking.is_empty() {
+        println!("breaking changes since {}:", args.since);
+        breaking.iter().for_each(|reason| println!("  {reason}"));
+    }
+
+    match bump(&old_version, &new_version) {
+        Some(bumped) if bumped >= needed => {
+            println!(
+                "{old_version} -> {new_version} is a {bumped} bump, enough for {needed} changes"
+            );
+        }
+        // a release that changes nothing can keep its version
+        None if old_version == new_version && api.is_empty() => {
+            println!(
+                "no changes to the documented functions since {}",
+                args.since
+            );
+        }
+        Some(bumped) => {
+            eprintln!(
+                "error: {old_version} -> {new_version} is a {bumped} bump, but the documented functions have {needed} changes"
+            );
+            outcome |= Outcome::Failed;
+        }
+        None => {
+            eprintln!(
+                "error: {old_version} -> {new_version} is not a bump, but the documented functions have {needed} changes"
+            );
+            outcome |= Outcome::Failed;
         }
-        // Descriptions should have been parsed disparately
-        // only check if we captured the param lines, or the SINGLE return line
     }
 
-    DocComment {
-        description,
-        params,
-        return_type,
+    outcome
+}
+
+/// The version of the package in `manifest` at the git revision `rev`,
+/// or in the working tree for `None`
+fn version_at(manifest: &Path, rev: Option<&str>) -> Option<String> {
+    let package = match rev {
+        Some(rev) => {
+            let path = manifest.display().to_string();
+            let contents = git::show(rev, &path).ok()?;
+            parse_package(&contents, &format!("{path} at {rev}"))?
+        }
+        None => read_package(manifest)?,
+    };
+
+    Some(package.version)
+}
+
+/// Parse the doc comments of every input at the git revision `rev`,
+/// or in the working tree for `None`
+/// Returns them along with how it went
+fn docs_at(input: &InputArgs, rev: Option<&str>) -> (Vec<DocComment>, Outcome) {
+    let files = match rev {
+        Some(rev) => match input_files_at(input, rev) {
+            Ok(files) => files,
+            Err(e) => {
+                eprintln!("error: could not list the files at {rev}: {e}");
+                return (Vec::new(), Outcome::IoError);
+            }
+        },
+        None => input_files(input),
+    };
+
+    let mut outcome = Outcome::Clean;
+    let mut docs = Vec::new();
+
+    for f in &files {
+        let contents = match rev {
+            Some(rev) => git::show(rev, f).map_err(|e| format!("{f} at {rev}: {e}")),
+            None => read_file(f).map_err(|e| e.to_string()),
+        };
+
+        match contents {
+            Ok(contents) => {
+                let (file_docs, parse_failed) = parse_file(f, &contents, &input.language(f));
+                if parse_failed {
+                    outcome |= Outcome::ParseError;
+                }
+                docs.extend(file_docs);
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                outcome |= Outcome::IoError;
+            }
+        }
     }
+
+    (docs, outcome)
 }
 
-fn print_help() {
-    println!("Usage: ");
-    println!("marker [FILES...]");
+/// Every file named by the inputs at the git revision `rev`
+/// Directories are searched for every file matching the glob, like `input_files`
+fn input_files_at(input: &InputArgs, rev: &str) -> io::Result<Vec<String>> {
+    let files = git::files(rev, &input.inputs)?;
+
+    let matcher = glob_set(input.glob());
+    let named = |f: &str| {
+        input.inputs.iter().any(|i| {
+            let relative = match i.trim_start_matches("./").trim_end_matches('/') {
+                "." | "" => Some(f),
+                dir if f == dir => return true,
+                dir => f.strip_prefix(dir).and_then(|r| r.strip_prefix('/')),
+            };
+            relative.is_some_and(|r| matcher.is_match(r))
+        })
+    };
+
+    Ok(files.into_iter().filter(|f| named(f)).collect())
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect::<Vec<String>>().split_off(1);
+/// `marker workspace`: document every package with a typst.toml under the inputs
+/// into a directory of the output named after it, and write an index linking to each
+/// Returns how it went
+fn workspace(args: &WorkspaceArgs) -> Outcome {
+    let gen_args = &args.gen_args;
+    let Some(dir) = gen_args.out_dir.as_deref().or(gen_args.output.as_deref()) else {
+        eprintln!("error: marker workspace writes a directory per package, pass it with -o DIR");
+        return Outcome::Failed;
+    };
+
+    let mut packages: Vec<(PathBuf, Package)> = gen_args
+        .input
+        .inputs
+        .iter()
+        .flat_map(|i| walk(Path::new(i), MANIFEST_GLOB))
+        .filter_map(|manifest| {
+            let root = manifest.parent().unwrap_or(Path::new("")).to_path_buf();
+            Some((root, read_package(&manifest)?))
+        })
+        .collect();
+    packages.sort_by(|(_, a), (_, b)| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+    if packages.is_empty() {
+        eprintln!("error: no typst.toml found under the inputs");
+        return Outcome::Failed;
+    }
+
+    let mut cache = load_cache(gen_args);
+    let mut outcome = Outcome::Clean;
+    let mut index = Vec::new();
+
+    for (root, package) in &packages {
+        // several versions of a package get a directory each
+        let versions = packages
+            .iter()
+            .filter(|(_, p)| p.name == package.name)
+            .count();
+        let subdir = match versions {
+            1 => package.name.clone(),
+            _ => format!("{}/{}", package.name, package.version),
+        };
+
+        let mut package_args = gen_args.clone();
+        package_args.input.inputs = vec![root.display().to_string()];
+        package_args.input.package = Some(package.clone());
+        package_args.root = Some(root.clone());
+        package_args.out_dir = Some(Path::new(dir).join(&subdir).display().to_string());
+        package_args.output = None;
+        // every package would write these to the same place
+        package_args.toc_index = None;
+        package_args.index = None;
+        package_args.search_index = None;
+        package_args.sidebars = None;
+        package_args.stats_json = None;
+
+        let files = input_files(&package_args.input);
+        outcome |= generate_files(&package_args, &files, &files, &mut cache);
 
-    if args.is_empty() {
-        print_help();
+        let config = Config {
+            format: gen_args.format.unwrap_or_default(),
+            root: Some(root.clone()),
+            ..Default::default()
+        };
+        let entrypoint = root.join(&package.entrypoint).display().to_string();
+        let page = page_path(&entrypoint, &config);
+        let link = format!("{subdir}/{}", page.display()).replace('\\', "/");
+        index.push((package.clone(), link));
+    }
+
+    let config = gen_args.config();
+    let name = match config.format {
+        Format::Html => "index.html",
+        _ => "index.md",
+    };
+    let path = Path::new(dir).join(name).display().to_string();
+    if let Err(e) = write_file(&path, &workspace_index(&index, &config)) {
+        eprintln!("error: {e}");
+        outcome |= Outcome::IoError;
+    }
+
+    outcome
+}
+
+/// `marker watch`: render documentation, then render it again whenever an input changes
+/// Only files that changed are re-parsed and have their output rewritten
+fn watch(args: &WatchArgs) -> ! {
+    watch_inputs(&args.gen_args, || {})
+}
+
+/// `marker serve`: render html docs into the output directory, serve it,
+/// and render again whenever an input changes, reloading open pages
+fn serve(args: &ServeArgs) -> ! {
+    let gen_args = &args.gen_args;
+    let root = gen_args.out_dir().unwrap_or(SERVE_DIR);
+    let builds = Arc::new(Builds::default());
+
+    if let Err(e) = serve::spawn(root, args.port, Arc::clone(&builds)) {
+        eprintln!("error: could not serve on port {}: {e}", args.port);
         exit(1);
     }
+    eprintln!("serving {root} on http://localhost:{}", args.port);
 
-    let help_opts = ["-h", "--h", "--help", "help"];
-    args.iter().for_each(|arg| {
-        if help_opts.contains(&arg.as_str()) {
-            print_help();
-            exit(0);
-        }
+    watch_inputs(gen_args, || builds.bump())
+}
+
+/// How long inputs must go without changing before they're rendered again,
+/// so a save touching a file several times renders it once
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Render documentation, then render it again whenever an input changes,
+/// calling `rendered` after each change
+fn watch_inputs(gen_args: &GenArgs, rendered: impl Fn()) -> ! {
+    let mtime = |f: &str| fs::metadata(f).and_then(|m| m.modified()).ok();
+
+    let (tx, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).unwrap_or_else(|e| {
+        eprintln!("error: could not watch inputs: {e}");
+        exit(1);
     });
+    for input in gen_args.input.inputs.iter().filter(|i| *i != STDIN) {
+        // editors often save by replacing a file, so its directory is watched instead
+        let path = Path::new(input);
+        let (path, mode) = match path.is_dir() {
+            true => (path, RecursiveMode::Recursive),
+            false => match path.parent() {
+                Some(parent) if parent != Path::new("") => (parent, RecursiveMode::NonRecursive),
+                _ => (Path::new("."), RecursiveMode::NonRecursive),
+            },
+        };
+        if let Err(e) = watcher.watch(path, mode) {
+            eprintln!("warning: could not watch {input}: {e}");
+        }
+    }
 
-    args.iter().for_each(|f| {
-        let mut file = File::open(f).unwrap_or_else(|_| {
-            print_help();
-            panic!("Could not read file {f}")
-        });
+    // kept in memory between runs, even with --no-cache,
+    // since an unchanged file renders the same every time
+    let mut cache = load_cache(gen_args);
 
-        let mut contents = String::new();
+    let mut files = input_files(&gen_args.input);
+    let mut last: HashMap<String, Option<SystemTime>> =
+        files.iter().map(|f| (f.clone(), mtime(f))).collect();
+    generate_files(gen_args, &files, &files, &mut cache);
 
-        file.read_to_string(&mut contents).unwrap_or_else(|_| {
-            print_help();
-            panic!("Could not read file {f}")
-        });
+    // errors were already reported, and shouldn't stop the watch
+    loop {
+        // a closed channel means the watcher is gone, and nothing will change again
+        if events.recv().is_err() {
+            exit(1);
+        }
+        while events.recv_timeout(DEBOUNCE).is_ok() {}
 
-        let docs = parse_document(&contents);
+        let now_files = input_files(&gen_args.input);
+        let now: HashMap<String, Option<SystemTime>> =
+            now_files.iter().map(|f| (f.clone(), mtime(f))).collect();
 
-        docs.iter().for_each(|f| {
-            println!("{}", f.markdown());
-        });
+        // new files count as changed, removed files only change the combined output
+        let changed: Vec<String> = now_files
+            .iter()
+            .filter(|f| last.get(*f) != now.get(*f))
+            .cloned()
+            .collect();
+
+        if !changed.is_empty() || now_files != files {
+            if !changed.is_empty() {
+                eprintln!("regenerating {}", changed.join(", "));
+            }
+            generate_files(gen_args, &now_files, &changed, &mut cache);
+            rendered();
+        }
+
+        files = now_files;
+        last = now;
+    }
+}
+
+fn main() {
+    let mut cli = Cli::parse();
+
+    let mut project = Project::load(&cli.config).unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        exit(1);
     });
+    project.package = load_package(&cli.config);
+
+    let input = match &mut cli.command {
+        Some(Command::Gen(args)) => {
+            project.apply(args);
+            &args.input
+        }
+        Some(Command::Check(args)) => {
+            project.apply_input(&mut args.input);
+            &args.input
+        }
+        Some(Command::Coverage(args)) => {
+            project.apply_input(&mut args.input);
+            &args.input
+        }
+        Some(Command::Watch(args)) => {
+            project.apply(&mut args.gen_args);
+            &args.gen_args.input
+        }
+        Some(Command::Serve(args)) => {
+            // always a directory of html pages, so the project's output is left out
+            let gen_args = &mut args.gen_args;
+            gen_args.format = Some(Format::Html);
+            project.apply(gen_args);
+            gen_args.output = None;
+            gen_args.next_to_source = false;
+            gen_args.single_file = None;
+            gen_args
+                .out_dir
+                .get_or_insert_with(|| SERVE_DIR.to_string());
+            &gen_args.input
+        }
+        Some(Command::Workspace(args)) => {
+            // every package gets a directory of pages, so the other outputs are left out
+            let gen_args = &mut args.gen_args;
+            if gen_args.input.inputs.is_empty() && project.inputs.is_empty() {
+                gen_args.input.inputs = vec![".".to_string()];
+            }
+            project.apply(gen_args);
+            gen_args.next_to_source = false;
+            gen_args.single_file = None;
+            gen_args.inject = None;
+            &gen_args.input
+        }
+        Some(Command::Diff(args)) => {
+            if args.input.inputs.is_empty() && project.inputs.is_empty() {
+                args.input.inputs = vec![".".to_string()];
+            }
+            project.apply_input(&mut args.input);
+            &args.input
+        }
+        Some(Command::Changelog(args)) => {
+            if args.input.inputs.is_empty() && project.inputs.is_empty() {
+                args.input.inputs = vec![".".to_string()];
+            }
+            project.apply_input(&mut args.input);
+            &args.input
+        }
+        Some(Command::Scaffold(args)) => {
+            project.apply_input(&mut args.input);
+            &args.input
+        }
+        Some(Command::Fix(args)) => {
+            project.apply_input(&mut args.input);
+            &args.input
+        }
+        Some(Command::Semver(args)) => {
+            if args.input.inputs.is_empty() && project.inputs.is_empty() {
+                args.input.inputs = vec![".".to_string()];
+            }
+            project.apply_input(&mut args.input);
+            args.manifest = Path::new(&cli.config).with_file_name(MANIFEST_FILE);
+            &args.input
+        }
+        None => {
+            project.apply(&mut cli.gen_args);
+            &cli.gen_args.input
+        }
+    };
+
+    if input.inputs.is_empty() {
+        eprintln!(
+            "error: no inputs, pass FILES or set `inputs` in {}",
+            cli.config
+        );
+        exit(1);
+    }
+
+    let outcome = match &cli.command {
+        Some(Command::Gen(args)) => generate(args),
+        Some(Command::Check(args)) => check(args),
+        Some(Command::Coverage(args)) => coverage(args),
+        Some(Command::Workspace(args)) => workspace(args),
+        Some(Command::Diff(args)) => diff(args),
+        Some(Command::Changelog(args)) => changelog(args),
+        Some(Command::Semver(args)) => semver(args),
+        Some(Command::Fix(args)) => fix(args),
+        Some(Command::Scaffold(args)) => scaffold(args),
+        Some(Command::Watch(args)) => watch(args),
+        Some(Command::Serve(args)) => serve(args),
+        None => generate(&cli.gen_args),
+    };
+
+    exit(outcome.code());
 }
@@ -0,0 +1,262 @@
+use crate::{Config, DocComment, Example, Param, Return, Section, TagStyle};
+
+/// Trait representing the ability to be converted into a roff man page
+pub trait Manable {
+    fn man(&self, config: &Config) -> String;
+}
+
+/// Section of the manual functions are documented in, as in `man 3typst question`
+pub const MAN_SECTION: &str = "3typst";
+
+impl Manable for DocComment {
+    /// Convert a DocComment into a man page of its own, starting with `.TH`
+    /// Prose is never wrapped, man fills it to the width of the terminal
+    fn man(&self, config: &Config) -> String {
+        let mut man = String::new();
+
+        man.push_str(&format!(".TH {} {MAN_SECTION}\n", self.title));
+        man.push_str(".SH NAME\n");
+        match self.summary() {
+            "" => man.push_str(&format!("{}\n", self.title)),
+            summary => man.push_str(&format!("{} \\- {}\n", self.title, escape(summary))),
+        }
+        man.push_str(&format!(".SH SYNOPSIS\n.B {}\n", escape(&self.signature())));
+
+        for section in config.sections() {
+            match section {
+                Section::Description => {
+                    man.push_str(".SH DESCRIPTION\n");
+
+                    if let Some(metadata) = config.metadata(self) {
+                        man.push_str(&format!(".I {}\n.PP\n", escape(&metadata)));
+                    }
+
+                    let mut badges: Vec<String> = config
+                        .tags_in(TagStyle::Badge)
+                        .flat_map(|t| {
+                            self.tag_values(&t.name).map(move |v| match v {
+                                "" => format!("[{}]", t.label()),
+                                v => format!("[{}: {v}]", t.label()),
+                            })
+                        })
+                        .collect();
+                    let marks = config.marks(self).into_iter();
+                    badges.splice(0..0, marks.map(|label| format!("[{label}]")));
+                    if !badges.is_empty() {
+                        man.push_str(&format!("{}\n.PP\n", escape(&badges.join(" "))));
+                    }
+
+                    for tag in config.tags_in(TagStyle::KeyValue) {
+                        self.tag_values(&tag.name).for_each(|v| {
+                            man.push_str(&format!(
+                                "\\fB{}:\\fR {}\n.PP\n",
+                                escape(&tag.label()),
+                                escape(v)
+                            ));
+                        });
+                    }
+
+                    if let Some(msg) = &self.deprecated {
+                        man.push_str(format!("\\fBDeprecated\\fR {}", escape(msg)).trim_end());
+                        man.push_str("\n.PP\n");
+                    }
+
+                    man.push_str(&paragraphs(&self.description));
+                }
+                Section::Params if !self.params.is_empty() => {
+                    man.push_str(".SH PARAMETERS\n");
+
+                    for (group, params) in config.param_groups(self) {
+                        if let Some(group) = group {
+                            man.push_str(&format!(".SS {}\n", escape(group)));
+                        }
+                        params.iter().for_each(|p| {
+                            man.push_str(&p.man(config));
+                        });
+                    }
+                }
+                Section::Returns if !self.returns.is_empty() => {
+                    man.push_str(".SH RETURN VALUE\n");
+                    match self.returns.as_slice() {
+                        [ret] => man.push_str(&ret.man(config)),
+                        returns => returns.iter().for_each(|r| {
+                            man.push_str(&format!(".IP \\(bu 2\n{}", r.man(config)));
+                        }),
+                    }
+                }
+                Section::Errors if !self.errors.is_empty() => {
+                    man.push_str(".SH ERRORS\n");
+                    man.push_str(&bullets(&self.errors));
+                }
+                Section::Examples if !self.examples.is_empty() => {
+                    man.push_str(".SH EXAMPLES\n");
+
+                    self.examples.iter().for_each(|e| {
+                        man.push_str(&e.man(config));
+                    });
+                }
+                Section::Notes if !self.notes.is_empty() => {
+                    man.push_str(".SH NOTES\n");
+                    man.push_str(&bullets(&self.notes));
+                }
+                Section::SeeAlso if !self.see.is_empty() => {
+                    man.push_str(".SH SEE ALSO\n");
+
+                    let refs: Vec<String> = self
+                        .see
+                        .iter()
+                        .map(|s| format!("\\fB{}\\fR({MAN_SECTION})", escape(s)))
+                        .collect();
+                    man.push_str(&format!("{}\n", refs.join(", ")));
+                }
+                _ => {}
+            }
+        }
+
+        for tag in config.tags_in(TagStyle::Section) {
+            let values: Vec<String> = self.tag_values(&tag.name).map(str::to_string).collect();
+            if !values.is_empty() {
+                man.push_str(&format!(".SH {}\n", tag.label().to_uppercase()));
+                man.push_str(&bullets(&values));
+            }
+        }
+
+        man
+    }
+}
+
+impl Manable for Param {
+    /// Convert a parameter into a tagged paragraph in the form
+    /// name
+    ///        type (optional, default: x) description
+    fn man(&self, config: &Config) -> String {
+        let mut parts = Vec::new();
+
+        // params only found in the definition may have no type
+        if !self.data_type.is_empty() {
+            parts.push(format!("\\fI{}\\fR", escape(&self.types().join(" | "))));
+        }
+        let requirement = config.locale.requirement(self.is_required());
+        match &self.default {
+            Some(def) => parts.push(format!("({requirement}, default: \\fB{}\\fR)", escape(def))),
+            None => parts.push(format!("({requirement})")),
+        }
+        if !self.description.is_empty() {
+            parts.push(escape(&self.description));
+        }
+
+        format!(".TP\n.B {}\n{}\n", self.label(), parts.join(" "))
+    }
+}
+
+impl Manable for Return {
+    /// Convert a Return into the form "type description", after "name: " for a field
+    fn man(&self, _config: &Config) -> String {
+        let field = self
+            .name
+            .as_ref()
+            .map(|n| format!("{}: ", escape(n)))
+            .unwrap_or_default();
+        let line = format!(
+            "{field}\\fI{}\\fR {}",
+            escape(&self.data_type.join(" | ")),
+            escape(&self.description)
+        );
+        format!("{}\n", line.trim_end())
+    }
+}
+
+impl Manable for Example {
+    /// Convert an example into an indented block that is not filled
+    fn man(&self, _config: &Config) -> String {
+        let mut man = String::from(".PP\n.RS 4\n.nf\n");
+        self.code.lines().for_each(|l| {
+            man.push_str(&format!("{}\n", escape_line(&l.replace('\\', "\\e"))));
+        });
+        man.push_str(".fi\n.RE\n");
+
+        if let Some(image) = &self.image {
+            man.push_str(&format!(".PP\nOutput: \\fI{}\\fR\n", escape(image)));
+        }
+
+        man
+    }
+}
+
+/// Split prose on blank lines into paragraphs
+fn paragraphs(text: &str) -> String {
+    let paragraphs: Vec<String> = text
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(escape)
+        .collect();
+
+    match paragraphs.is_empty() {
+        true => String::new(),
+        false => format!("{}\n", paragraphs.join("\n.PP\n")),
+    }
+}
+
+/// A bulleted list with an item per entry
+fn bullets(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|i| format!(".IP \\(bu 2\n{}\n", escape(i)))
+        .collect()
+}
+
+/// Escape the characters roff would read as requests or escapes
+/// `code` spans written like markdown become bold
+pub fn escape(text: &str) -> String {
+    let escaped: String = text
+        .split('`')
+        .enumerate()
+        .map(|(i, part)| {
+            let part = part.replace('\\', "\\e").replace('-', "\\-");
+            match i % 2 {
+                1 => format!("\\fB{part}\\fR"),
+                _ => part,
+            }
+        })
+        .collect();
+
+    escaped
+        .lines()
+        .map(escape_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Keep a line starting with . or ' from being read as a request
+fn escape_line(line: &str) -> String {
+    match line.starts_with(['.', '\'']) {
+        true => format!("\\&{line}"),
+        false => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_block;
+
+    #[test]
+    fn prose_is_escaped() {
+        let doc = parse_block(
+            "f: Pick <b> or *a* | b-c, see `x\\y`\n@param a int .B not a request\n@return int the *sum* | total\n\n.TH not a header\n",
+        )
+        .unwrap();
+        let man = doc.man(&Config::default());
+
+        // only -, \ and a leading . or ' mean anything to roff
+        assert!(man.contains("\nPick <b> or *a* | b\\-c, see \\fBx\\ey\\fR\n"));
+        assert!(man.contains("\\fIint\\fR (required) \\&.B not a request\n"));
+        assert!(man.contains("\n\\fIint\\fR the *sum* | total\n"));
+        assert!(man.lines().any(|l| l == "\\&.TH not a header"));
+        assert!(
+            !man.lines()
+                .any(|l| l.starts_with(".B not") || l.starts_with(".TH not"))
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use std::{fs, io::ErrorKind, path::Path};
+
+use marker_typ::Package;
+use serde::Deserialize;
+
+/// Name of the manifest of a typst package
+pub const MANIFEST_FILE: &str = "typst.toml";
+
+/// The parts of typst.toml `marker` reads, the rest of it is left to typst
+#[derive(Deserialize)]
+struct Manifest {
+    package: Package,
+}
+
+/// Glob finding the manifest of every package in a directory
+pub const MANIFEST_GLOB: &str = "**/typst.toml";
+
+/// Read the package from the typst.toml beside the project file at `project`
+/// `None` without one, and a manifest that doesn't describe a package is ignored with a warning
+pub fn load_package(project: &str) -> Option<Package> {
+    read_package(&Path::new(project).with_file_name(MANIFEST_FILE))
+}
+
+/// Read the package from the manifest at `path`
+/// `None` without one, and a manifest that doesn't describe a package is ignored with a warning
+pub fn read_package(path: &Path) -> Option<Package> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return None,
+        Err(e) => {
+            eprintln!("warning: ignoring {}: {e}", path.display());
+            return None;
+        }
+    };
+
+    parse_package(&contents, &path.display().to_string())
+}
+
+/// Read the package from the `contents` of the manifest at `path`
+/// A manifest that doesn't describe a package is ignored with a warning
+pub fn parse_package(contents: &str, path: &str) -> Option<Package> {
+    match toml::from_str::<Manifest>(contents) {
+        Ok(manifest) => Some(manifest.package),
+        Err(e) => {
+            eprintln!("warning: ignoring {path}: {e}");
+            None
+        }
+    }
+}
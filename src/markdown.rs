@@ -0,0 +1,554 @@
+use std::collections::{BTreeMap, HashSet};
+
+use tera::Context;
+
+use crate::{
+    Config, DocComment, Example, Param, Return, Section, TagStyle, template::fill, types, warn,
+};
+
+/// Trait representing the ability to be converted into markdown
+pub trait Markdownable {
+    fn markdown(&self, config: &Config) -> String;
+}
+
+impl Markdownable for DocComment {
+    /// Convert a DocComment into its markdown representation
+    /// A template in the config decides which parts go where,
+    /// otherwise every part is rendered in the default order
+    fn markdown(&self, config: &Config) -> String {
+        if let Some(template) = &config.template {
+            match fill("function.md", template, &context(self, config)) {
+                Ok(md) => return md + &aliases(self, config),
+                Err(e) => warn(format!(
+                    "rendering {} without the template, {e}",
+                    self.title
+                )),
+            }
+        }
+
+        let mut md = String::new();
+
+        ["header", "metadata", "badges", "key-values", "deprecated"]
+            .into_iter()
+            .chain(config.sections().iter().map(Section::name))
+            .chain(["tags"])
+            .for_each(|name| md.push_str(&part(self, name, config).unwrap_or_default()));
+        md.push('\n');
+        md.push_str(&aliases(self, config));
+
+        md
+    }
+}
+
+/// The introduction to a file from its doc comment, its description and examples
+pub(crate) fn intro(doc: &DocComment, config: &Config) -> String {
+    let mut md = format!("{}\n\n", config.wrap(&prose(&doc.description, config)));
+    doc.examples
+        .iter()
+        .for_each(|e| md.push_str(&format!("{}\n", e.markdown(config))));
+    md
+}
+
+/// Variables of a template rendering `doc`: every part by name, with `_` for `-`,
+/// `tag.NAME` for the values of a tag joined by commas,
+/// and `doc` itself, to loop over its params and the like
+fn context(doc: &DocComment, config: &Config) -> Context {
+    let mut context = Context::new();
+
+    [
+        "title",
+        "slug",
+        "signature",
+        "header",
+        "metadata",
+        "badges",
+        "key-values",
+        "deprecated",
+    ]
+    .into_iter()
+    .chain(Section::ALL.iter().map(Section::name))
+    .chain(["tags"])
+    .for_each(|name| {
+        let part = part(doc, name, config).unwrap_or_default();
+        context.insert(name.replace('-', "_"), &part);
+    });
+
+    let tags: BTreeMap<&str, String> = doc
+        .tags
+        .iter()
+        .map(|t| {
+            (
+                t.name.as_str(),
+                doc.tag_values(&t.name).collect::<Vec<_>>().join(", "),
+            )
+        })
+        .collect();
+    context.insert("tag", &tags);
+    context.insert("doc", doc);
+
+    context
+}
+
+/// A stub section per @alias of `doc`, so links to the other name lead to its docs
+fn aliases(doc: &DocComment, config: &Config) -> String {
+    doc.aliases
+        .iter()
+        .map(|alias| {
+            format!(
+                "{} {alias}\n{} [{}](#{}).\n\n",
+                config.heading(0),
+                config.locale.alias_of(),
+                doc.title,
+                doc.slug
+            )
+        })
+        .collect()
+}
+
+/// A single named part of the markdown for `doc`, `None` if there is no such part
+/// Sections are named as in `section_order`, and `tag.NAME` gives the raw values of a tag
+fn part(doc: &DocComment, name: &str, config: &Config) -> Option<String> {
+    let mut md = String::new();
+
+    if let Some(section) = Section::from_name(name) {
+        match section {
+            Section::Description => {
+                md.push_str(&config.wrap(&prose(&doc.description, config)));
+                md.push('\n');
+            }
+            Section::Params if !doc.params.is_empty() => {
+                md.push_str(&format!(
+                    "{} {}: ",
+                    config.heading(1),
+                    config.label(Section::Params)
+                ));
+                md.push('\n');
+
+                for (group, params) in config.param_groups(doc) {
+                    if let Some(group) = group {
+                        md.push_str(&format!("{} {group}\n", config.heading(2)));
+                    }
+                    if !config.param_list {
+                        let columns = config.locale.param_columns();
+                        md.push_str(&format!("| {} |\n", columns.join(" | ")));
+                        md.push_str("| --- | --- | --- | --- |\n");
+                    }
+
+                    params.iter().for_each(|p| {
+                        md.push_str(&p.markdown(config));
+                    });
+                    if !config.param_list {
+                        md.push('\n');
+                    }
+                }
+            }
+            Section::Returns if !doc.returns.is_empty() => {
+                md.push_str(&format!(
+                    "{} {}: ",
+                    config.heading(1),
+                    config.label(Section::Returns)
+                ));
+                md.push('\n');
+                match doc.returns.as_slice() {
+                    [ret] => md.push_str(&ret.markdown(config)),
+                    returns => returns.iter().for_each(|r| {
+                        md.push_str(&format!("- {}", r.markdown(config)));
+                    }),
+                }
+            }
+            Section::Examples if !doc.examples.is_empty() => {
+                md.push_str(&format!(
+                    "{} {}: ",
+                    config.heading(1),
+                    config.label(Section::Examples)
+                ));
+                md.push('\n');
+
+                doc.examples.iter().for_each(|e| {
+                    md.push_str(&e.markdown(config));
+                });
+            }
+            Section::Errors if !doc.errors.is_empty() => {
+                md.push_str(&format!(
+                    "{} {}: ",
+                    config.heading(1),
+                    config.label(Section::Errors)
+                ));
+                md.push('\n');
+
+                doc.errors.iter().for_each(|e| {
+                    md.push_str(&format!("- {}\n", config.wrap(&prose(e, config))));
+                });
+            }
+            Section::Notes if !doc.notes.is_empty() => {
+                md.push_str(&format!(
+                    "{} {}: ",
+                    config.heading(1),
+                    config.label(Section::Notes)
+                ));
+                md.push('\n');
+
+                doc.notes.iter().for_each(|n| {
+                    md.push_str(&format!("- {}\n", config.wrap(&prose(n, config))));
+                });
+            }
+            Section::SeeAlso if !doc.see.is_empty() => {
+                md.push_str(&format!(
+                    "{} {}: ",
+                    config.heading(1),
+                    config.label(Section::SeeAlso)
+                ));
+                md.push('\n');
+
+                doc.see.iter().for_each(|s| {
+                    md.push_str(&format!("- [{s}]({}#{})\n", config.page_of(s), anchor(s)));
+                });
+            }
+            _ => {}
+        }
+
+        if config.collapse.contains(&section) && !md.is_empty() {
+            md = collapse(&md, config.label(section));
+        }
+
+        return Some(md);
+    }
+
+    if let Some(tag) = name.strip_prefix("tag.") {
+        let values: Vec<&str> = doc.tag_values(tag).collect();
+        return Some(values.join(", "));
+    }
+
+    match name {
+        "title" => md.push_str(&doc.title),
+        "slug" => md.push_str(&doc.slug),
+        "signature" => md.push_str(&doc.signature()),
+        "header" => {
+            md.push_str(&format!("{} {}\n", config.heading(0), doc.title));
+            md.push_str(&format!("`{}`\n\n", doc.signature()));
+        }
+        "metadata" => {
+            if let Some(metadata) = config.metadata(doc) {
+                md.push_str(&format!("_{metadata}_\n\n"));
+            }
+        }
+        "badges" => {
+            let mut badges: Vec<String> = config
+                .tags_in(TagStyle::Badge)
+                .flat_map(|t| {
+                    doc.tag_values(&t.name).map(move |v| match v {
+                        "" => format!("`{}`", t.label()),
+                        v => format!("`{}: {v}`", t.label()),
+                    })
+                })
+                .collect();
+            let marks = config.marks(doc).into_iter();
+            badges.splice(0..0, marks.map(|label| format!("`{label}`")));
+            if !badges.is_empty() {
+                md.push_str(&format!("{}\n\n", badges.join(" ")));
+            }
+        }
+        "key-values" => {
+            for tag in config.tags_in(TagStyle::KeyValue) {
+                doc.tag_values(&tag.name).for_each(|v| {
+                    md.push_str(&format!("**{}:** {}\n\n", tag.label(), config.wrap(v)));
+                });
+            }
+        }
+        "deprecated" => {
+            if let Some(msg) = &doc.deprecated {
+                md.push_str(&format!("> [!WARNING]\n> **Deprecated** {}\n\n", msg));
+            }
+        }
+        "tags" => {
+            for tag in config.tags_in(TagStyle::Section) {
+                let mut values = doc.tag_values(&tag.name).peekable();
+                if values.peek().is_some() {
+                    md.push_str(&format!("{} {}: ", config.heading(1), tag.label()));
+                    md.push('\n');
+
+                    values.for_each(|v| {
+                        md.push_str(&format!("- {}\n", config.wrap(v)));
+                    });
+                }
+            }
+        }
+        _ => return None,
+    }
+
+    Some(md)
+}
+
+impl Markdownable for Return {
+    /// Convert a Return struct into its markdown representation
+    /// Into the form: "`type` description", after the name of the field of the result it is
+    fn markdown(&self, config: &Config) -> String {
+        let field = self
+            .name
+            .as_ref()
+            .map(|n| format!("{n} "))
+            .unwrap_or_default();
+        let line = format!(
+            "{field}{}: {}",
+            type_code(&self.data_type, config),
+            prose(&self.description, config)
+        );
+        format!("{} \n", config.wrap(&line))
+    }
+}
+
+/// Fold a rendered section into a `<details>` block, its heading becoming the summary
+/// The blank line lets markdown inside the block render
+fn collapse(rendered: &str, label: &str) -> String {
+    let body = rendered.split_once('\n').map_or("", |(_, body)| body);
+    format!("<details>\n<summary>{label}</summary>\n\n{body}\n</details>\n\n")
+}
+
+/// Escape text for a table cell, which must stay on one line and
+/// can't contain a bare pipe, even inside code
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Types as code, e.g. "`int | none`", or with `link_types`, each typst type as code
+/// linked to its page of the typst reference
+fn type_code(data_type: &[String], config: &Config) -> String {
+    if !config.link_types {
+        return format!("`{}`", data_type.join(" | "));
+    }
+    let linked: Vec<String> = data_type
+        .iter()
+        .map(|t| match types::reference(t) {
+            Some(href) => format!("[`{t}`]({href})"),
+            None => format!("`{t}`"),
+        })
+        .collect();
+    linked.join(" | ")
+}
+
+/// Prose with mentions of documented functions linked to their docs
+fn prose(text: &str, config: &Config) -> String {
+    config.autolink(text, |name, href| format!("[{name}]({href})"))
+}
+
+/// The anchor GitHub generates for a heading
+/// Lowercased, spaces become dashes, and punctuation other than - and _ is dropped
+pub fn anchor(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Anchors for the headings of a single document, in order
+/// Repeated headings get a numbered suffix like GitHub gives them,
+/// e.g. ["question", "question"] -> ["question", "question-1"]
+pub fn slugs<'a>(headings: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut used = HashSet::new();
+
+    headings
+        .into_iter()
+        .map(|heading| unique_slug(heading, &mut used))
+        .collect()
+}
+
+/// The anchor of `heading`, numbered so it differs from every slug in `used`,
+/// which it is then added to
+pub(crate) fn unique_slug(heading: &str, used: &mut HashSet<String>) -> String {
+    let base = anchor(heading);
+    let mut slug = base.clone();
+    let mut n = 0;
+    while used.contains(&slug) {
+        n += 1;
+        slug = format!("{base}-{n}");
+    }
+    used.insert(slug.clone());
+    slug
+}
+
+impl Markdownable for Example {
+    /// Convert an example into a fenced code block in its language
+    /// Code is never wrapped
+    fn markdown(&self, _config: &Config) -> String {
+        // the fence must be longer than any run of backticks in the code,
+        // or raw blocks inside the example would close it
+        let longest = self
+            .code
+            .split(|c| c != '`')
+            .map(str::len)
+            .max()
+            .unwrap_or(0);
+        let fence = "`".repeat(longest.max(2) + 1);
+
+        let mut md = format!("{fence}{}\n{}{fence}\n", self.language, self.code);
+
+        if let Some(image) = &self.image {
+            md.push_str(&format!("\n![Output of the example]({image})\n"));
+        }
+
+        md
+    }
+}
+
+impl Markdownable for Param {
+    /// Convert a parameter into a row of the parameter table
+    /// or with `param_list`, into the form: "name: `type` description"
+    fn markdown(&self, config: &Config) -> String {
+        if !config.param_list {
+            let code = |s: &str| format!("`{}`", cell(s));
+            return format!(
+                "| {} ({}) | {} | {} | {} |\n",
+                code(&self.label()),
+                config.locale.requirement(self.is_required()),
+                // params only found in the definition may have no type
+                match self.data_type.is_empty() {
+                    true => String::new(),
+                    false => cell(&type_code(&self.types(), config)),
+                },
+                self.default.as_deref().map(code).unwrap_or_default(),
+                cell(&prose(&self.description, config))
+            );
+        }
+
+        // params only found in the definition may have no type
+        let mut data_type_str = String::new();
+        if !self.data_type.is_empty() {
+            data_type_str = type_code(&self.types(), config);
+        }
+
+        let requirement = config.locale.requirement(self.is_required());
+        let default_str = match &self.default {
+            Some(def) => format!("({requirement}, default: {def})"),
+            None => format!("({requirement})"),
+        };
+
+        let line = format!(
+            "{}: {} {} {}",
+            self.label(),
+            data_type_str,
+            default_str,
+            prose(&self.description, config)
+        );
+
+        format!("{} \n \n", config.wrap(&line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_block;
+
+    #[test]
+    fn example_without_language_is_typst() {
+        let doc = parse_block("f: Do it\n@example\n#f()\n").unwrap();
+
+        assert_eq!(doc.examples[0].language, "typst");
+        assert_eq!(
+            doc.examples[0].markdown(&Config::default()),
+            "```typst\n#f()\n```\n"
+        );
+    }
+
+    #[test]
+    fn example_with_language_gets_its_fence() {
+        let doc = parse_block("f: Do it\n@example java\npublic class A {}\n").unwrap();
+
+        assert_eq!(doc.examples[0].language, "java");
+        assert_eq!(
+            doc.examples[0].markdown(&Config::default()),
+            "```java\npublic class A {}\n```\n"
+        );
+    }
+
+    #[test]
+    fn wraps_prose_but_not_code_or_tables() {
+        let code = "#let answer = question(body: [What is the longest line in this example?])";
+        let doc = parse_block(&format!(
+            "f: A description that is far too long to fit on a single line of forty columns\n\
+             @param body content The body of the question, which is also much longer than forty columns\n\
+             @example\n{code}\n"
+        ))
+        .unwrap();
+        let config = Config {
+            wrap: Some(40),
+            ..Default::default()
+        };
+        let md = doc.markdown(&config);
+
+        assert!(md.contains(
+            "A description that is far too long to\nfit on a single line of forty columns"
+        ));
+        // table rows and code can't be broken up
+        assert!(
+            md.lines()
+                .any(|l| l.starts_with("| `body`") && l.len() > 40)
+        );
+        assert!(md.lines().any(|l| l == code));
+        for line in md
+            .lines()
+            .filter(|l| !l.starts_with('|') && !l.starts_with('`') && *l != code)
+        {
+            assert!(
+                line.chars().count() <= 40,
+                "{line:?} is longer than 40 columns"
+            );
+        }
+    }
+
+    #[test]
+    fn sections_follow_the_configured_order() {
+        let doc = parse_block(
+            "f: Do it\n@param body content the body\n@return int the score\n@example\n#f[]\n",
+        )
+        .unwrap();
+        let position = |markdown: &str, heading: &str| markdown.find(heading).unwrap();
+
+        let default = doc.markdown(&Config::default());
+        assert!(position(&default, "Parameters") < position(&default, "Returns"));
+        assert!(position(&default, "Returns") < position(&default, "Example"));
+
+        let mut config = Config::default();
+        config.set_section_order(&["examples", "returns"]);
+        let reordered = doc.markdown(&config);
+        assert!(position(&reordered, "Example") < position(&reordered, "Returns"));
+        // sections left out follow in their default order
+        assert!(position(&reordered, "Returns") < position(&reordered, "Parameters"));
+    }
+
+    #[test]
+    fn template_loops_over_params_and_tests_tags() {
+        let doc = parse_block(
+            "f: Do it\n@param a int the first\n@param b int the second\n@todo tidy up\n",
+        )
+        .unwrap();
+        let config = Config {
+            template: Some(
+                "{{ title }}:{% for p in doc.params %} {{ p.name }}{% endfor %}\n\
+                 {% if tag.todo %}todo: {{ tag.todo }}{% endif %}{% if tag.since %}since{% endif %}"
+                    .to_string(),
+            ),
+            ..Default::default()
+        };
+
+        assert_eq!(doc.markdown(&config), "f: a b\ntodo: tidy up");
+    }
+
+    #[test]
+    fn template_that_fails_to_render_falls_back_with_a_warning() {
+        let doc = parse_block("f: Do it\n").unwrap();
+        let config = Config {
+            template: Some("{{ nope }}".to_string()),
+            ..Default::default()
+        };
+        let before = crate::THREAD_WARNINGS.get();
+
+        assert!(doc.markdown(&config).starts_with("## f\n"));
+        assert_eq!(crate::THREAD_WARNINGS.get(), before + 1);
+    }
+}
@@ -0,0 +1,35 @@
+use std::ops::BitOrAssign;
+
+/// How a run went, ordered from best to worst, and the status `marker` exits with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Outcome {
+    /// Nothing went wrong
+    #[default]
+    Clean = 0,
+    /// Warnings with --strict or from `marker check`, coverage under --fail-under,
+    /// or options that can't be used together
+    Failed = 1,
+    /// A doc comment could not be parsed
+    ParseError = 2,
+    /// A file could not be read or written
+    IoError = 3,
+}
+
+impl Outcome {
+    /// The status to exit with
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// Whether anything went wrong
+    pub fn failed(self) -> bool {
+        self != Outcome::Clean
+    }
+}
+
+/// Keep the worst of two outcomes
+impl BitOrAssign for Outcome {
+    fn bitor_assign(&mut self, other: Outcome) {
+        *self = (*self).max(other);
+    }
+}
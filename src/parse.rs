@@ -0,0 +1,1194 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+    io::{self, BufRead, Lines},
+    sync::OnceLock,
+};
+
+use regex::Regex;
+
+use crate::{
+    Definition, DocComment, Error, Example, Finding, Kind, Language, Param, Result, Span, Tag,
+    Visibility,
+    directive::{Directive, RULES, directive, ignores_block},
+    grammar,
+    markdown::{slugs, unique_slug},
+    types, warn, warn_finding,
+};
+
+/// Names of every tag `marker` understands without configuration
+const BUILTIN_TAGS: [&str; 17] = [
+    "param",
+    "return",
+    "returns",
+    "example",
+    "deprecated",
+    "panics",
+    "throws",
+    "see",
+    "since",
+    "version",
+    "author",
+    "public",
+    "internal",
+    "inheritdoc",
+    "copydoc",
+    "alias",
+    "module",
+];
+
+/// Parses an entire typst file (document) into a DocComment per block
+/// A block that fails to parse does not stop the rest of the document
+/// Spans of errors point into `input`
+pub fn parse_document(input: &str) -> Vec<Result<DocComment>> {
+    Language::typst().parse(input)
+}
+
+/// Parses a file of a language with the `Tags` syntax into a DocComment per block
+pub(crate) fn parse_with(input: &str, language: &Language) -> Vec<Result<DocComment>> {
+    let module =
+        module_block(input, language).map(|(block, origins)| finish_module(&block, &origins));
+    let mut docs: Vec<Result<DocComment>> = module
+        .into_iter()
+        .chain(
+            blocks(input, language)
+                .into_iter()
+                .map(|(block, origins, definition)| finish_any(&block, &origins, definition)),
+        )
+        .collect();
+
+    // functions documented twice in a file would otherwise share an anchor
+    let slugs = slugs(docs.iter().flatten().map(|d| d.title.as_str()));
+    docs.iter_mut()
+        .flatten()
+        .zip(slugs)
+        .for_each(|(doc, slug)| doc.slug = slug);
+
+    inherit(&mut docs);
+    docs
+}
+
+/// Fill in the doc comments with @inheritdoc from the functions they name in the file,
+/// each after the one it names, so a function can inherit from one that inherits itself
+/// Names not documented in the file and cycles are warned about and left alone
+fn inherit(docs: &mut [Result<DocComment>]) {
+    let mut docs: Vec<&mut DocComment> = docs.iter_mut().flatten().collect();
+    let find = |docs: &[&mut DocComment], name: &str| {
+        docs.iter().position(|d| {
+            d.title == name || d.definition.as_ref().is_some_and(|def| def.name == name)
+        })
+    };
+
+    let mut waiting: Vec<usize> = (0..docs.len())
+        .filter(|&i| docs[i].inherit.is_some())
+        .collect();
+    while !waiting.is_empty() {
+        let ready = waiting.iter().position(|&i| {
+            let name = docs[i].inherit.as_deref().unwrap_or_default();
+            find(&docs, name).is_none_or(|source| !waiting.contains(&source))
+        });
+
+        // everything left waits on another, so they inherit in a cycle
+        let Some(ready) = ready else {
+            for i in waiting {
+                let doc = &docs[i];
+                let name = doc.inherit.as_deref().unwrap_or_default();
+                if !doc.allows("inheritdoc-cycle") {
+                    warn_finding(Finding::new(
+                        doc.span,
+                        "inheritdoc-cycle",
+                        format!(
+                            "{} inherits from {name}, which inherits back from it",
+                            doc.title
+                        ),
+                    ));
+                }
+            }
+            return;
+        };
+
+        let i = waiting.remove(ready);
+        let name = docs[i].inherit.clone().unwrap_or_default();
+        match find(&docs, &name) {
+            Some(source) => {
+                // taken out for a moment so the two can be borrowed together
+                let source_doc = std::mem::take(&mut *docs[source]);
+                docs[i].inherit_from(&source_doc);
+                *docs[source] = source_doc;
+            }
+            None if !docs[i].allows("unknown-inheritdoc") => warn_finding(Finding::new(
+                docs[i].span,
+                "unknown-inheritdoc",
+                format!(
+                    "{} inherits from {name} with @inheritdoc, but it is not documented in the file",
+                    docs[i].title
+                ),
+            )),
+            None => {}
+        }
+    }
+}
+
+/// Parses a typst file into a DocComment per block as it is read, a line at a time,
+/// so only the block being parsed is held in memory, however large the file
+/// Gives the same doc comments as `parse_document`, except that a #let whose arguments
+/// run on for more than 64 lines is cut off, a read error ends the iteration,
+/// `marker:ignore-file` only leaves out the doc comments after it,
+/// //! comments are not read, and @inheritdoc is left to the caller,
+/// since it may name a function further on
+pub fn parse_iter<R: BufRead>(reader: R) -> impl Iterator<Item = Result<DocComment>> {
+    let typst = Language::typst();
+    DocIter {
+        lines: reader.lines(),
+        pending: VecDeque::new(),
+        number: 0,
+        used: HashSet::new(),
+        comments: Comments::new(&typst),
+        signature: signature(&typst),
+        done: false,
+    }
+}
+
+/// Lines read past the end of a block when streaming, to find the #let it documents
+const DEFINITION_LOOKAHEAD: usize = 64;
+
+/// Iterator behind `parse_iter`
+struct DocIter<R> {
+    lines: Lines<R>,
+    /// Lines read ahead for a definition but not scanned for doc comments yet
+    pending: VecDeque<io::Result<String>>,
+    /// Number of the last line scanned
+    number: usize,
+    /// Slugs given out so far, so repeated titles get their own
+    used: HashSet<String>,
+    comments: Comments,
+    signature: Option<Regex>,
+    done: bool,
+}
+
+impl<R: BufRead> DocIter<R> {
+    /// The next line to scan, read ahead or from the reader
+    fn next_line(&mut self) -> Option<io::Result<String>> {
+        self.pending.pop_front().or_else(|| self.lines.next())
+    }
+
+    /// The definition starting at the next line to scan,
+    /// reading ahead as far as the lookahead allows
+    fn definition(&mut self) -> Option<Definition> {
+        while self.pending.len() < DEFINITION_LOOKAHEAD {
+            match self.lines.next() {
+                Some(line) => self.pending.push_back(line),
+                None => break,
+            }
+        }
+
+        let lines: Vec<&str> = self
+            .pending
+            .iter()
+            .map_while(|l| l.as_ref().ok())
+            .map(String::as_str)
+            .collect();
+        definition(&lines, self.signature.as_ref()?)
+    }
+}
+
+impl<R: BufRead> Iterator for DocIter<R> {
+    type Item = Result<DocComment>;
+
+    fn next(&mut self) -> Option<Result<DocComment>> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            let mut block = String::new();
+            let mut origins = Vec::new();
+
+            let definition = loop {
+                let line = match self.next_line() {
+                    Some(Ok(line)) => line,
+                    Some(Err(source)) => {
+                        self.done = true;
+                        return Some(Err(Error::Io {
+                            path: format!("line {}", self.number + 1),
+                            source,
+                        }));
+                    }
+                    // a block at the very end of the file has no #let after it
+                    None if !block.is_empty() => break None,
+                    None => return None,
+                };
+                self.number += 1;
+
+                // docs already given out can't be taken back, so this only stops the rest
+                if directive(&line) == Some(Directive::IgnoreFile) {
+                    self.done = true;
+                    return None;
+                }
+
+                match self.comments.line(&line, self.number) {
+                    Some(doc_line) => {
+                        if let Some((content, origin)) = doc_line.content {
+                            block.push_str(content);
+                            block.push('\n');
+                            origins.push(origin);
+                        }
+                        // the line right after a /** */ block may be the #let it documents
+                        if doc_line.closes && !block.is_empty() {
+                            break self.definition();
+                        }
+                    }
+                    None if !block.is_empty() => {
+                        // the line right after the block may be the #let it documents
+                        self.pending.push_front(Ok(line));
+                        self.number -= 1;
+                        break self.definition();
+                    }
+                    None => {}
+                }
+            };
+
+            // an ignored block is skipped for the one after it
+            if ignores_block(&block) {
+                continue;
+            }
+
+            let doc = finish_any(&block, &origins, definition).map(|mut doc| {
+                doc.slug = unique_slug(&doc.title, &mut self.used);
+                doc
+            });
+            return Some(doc);
+        }
+    }
+}
+
+/// The doc comment of a whole file, from the module comments like //! it starts with,
+/// given as (block, where each of its lines starts in the file)
+/// Blank lines between them are skipped
+fn module_block(input: &str, language: &Language) -> Option<(String, Vec<Span>)> {
+    let mut block = String::new();
+    let mut origins = Vec::new();
+
+    for (number, line) in input.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let Some(rest) = language
+            .module_comments
+            .iter()
+            .find_map(|p| trimmed.strip_prefix(p.as_str()))
+        else {
+            match line.trim().is_empty() {
+                true => continue,
+                false => break,
+            }
+        };
+
+        let content = rest.strip_prefix(' ').unwrap_or(rest).trim_end();
+        block.push_str(content);
+        block.push('\n');
+        origins.push(Span {
+            line: number + 1,
+            column: line[..offset(line, content)].chars().count() + 1,
+        });
+    }
+
+    (!block.trim().is_empty()).then_some((block, origins))
+}
+
+/// Whether a block documents the whole file, with a @module tag
+fn is_module(block: &str) -> bool {
+    block
+        .lines()
+        .any(|l| grammar::tag(l.trim()).is_some_and(|(name, _)| name == "module"))
+}
+
+/// Parse a block found in a file like `finish`, or like `finish_module` for a @module block
+fn finish_any(block: &str, origins: &[Span], definition: Option<Definition>) -> Result<DocComment> {
+    match is_module(block) {
+        true => finish_module(block, origins),
+        false => finish(block, origins, definition),
+    }
+}
+
+/// Parse the doc comment of a whole file, which needs no title,
+/// given as (block, where each of its lines starts in the file)
+fn finish_module(block: &str, origins: &[Span]) -> Result<DocComment> {
+    finish_block(block, origins, None, true)
+}
+
+/// Parse a block found in a file, given as (block, where each of its lines starts
+/// in the file, the #let after it), into a DocComment with spans into the file
+fn finish(block: &str, origins: &[Span], definition: Option<Definition>) -> Result<DocComment> {
+    finish_block(block, origins, definition, false)
+}
+
+/// Parse a block like `finish`, or like `finish_module` if it documents the whole file
+fn finish_block(
+    block: &str,
+    origins: &[Span],
+    definition: Option<Definition>,
+    module: bool,
+) -> Result<DocComment> {
+    let mut problems = Vec::new();
+    let doc = parse_block_with(block, module, &mut problems);
+    for mut problem in problems {
+        relocate(&mut problem.span, origins);
+        warn_finding(Finding::new(problem.span, problem.rule, problem.message));
+    }
+
+    let mut doc = doc.map_err(|mut e| {
+        if let Some(span) = e.span_mut() {
+            relocate(span, origins);
+        }
+        e
+    })?;
+
+    relocate(&mut doc.span, origins);
+    doc.params
+        .iter_mut()
+        .filter_map(|p| p.span.as_mut())
+        .chain(doc.returns.iter_mut().filter_map(|r| r.span.as_mut()))
+        .for_each(|span| relocate(span, origins));
+
+    Ok(match definition {
+        Some(definition) => doc.with_definition(definition),
+        None => doc,
+    })
+}
+
+/// Move a span from a block into its file, given where each line of the block starts
+fn relocate(span: &mut Span, origins: &[Span]) {
+    if let Some(origin) = span.line.checked_sub(1).and_then(|i| origins.get(i)) {
+        span.line = origin.line;
+        span.column += origin.column - 1;
+    }
+}
+
+/// Titles of every doc comment in a typst file, without parsing their tags
+/// Blocks with a malformed header are skipped, and nothing is warned about
+pub fn parse_titles(input: &str) -> Vec<String> {
+    Language::typst().titles(input)
+}
+
+/// Titles of every doc comment in a file of a language with the `Tags` syntax
+pub(crate) fn titles_with(input: &str, language: &Language) -> Vec<String> {
+    blocks(input, language)
+        .iter()
+        .filter(|(block, _, _)| !is_module(block))
+        .filter_map(|(block, _, _)| {
+            let header = header(block);
+            header.split_once(": ").map(|(title, _)| title.to_string())
+        })
+        .collect()
+}
+
+/// Split a file into its doc comment blocks with the /// or * gutters stripped,
+/// given as (block, where each of its lines starts in the file, the #let after it)
+fn blocks(input: &str, language: &Language) -> Vec<(String, Vec<Span>, Option<Definition>)> {
+    let mut comment_chunks = Vec::new();
+    let mut cur = String::new();
+    // where each line of the current block starts in the file
+    let mut origins = Vec::new();
+    let mut comments = Comments::new(language);
+    let signature = signature(language);
+    let definition = |lines: &[&str]| definition(lines, signature.as_ref()?);
+    let lines: Vec<&str> = input.lines().collect();
+
+    for (number, line) in lines.iter().enumerate() {
+        if let Some(doc_line) = comments.line(line, number + 1) {
+            if let Some((content, origin)) = doc_line.content {
+                cur.push_str(content);
+                cur.push('\n');
+                origins.push(origin);
+            }
+
+            // the line right after a /** */ block may be the #let it documents
+            if doc_line.closes && !cur.is_empty() {
+                let definition = definition(&lines[number + 1..]);
+                comment_chunks.push((cur.clone(), origins.clone(), definition));
+                cur.clear();
+                origins.clear();
+            }
+
+        // should handle breaks in between the doc comments
+        } else if !cur.is_empty() {
+            // the line right after the block may be the #let it documents
+            let definition = definition(&lines[number..]);
+            comment_chunks.push((cur.clone(), origins.clone(), definition));
+            cur.clear();
+            origins.clear();
+        }
+    }
+
+    // a block at the very end of the file has no following line to flush it
+    if !cur.is_empty() {
+        comment_chunks.push((cur, origins, None));
+    }
+
+    comment_chunks.retain(|(block, _, _)| !ignores_block(block));
+    comment_chunks
+}
+
+/// A line that is part of a doc comment
+struct DocLine<'a> {
+    /// What the line adds to the block with its gutter stripped, and where that starts,
+    /// `None` for lines with nothing to add, like a bare /**
+    content: Option<(&'a str, Span)>,
+    /// Whether the line ends a /** */ block
+    closes: bool,
+}
+
+/// Picks the doc comments out of a file a line at a time,
+/// both line comments like /// and block comments like /** */
+#[derive(Debug)]
+struct Comments {
+    line_comments: Vec<String>,
+    block_comments: Vec<(String, String)>,
+    /// Index of the block comment the scan is inside
+    in_block: Option<usize>,
+}
+
+impl Comments {
+    /// Scan for the doc comments of `language`
+    fn new(language: &Language) -> Comments {
+        Comments {
+            line_comments: language.line_comments.clone(),
+            block_comments: language.block_comments.clone(),
+            in_block: None,
+        }
+    }
+
+    /// The doc comment line `line`, numbered `number`, is, `None` for any other line
+    fn line<'a>(&mut self, line: &'a str, number: usize) -> Option<DocLine<'a>> {
+        let trimmed = line.trim();
+        let opened = self
+            .block_comments
+            .iter()
+            .enumerate()
+            .find_map(|(i, (open, _))| Some((i, trimmed.strip_prefix(open.as_str())?)));
+
+        let (block, content, closes) = if let Some(block) = self.in_block {
+            let (_, close) = &self.block_comments[block];
+            let (inside, closes) = match line.split_once(close.as_str()) {
+                Some((inside, _)) => (inside, true),
+                None => (line, false),
+            };
+            // the gutter is optional, but most blocks have one, e.g. the * of /** */
+            let inside = match inside.trim_start().strip_prefix(block_gutter(close)) {
+                Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+                None => inside,
+            };
+            (Some(block), inside, closes)
+        } else if let Some((block, rest)) = opened {
+            let (open, close) = &self.block_comments[block];
+            // /*** is a separator, and /**/ an empty comment
+            let empty = format!("{}{close}", &open[..open.len() - gutter(open).len_utf8()]);
+            if rest.starts_with(gutter(open)) || trimmed.starts_with(&empty) {
+                return None;
+            }
+            let (inside, closes) = match rest.split_once(close.as_str()) {
+                Some((inside, _)) => (inside, true),
+                None => (rest, false),
+            };
+            (
+                Some(block),
+                inside.strip_prefix(' ').unwrap_or(inside),
+                closes,
+            )
+        } else if let Some((prefix, rest)) = self
+            .line_comments
+            .iter()
+            .find_map(|p| Some((p, trimmed.strip_prefix(p.as_str())?)))
+        {
+            // every doc comment MUST have a /// anyway, and extra slashes are ignored
+            // only a single space is stripped so examples keep their indentation
+            let rest = rest.trim_start_matches(gutter(prefix));
+            (None, rest.strip_prefix(' ').unwrap_or(rest), false)
+        } else {
+            return None;
+        };
+
+        self.in_block = block.filter(|_| !closes);
+
+        let content = content.trim_end();
+        // a bare /** or */ adds nothing to the block
+        let bare = content.is_empty() && (closes || opened.is_some());
+        let origin = Span {
+            line: number,
+            column: line[..offset(line, content)].chars().count() + 1,
+        };
+
+        Some(DocLine {
+            content: (!bare).then_some((content, origin)),
+            closes,
+        })
+    }
+}
+
+/// The character a comment marker repeats, e.g. the * of /** or the / of ///
+pub(crate) fn gutter(marker: &str) -> char {
+    marker.chars().last().unwrap_or(' ')
+}
+
+/// The character lines inside a block comment start with, the first of its closing
+/// delimiter, e.g. the * of */
+pub(crate) fn block_gutter(close: &str) -> char {
+    close.chars().next().unwrap_or(' ')
+}
+
+/// The signature pattern of `language`, warning if it is not a valid regex
+pub(crate) fn signature(language: &Language) -> Option<Regex> {
+    let pattern = language.signature.as_ref()?;
+    Regex::new(pattern)
+        .inspect_err(|e| warn(format!("{}: invalid signature pattern: {e}", language.name)))
+        .ok()
+}
+
+/// Byte offset of `part`, a slice of `line`, within it
+pub(crate) fn offset(line: &str, part: &str) -> usize {
+    (part.as_ptr() as usize).saturating_sub(line.as_ptr() as usize)
+}
+
+/// Parses a function definition, e.g. "#let question(body, points: 1) = ...",
+/// or a binding, e.g. "#let answer_indents = (1fr, 10fr, 1fr)"
+/// The arguments or value may continue over the following lines
+/// Returns None if `lines` does not start with a definition
+pub fn parse_definition(lines: &[&str]) -> Option<Definition> {
+    definition(lines, &signature(&Language::typst())?)
+}
+
+/// Parses the definition `lines` start with, if `signature` matches the first of them
+/// Arguments are only read when the match ends at their opening parenthesis,
+/// a value when it ends at an `=`, and the whole rule when it matches a `rule` group
+pub(crate) fn definition(lines: &[&str], signature: &Regex) -> Option<Definition> {
+    let first = lines.first()?;
+    let caps = signature.captures(first)?;
+    let matched = caps.get(0)?;
+
+    if let Some(rule) = caps.name("rule") {
+        let value = binding_value(&first[rule.start()..], &lines[1..]);
+        // named by what the rule applies to, e.g. "show heading" or "set text"
+        let (kind, end) = match rule.as_str() {
+            "show" => (Kind::ShowRule, ':'),
+            _ => (Kind::SetRule, '('),
+        };
+        let name = value[..top_level(&value, end).unwrap_or(value.len())].trim();
+        return Some(Definition {
+            name: name.to_string(),
+            value: Some(value.clone()),
+            kind,
+            ..Default::default()
+        });
+    }
+
+    let name = caps.name("name")?.as_str().to_string();
+    if matched.as_str().ends_with('=') {
+        let value = binding_value(&first[matched.end()..], &lines[1..]);
+        return Some(Definition {
+            name,
+            kind: Kind::of_value(&value),
+            value: Some(value),
+            ..Default::default()
+        });
+    }
+    if !matched.as_str().ends_with('(') {
+        return Some(Definition {
+            name,
+            ..Default::default()
+        });
+    }
+
+    // collect everything up to the matching ) across lines
+    let mut args = vec![String::new()];
+    let mut depth = 0;
+    let mut quoted = false;
+    let rest = &first[caps.get(0)?.end()..];
+
+    'outer: for line in std::iter::once(&rest).chain(&lines[1..]) {
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            let arg = args.last_mut()?;
+            match c {
+                '\\' if quoted => {
+                    arg.push(c);
+                    arg.extend(chars.next());
+                    continue;
+                }
+                '"' => quoted = !quoted,
+                _ if quoted => {}
+                '(' | '[' | '{' => depth += 1,
+                ')' if depth == 0 => break 'outer,
+                ')' | ']' | '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    args.push(String::new());
+                    continue;
+                }
+                _ => {}
+            }
+            arg.push(c);
+        }
+        args.last_mut()?.push(' ');
+    }
+
+    static ARG_RE: OnceLock<Regex> = OnceLock::new();
+    let arg_re = ARG_RE.get_or_init(|| {
+        Regex::new(r"^(?P<name>(?:\.\.)?[\w-]+)(?:\s*:\s*(?P<default>.+))?$").unwrap()
+    });
+
+    let params = args
+        .iter()
+        .map(|a| a.split_whitespace().collect::<Vec<_>>().join(" "))
+        // destructured arguments have no single name to document
+        .filter_map(|a| {
+            let caps = arg_re.captures(&a)?;
+            let name = &caps["name"];
+            let default = caps.name("default").map(|d| d.as_str());
+            let param = Param {
+                name: name.trim_start_matches("..").to_string(),
+                variadic: name.starts_with(".."),
+                named: default.is_some(),
+                // a literal default gives the type away, e.g. `points: 1` is an int,
+                // but a none or auto default only says what else it may be
+                data_type: default
+                    .and_then(grammar::literal_type)
+                    .filter(|t| !matches!(*t, "none" | "auto"))
+                    .map(|t| vec![t.to_string()])
+                    .unwrap_or_default(),
+                default: default.map(str::to_string),
+                ..Default::default()
+            };
+            Some(param)
+        })
+        .collect();
+
+    Some(Definition {
+        name,
+        params,
+        ..Default::default()
+    })
+}
+
+/// Byte offset of the first `c` in `text` outside of brackets and strings
+fn top_level(text: &str, c: char) -> Option<usize> {
+    let mut depth = 0;
+    let mut quoted = false;
+    for (i, ch) in text.char_indices() {
+        match ch {
+            '"' => quoted = !quoted,
+            _ if quoted => {}
+            _ if ch == c && depth == 0 => return Some(i),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The value of a binding, from `rest` of the line after its `=`
+/// and the `lines` after it for as long as a bracket or string is left open,
+/// joined into a single line
+fn binding_value(rest: &str, lines: &[&str]) -> String {
+    let mut value = String::new();
+    let mut depth = 0;
+    let mut quoted = false;
+
+    for line in std::iter::once(&rest).chain(lines) {
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if quoted => {
+                    chars.next();
+                }
+                '"' => quoted = !quoted,
+                _ if quoted => {}
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        // lines join with a space, except inside the brackets they open and close
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !value.is_empty()
+            && !value.ends_with(['(', '[', '{'])
+            && !line.starts_with([')', ']', '}'])
+        {
+            value.push(' ');
+        }
+        value.push_str(&line);
+        if depth <= 0 && !quoted {
+            break;
+        }
+    }
+
+    value
+}
+
+/// The `title: description` header of a block, joined onto a single line
+/// asserting that the header is everything before the first @ tag
+fn header(block: &str) -> String {
+    block
+        .lines()
+        .filter(|l| directive(l).is_none())
+        .take_while(|l| !l.trim().starts_with('@'))
+        .map(|l| l.trim())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim()
+        .to_string()
+}
+
+/// Parses a single doc comment block into a DocComment
+/// Fails if the header does not follow the `title: description` format,
+/// or a tag is missing a required piece
+/// Spans of errors and warnings are relative to `block`
+pub fn parse_block(block: &str) -> Result<DocComment> {
+    let mut problems = Vec::new();
+    let doc = parse_block_with(block, false, &mut problems);
+    problems.into_iter().for_each(warn);
+    doc
+}
+
+/// Something wrong with a doc comment that doesn't stop it from parsing, reported as a warning
+struct Problem {
+    span: Span,
+    /// Name of the rule `marker:allow` turns it off with
+    rule: &'static str,
+    message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}:{}: {} [{}]",
+            self.span.line, self.span.column, self.message, self.rule
+        )
+    }
+}
+
+/// A problem for every type in `data_type` that typst doesn't have, like a typo'd `ms` for `int`
+fn unknown_types(data_type: &[String], what: &str, title: &str, span: Span) -> Vec<Problem> {
+    data_type
+        .iter()
+        .flat_map(|t| types::unknown(t))
+        .map(|t| Problem {
+            span,
+            rule: "unknown-type",
+            message: format!("{title} documents {what} as `{t}`, which is not a typst type"),
+        })
+        .collect()
+}
+
+/// Parses a single doc comment block, adding what is wrong with it that
+/// doesn't stop it from parsing to `problems`
+/// The doc comment of a whole file has no `title: description` header, all of its
+/// prose is the description, and its title is the @module value if it has one
+fn parse_block_with(block: &str, module: bool, problems: &mut Vec<Problem>) -> Result<DocComment> {
+    let lines = block.lines();
+    let header = header(block);
+
+    // get the title via split on the first ": " of the header
+    // the header starts at the first line with anything on it
+    let (line, column) = lines
+        .clone()
+        .enumerate()
+        .find(|(_, l)| !l.trim().is_empty() && directive(l).is_none())
+        .map(|(i, l)| (i, l.chars().take_while(|c| c.is_whitespace()).count()))
+        .unwrap_or_default();
+    let span = Span {
+        line: line + 1,
+        column: column + 1,
+    };
+
+    let (title, description) = match module {
+        true => ("", header.as_str()),
+        false => header
+            .split_once(": ")
+            .ok_or_else(|| Error::MissingSeparator {
+                header: header.clone(),
+                span,
+            })?,
+    };
+
+    let mut doc = DocComment::new(title, description);
+    doc.span = span;
+    doc.module = module;
+    let mut in_example = false;
+    let mut seen_tag = false;
+    let mut in_note = false;
+    let mut continues = Continues::Nothing;
+
+    for (number, line) in lines.enumerate() {
+        let trimmed = line.trim();
+        let span = Span {
+            line: number + 1,
+            column: line.chars().take_while(|c| c.is_whitespace()).count() + 1,
+        };
+
+        // directives are about the doc comment, not part of it
+        if let Some(directive) = directive(trimmed) {
+            if let Directive::Allow(rules) = directive {
+                for rule in &rules {
+                    if !RULES.contains(&rule.as_str()) {
+                        problems.push(Problem {
+                            span,
+                            rule: "unknown-rule",
+                            message: format!(
+                                "marker:allow in {} names unknown rule {rule}",
+                                doc.title
+                            ),
+                        });
+                    }
+                }
+                doc.allow.extend(rules);
+            }
+            continue;
+        }
+        // tags are case insensitive, and may have leading whitespace or a trailing colon
+        // e.g. "  @Param:" is the same as "@param"
+        let tag = grammar::tag(trimmed);
+
+        // the language of the fence is optional and defaults to typst
+        if let Some((name, rest)) = &tag
+            && name == "example"
+            && rest.split_whitespace().count() <= 1
+        {
+            let example = match rest.is_empty() {
+                true => Example::default(),
+                false => Example::default().with_language(*rest),
+            };
+            doc = doc.with_example(example);
+            in_example = true;
+            continue;
+        }
+
+        // any other tag ends the example
+        if trimmed.starts_with('@') {
+            in_example = false;
+            seen_tag = true;
+        }
+
+        // a tag or a blank line ends the current note or description
+        if trimmed.starts_with('@') || trimmed.is_empty() {
+            in_note = false;
+            continues = Continues::Nothing;
+        }
+
+        if in_example {
+            if let Some(example) = doc.examples.last_mut() {
+                example.code.push_str(line);
+                example.code.push('\n');
+            }
+        } else if let Some((name, rest)) = tag {
+            let malformed = |tag, missing| Error::MalformedTag { tag, missing, span };
+
+            match name.as_str() {
+                "param" => {
+                    let mut p =
+                        grammar::param(rest).map_err(|missing| malformed("param", missing))?;
+                    p.span = Some(span);
+
+                    // copy-pasted params would otherwise render twice
+                    if doc.params.iter().any(|existing| existing.name == p.name) {
+                        problems.push(Problem {
+                            span,
+                            rule: "duplicate-param",
+                            message: format!(
+                                "{} documents @param {} more than once, keeping the first",
+                                doc.title, p.name
+                            ),
+                        });
+                        continue;
+                    }
+
+                    let what = format!("@param {}", p.name);
+                    problems.extend(unknown_types(&p.data_type, &what, &doc.title, span));
+
+                    doc = doc.with_param(p);
+                    continues = Continues::Param;
+                }
+                // @returns is how JSDoc spells it
+                "return" | "returns" => {
+                    let mut ret =
+                        grammar::returns(rest).map_err(|missing| malformed("return", missing))?;
+                    ret.span = Some(span);
+                    problems.extend(unknown_types(&ret.data_type, "@return", &doc.title, span));
+                    doc = doc.with_return(ret);
+                    continues = Continues::Return;
+                }
+                "deprecated" => doc = doc.with_deprecated(rest),
+                // typst has no exceptions, but @throws reads naturally to people coming from other languages
+                "panics" | "throws" if !rest.is_empty() => doc = doc.with_error(rest),
+                // several functions may be listed, e.g. "@see question, matching()"
+                "see" => {
+                    for title in rest.split([',', ' ']).filter(|t| !t.is_empty()) {
+                        doc = doc.with_see(title.trim_end_matches("()"));
+                    }
+                }
+                "module" => {
+                    doc.module = true;
+                    if doc.title.is_empty() {
+                        doc.title = rest.to_string();
+                    }
+                }
+                // several names may be listed, e.g. "@alias mc, choice"
+                "alias" => {
+                    for alias in rest.split([',', ' ']).filter(|a| !a.is_empty()) {
+                        doc.aliases.push(alias.to_string());
+                    }
+                }
+                "since" if !rest.is_empty() => doc = doc.with_since(rest),
+                "version" if !rest.is_empty() => doc = doc.with_version(rest),
+                "author" if !rest.is_empty() => doc = doc.with_author(rest),
+                // override the underscore convention, in either direction
+                "public" => doc.visibility = Some(Visibility::Public),
+                "internal" => doc.visibility = Some(Visibility::Private),
+                // @copydoc is how doxygen spells it
+                "inheritdoc" | "copydoc" if !rest.is_empty() => {
+                    doc.inherit = Some(rest.trim_end_matches("()").to_string());
+                }
+                // other built in tags without a value are left alone
+                name if BUILTIN_TAGS.contains(&name) => {}
+                _ => {
+                    // custom tags can be anything, but one a typo away from a built in tag is likely a typo
+                    if let Some(known) = near_miss(&name) {
+                        problems.push(Problem {
+                            span,
+                            rule: "unknown-tag",
+                            message: format!(
+                                "unknown tag `{trimmed}` in {}, did you mean @{known}?",
+                                doc.title
+                            ),
+                        });
+                    }
+                    doc = doc.with_tag(Tag::new(&name, rest));
+                    continues = Continues::Tag;
+                }
+            }
+        } else if trimmed.starts_with('@') {
+            problems.push(Problem {
+                span,
+                rule: "not-a-tag",
+                message: format!(
+                    "`{trimmed}` in {} is not a tag, so it is ignored",
+                    doc.title
+                ),
+            });
+        } else if continues != Continues::Nothing && !trimmed.is_empty() {
+            // descriptions wrap onto the following lines, up until a blank line
+            let desc = match continues {
+                Continues::Param => doc.params.last_mut().map(|p| &mut p.description),
+                Continues::Return => doc.returns.last_mut().map(|r| &mut r.description),
+                Continues::Tag => doc.tags.last_mut().map(|t| &mut t.value),
+                Continues::Nothing => None,
+            };
+
+            if let Some(desc) = desc {
+                if !desc.is_empty() {
+                    desc.push(' ');
+                }
+                desc.push_str(trimmed);
+            }
+        } else if module && seen_tag && !trimmed.is_empty() {
+            // prose around the tags of a file is all part of its introduction
+            if !doc.description.is_empty() {
+                doc.description.push(' ');
+            }
+            doc.description.push_str(trimmed);
+        } else if seen_tag && !trimmed.is_empty() && !trimmed.starts_with('@') {
+            // prose between tags would otherwise be silently dropped
+            // consecutive lines are joined into a single note
+            match doc.notes.last_mut() {
+                Some(note) if in_note => {
+                    note.push(' ');
+                    note.push_str(trimmed);
+                }
+                _ => doc = doc.with_note(trimmed),
+            }
+            in_note = true;
+        }
+        // Descriptions should have been parsed disparately
+        // only check if we captured the tag lines, or prose after them
+    }
+
+    // examples are usually indented under their tag
+    doc.examples
+        .iter_mut()
+        .for_each(|e| e.code = dedent(&e.code));
+
+    problems.retain(|p| !doc.allows(p.rule));
+    Ok(doc)
+}
+
+/// The built in tag `name` is a near miss of, e.g. "parm" -> "param"
+/// `None` for built in tags themselves, and names not close to any
+pub(crate) fn near_miss(name: &str) -> Option<&'static str> {
+    if BUILTIN_TAGS.contains(&name) {
+        return None;
+    }
+
+    BUILTIN_TAGS
+        .iter()
+        .map(|known| (*known, edit_distance(name, known)))
+        .filter(|(known, distance)| *distance <= (known.len() / 2).min(2))
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(known, _)| known)
+}
+
+/// Number of single character insertions, deletions and substitutions that turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous[j] + usize::from(ca != *cb);
+            current.push(substitute.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// The tag whose description a plain line continues
+#[derive(Clone, Copy, PartialEq)]
+enum Continues {
+    Nothing,
+    Param,
+    Return,
+    Tag,
+}
+
+/// Remove the leading whitespace shared by every non-blank line
+pub(crate) fn dedent(code: &str) -> String {
+    let indent = code
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    code.lines()
+        .map(|l| format!("{}\n", l.get(indent..).unwrap_or("")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, Markdownable};
+
+    /// Every doc comment of a typst file, failing the test on any that doesn't parse
+    fn docs(input: &str) -> Vec<DocComment> {
+        parse_document(input)
+            .into_iter()
+            .collect::<Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn signature_of_multiple_choice() {
+        let docs = docs(include_str!("../sample.typ"));
+        let doc = docs.iter().find(|d| d.title == "multiple_choice").unwrap();
+
+        assert_eq!(
+            doc.signature(),
+            "multiple_choice(body, points: 1, cols: 1, ..answers)"
+        );
+    }
+
+    #[test]
+    fn duplicate_param_is_kept_once_with_a_warning() {
+        let input = "/// f: Do it\n/// @param body content first\n/// @param body str second\n#let f(body) = 1\n";
+        let (docs, findings) = crate::collect_findings(|| docs(input));
+
+        assert_eq!(docs[0].params.len(), 1);
+        assert_eq!(docs[0].params[0].description, "first");
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "duplicate-param");
+        assert_eq!(findings[0].span.line, 3);
+    }
+
+    #[test]
+    fn tags_ignore_case_indentation_and_a_trailing_colon() {
+        let doc = parse_block(
+            "f: Do it\n@Param body content the body\n   @param points int = 1 the points\n@return: int the score\n",
+        ).unwrap();
+
+        assert_eq!(doc.title, "f");
+        assert_eq!(doc.description, "Do it");
+        assert_eq!(doc.params.len(), 2);
+        assert_eq!(doc.params[0].name, "body");
+        assert_eq!(doc.params[1].name, "points");
+        assert_eq!(doc.params[1].default.as_deref(), Some("1"));
+        assert_eq!(doc.returns.len(), 1);
+        assert_eq!(doc.returns[0].data_type, ["int"]);
+        assert_eq!(doc.returns[0].description, "the score");
+    }
+
+    #[test]
+    fn prose_between_params_stays_with_the_first() {
+        let doc = parse_block(
+            "f: Do it\n@param body content the body\nwhich may span lines\n@param points int the points\n\nScores are rounded\n",
+        )
+        .unwrap();
+
+        assert_eq!(doc.params.len(), 2);
+        assert_eq!(doc.params[0].description, "the body which may span lines");
+        assert_eq!(doc.params[1].description, "the points");
+        // past a blank line it no longer belongs to a param, but is still kept
+        assert_eq!(doc.notes, ["Scores are rounded"]);
+        assert!(
+            doc.markdown(&Config::default())
+                .contains("- Scores are rounded\n")
+        );
+    }
+
+    #[test]
+    fn doc_block_ending_the_file_is_parsed() {
+        let docs = docs("#let f() = 1\n\n/// g: Not yet written\n/// @param body content the body");
+
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].title, "g");
+        assert_eq!(docs[0].params[0].name, "body");
+    }
+
+    #[test]
+    fn module_comment_is_all_description() {
+        let docs = docs(
+            "//! Helpers: for building exams\n//! with questions\n\n/// q: A question\n#let q() = 1\n",
+        );
+
+        assert!(docs[0].module);
+        assert_eq!(docs[0].title, "");
+        assert_eq!(
+            docs[0].description,
+            "Helpers: for building exams with questions"
+        );
+        assert_eq!(docs[1].title, "q");
+    }
+
+    #[test]
+    fn module_tag_names_the_file() {
+        let docs = docs(
+            "/// @module exam\n/// Helpers: for building exams\n\n/// q: A question\n#let q() = 1\n",
+        );
+
+        assert!(docs[0].module);
+        assert_eq!(docs[0].title, "exam");
+        assert_eq!(docs[0].description, "Helpers: for building exams");
+        assert_eq!(docs[1].title, "q");
+    }
+
+    #[test]
+    fn iter_skips_any_number_of_ignored_blocks() {
+        let ignored = "/// f: Hidden\n/// marker:ignore\n#let f() = 1\n".repeat(20_000);
+        let input = format!("{ignored}/// g: Shown\n#let g() = 1\n");
+        let docs: Vec<DocComment> = parse_iter(input.as_bytes()).collect::<Result<_>>().unwrap();
+
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].title, "g");
+    }
+}
@@ -0,0 +1,207 @@
+use std::{fs, io::ErrorKind};
+
+use marker_typ::{Format, Language, Locale, Package, Sort};
+use serde::Deserialize;
+
+use crate::{
+    cli::{GenArgs, InputArgs},
+    expand::expand_path,
+};
+
+/// Default location of the project file
+pub const PROJECT_FILE: &str = "marker.toml";
+
+/// Settings shared by every run in a project, read from `marker.toml`
+/// Every setting is optional, and flags given on the command line take priority
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Project {
+    /// Files or directories to use when none are given
+    pub inputs: Vec<String>,
+    pub glob: Option<String>,
+    pub lang: Option<String>,
+    pub output: Option<String>,
+    /// File to write the docs into, between marker comments
+    pub inject: Option<String>,
+    pub out_dir: Option<String>,
+    pub format: Option<String>,
+    pub wrap: Option<usize>,
+    pub jobs: Option<usize>,
+    pub section_order: Vec<String>,
+    pub collapse: Vec<String>,
+    pub heading_level: Option<u8>,
+    pub locale: Option<String>,
+    pub sort: Option<String>,
+    /// Name patterns of the functions to document
+    pub include: Vec<String>,
+    /// Name patterns of the functions to leave out
+    pub exclude: Vec<String>,
+    pub document_private: bool,
+    /// Headings to use instead of the default section labels, by section name
+    pub labels: toml::Table,
+    pub templates: Option<String>,
+    pub theme: Option<String>,
+    pub toc: bool,
+    pub param_list: bool,
+    pub link_types: bool,
+    pub strict: bool,
+    pub front_matter: bool,
+    /// Keys to add to the front matter, turning it on
+    pub front_matter_keys: toml::Table,
+    /// Custom tags, mapping each name to how it renders
+    pub tags: toml::Table,
+    /// The package from the typst.toml beside the project file
+    #[serde(skip)]
+    pub package: Option<Package>,
+}
+
+impl Project {
+    /// Read the project file at `path`
+    /// A missing file is the same as an empty one, a malformed file is an error
+    pub fn load(path: &str) -> Result<Project, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Project::default()),
+            Err(e) => return Err(format!("{path}: {e}")),
+        };
+
+        let project: Project = toml::from_str(&contents).map_err(|e| format!("{path}: {e}"))?;
+
+        if let Some(format) = &project.format
+            && Format::from_name(format).is_none()
+        {
+            return Err(format!(
+                "{path}: unknown format `{format}`, expected one of: markdown, text, json, html, asciidoc, rst, man, mdbook, mdx"
+            ));
+        }
+
+        if let Some(lang) = &project.lang
+            && Language::from_name(lang).is_none()
+        {
+            return Err(format!(
+                "{path}: unknown lang `{lang}`, expected one of: typst, python, rust, c"
+            ));
+        }
+
+        if let Some(locale) = &project.locale
+            && Locale::from_name(locale).is_none()
+        {
+            return Err(format!(
+                "{path}: unknown locale `{locale}`, expected one of: en, de"
+            ));
+        }
+
+        if let Some(sort) = &project.sort
+            && Sort::from_name(sort).is_none()
+        {
+            return Err(format!(
+                "{path}: unknown sort `{sort}`, expected one of: source, alpha, group"
+            ));
+        }
+
+        Ok(project)
+    }
+
+    /// Fill in the inputs the command line left out
+    pub fn apply_input(&self, input: &mut InputArgs) {
+        if input.inputs.is_empty() {
+            input.inputs = self.inputs.clone();
+        }
+        if input.glob.is_none() {
+            input.glob = self.glob.clone();
+        }
+        if input.lang.is_none() {
+            input.lang = self.lang.as_deref().and_then(Language::from_name);
+        }
+        // a package is documented through its entrypoint unless told otherwise
+        if let Some(package) = &self.package
+            && input.inputs.is_empty()
+        {
+            input.inputs = vec![package.entrypoint.clone()];
+        }
+        input.package = self.package.clone();
+    }
+
+    /// Fill in the options the command line left out
+    pub fn apply(&self, args: &mut GenArgs) {
+        self.apply_input(&mut args.input);
+
+        // --next-to-source conflicts with an output file or directory
+        if args.output.is_none()
+            && args.out_dir.is_none()
+            && args.inject.is_none()
+            && !args.next_to_source
+        {
+            args.output = self.output.as_deref().map(expand_path);
+            args.out_dir = self.out_dir.as_deref().map(expand_path);
+            args.inject = self.inject.as_deref().map(expand_path);
+        }
+        if args.format.is_none() {
+            args.format = self.format.as_deref().and_then(Format::from_name);
+        }
+        if args.wrap.is_none() {
+            args.wrap = self.wrap;
+        }
+        if args.jobs.is_none() {
+            args.jobs = self.jobs;
+        }
+        args.toc |= self.toc;
+        args.param_list |= self.param_list;
+        args.link_types |= self.link_types;
+        args.strict |= self.strict;
+        args.front_matter |= self.front_matter;
+        args.document_private |= self.document_private;
+        if args.section_order.is_empty() {
+            args.section_order = self.section_order.clone();
+        }
+        if args.collapse.is_empty() {
+            args.collapse = self.collapse.clone();
+        }
+        if args.include.is_empty() {
+            args.include = self.include.clone();
+        }
+        if args.exclude.is_empty() {
+            args.exclude = self.exclude.clone();
+        }
+        if args.heading_level.is_none() {
+            args.heading_level = self.heading_level;
+        }
+        if args.locale.is_none() {
+            args.locale = self.locale.as_deref().and_then(Locale::from_name);
+        }
+        if args.sort.is_none() {
+            args.sort = self.sort.as_deref().and_then(Sort::from_name);
+        }
+        if args.templates.is_none() {
+            args.templates = self.templates.as_deref().map(expand_path);
+        }
+        if args.theme.is_none() {
+            args.theme = self.theme.as_deref().map(expand_path);
+        }
+        if args.labels.is_empty() {
+            args.labels = self
+                .labels
+                .iter()
+                .map(|(name, text)| format!("{name}={}", text.as_str().unwrap_or_default()))
+                .collect();
+        }
+        if args.front_matter_keys.is_empty() {
+            // strings are written without their quotes, other values as toml wrote them
+            args.front_matter_keys = self
+                .front_matter_keys
+                .iter()
+                .map(|(key, value)| match value.as_str() {
+                    Some(text) => format!("{key}={text}"),
+                    None => format!("{key}={value}"),
+                })
+                .collect();
+        }
+        if args.tags.is_empty() {
+            args.tags = self
+                .tags
+                .iter()
+                .map(|(name, style)| format!("{name}={}", style.as_str().unwrap_or_default()))
+                .collect();
+        }
+    }
+}
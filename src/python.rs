@@ -0,0 +1,647 @@
+use regex::Regex;
+
+use crate::{
+    Definition, DocComment, Error, Example, Language, Param, Result, Return, Span,
+    grammar::union,
+    markdown::slugs,
+    parse::{dedent, offset, signature},
+    warn,
+};
+
+/// Parses a python file into a DocComment per `def` or `class` with a docstring,
+/// reading Google style `Args:` or NumPy style `Parameters` sections into its params
+/// The title is the name of the function or class, the description is everything
+/// before the first section, and annotations fill in the types the docstring leaves out
+pub fn parse_python(input: &str) -> Vec<Result<DocComment>> {
+    parse(input, &Language::python())
+}
+
+/// Parses a file of a language with the `Docstring` syntax
+pub(crate) fn parse(input: &str, language: &Language) -> Vec<Result<DocComment>> {
+    let mut docs: Vec<Result<DocComment>> = docstrings(input, language)
+        .into_iter()
+        .map(|found| {
+            let (statement, lines) = found?;
+            Ok(parse_docstring(&statement, &lines))
+        })
+        .collect();
+
+    // methods of different classes often share a name
+    let slugs = slugs(docs.iter().flatten().map(|d| d.title.as_str()));
+    docs.iter_mut()
+        .flatten()
+        .zip(slugs)
+        .for_each(|(doc, slug)| doc.slug = slug);
+
+    docs
+}
+
+/// Titles of every `def` or `class` with a docstring in a python file
+pub fn python_titles(input: &str) -> Vec<String> {
+    titles(input, &Language::python())
+}
+
+/// Titles of every documented definition in a file of a language with the `Docstring` syntax
+pub(crate) fn titles(input: &str, language: &Language) -> Vec<String> {
+    docstrings(input, language)
+        .into_iter()
+        .flatten()
+        .map(|(statement, _)| statement.name)
+        .collect()
+}
+
+/// A `def` or `class` statement, joined onto a single line
+struct Statement {
+    name: String,
+    /// Everything after the name up to the closing :, e.g. "(a, b: int = 1) -> int"
+    rest: String,
+    is_class: bool,
+}
+
+/// The lines of a docstring, each with where it starts in the file
+type DocLines<'a> = Vec<(&'a str, Span)>;
+
+/// Every docstring in a python file, with the statement it documents
+fn docstrings<'a>(input: &'a str, language: &Language) -> Vec<Result<(Statement, DocLines<'a>)>> {
+    let Some(def_re) = signature(language) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = input.lines().collect();
+    let mut found = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let Some(caps) = def_re
+            .captures(lines[i])
+            .filter(|c| c.name("name").is_some())
+        else {
+            i += 1;
+            continue;
+        };
+
+        // the statement may run over several lines, e.g. one argument per line
+        let mut rest = lines[i][caps.get(0).map_or(0, |m| m.end())..].to_string();
+        let mut end = i;
+        while !ends_statement(&rest) && end + 1 < lines.len() {
+            end += 1;
+            rest.push(' ');
+            rest.push_str(lines[end].trim());
+        }
+        i = end + 1;
+
+        // the docstring is the first statement of the body, comments aside
+        let body = lines[i..]
+            .iter()
+            .position(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+            .map(|skipped| i + skipped);
+        let Some(docstring) = body.and_then(|start| docstring(&lines, start)) else {
+            continue;
+        };
+
+        let statement = Statement {
+            name: caps["name"].to_string(),
+            rest: rest.trim().to_string(),
+            is_class: caps.name("kind").is_some_and(|k| k.as_str() == "class"),
+        };
+        found.push(docstring.map(|doc_lines| (statement, doc_lines)));
+    }
+
+    found
+}
+
+/// Whether `text` reaches the : that ends a def or class statement
+fn ends_statement(text: &str) -> bool {
+    find_top(text, ':').is_some()
+}
+
+/// The docstring starting on line `start` of `lines`, with its quotes and the
+/// indentation shared by its lines stripped, `None` if that line is not a string
+/// Fails if the string is never closed
+fn docstring<'a>(lines: &[&'a str], start: usize) -> Option<Result<DocLines<'a>>> {
+    let first = lines[start].trim_start();
+    let opened = first.trim_start_matches(['r', 'R', 'u', 'U']);
+    let quote = ["\"\"\"", "'''"]
+        .into_iter()
+        .find(|q| opened.starts_with(q))?;
+
+    let mut text = Vec::new();
+    for (i, line) in lines[start..].iter().enumerate() {
+        let inside = match i {
+            0 => &opened[quote.len()..],
+            _ => line,
+        };
+
+        match inside.find(quote) {
+            Some(end) => {
+                text.push((&inside[..end], start + i));
+                return Some(Ok(strip_indent(&text, lines)));
+            }
+            None => text.push((inside, start + i)),
+        }
+    }
+
+    let span = Span {
+        line: start + 1,
+        column: lines[start].len() - first.len() + 1,
+    };
+    Some(Err(Error::UnclosedDocstring { span }))
+}
+
+/// Strip the indentation shared by every line of a docstring but the first,
+/// which starts right after the quotes, as PEP 257 does
+/// Lines are given with their index in `lines`, and come back with where they start
+fn strip_indent<'a>(text: &[(&'a str, usize)], lines: &[&'a str]) -> DocLines<'a> {
+    let indent = text
+        .iter()
+        .skip(1)
+        .filter(|(l, _)| !l.trim().is_empty())
+        .map(|(l, _)| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    text.iter()
+        .enumerate()
+        .map(|(i, (l, index))| {
+            let l = match i {
+                0 => l.trim_start(),
+                _ => l.get(indent..).unwrap_or_default(),
+            };
+            let line = lines[*index];
+            let span = Span {
+                line: index + 1,
+                column: line[..offset(line, l)].chars().count() + 1,
+            };
+            (l.trim_end(), span)
+        })
+        .collect()
+}
+
+/// A docstring section, from its heading in either style
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Params,
+    Returns,
+    Raises,
+    Examples,
+    Notes,
+    SeeAlso,
+    Deprecated,
+}
+
+impl Kind {
+    /// The section a heading starts, e.g. "Args" or "Parameters"
+    fn from_heading(heading: &str) -> Option<Kind> {
+        match heading.to_lowercase().as_str() {
+            "args" | "arguments" | "params" | "parameters" | "keyword args"
+            | "keyword arguments" | "other parameters" => Some(Kind::Params),
+            "returns" | "return" | "yields" | "yield" => Some(Kind::Returns),
+            "raises" | "raise" => Some(Kind::Raises),
+            "example" | "examples" => Some(Kind::Examples),
+            "note" | "notes" | "warning" | "warnings" => Some(Kind::Notes),
+            "see also" => Some(Kind::SeeAlso),
+            "deprecated" => Some(Kind::Deprecated),
+            _ => None,
+        }
+    }
+}
+
+/// A section of a docstring, with the lines under its heading
+struct SectionLines<'a, 'b> {
+    kind: Kind,
+    /// Index of the heading in the docstring
+    heading: usize,
+    /// NumPy sections underline their heading, Google sections end it with a colon
+    numpy: bool,
+    lines: &'b [(&'a str, Span)],
+}
+
+/// A line under a section heading, with the more indented lines continuing it
+struct Entry<'a> {
+    line: &'a str,
+    span: Span,
+    /// The continuing lines, joined onto a single line
+    more: String,
+}
+
+impl Entry<'_> {
+    /// `first` followed by the continuing lines
+    fn description(&self, first: &str) -> String {
+        format!("{first} {}", self.more).trim().to_string()
+    }
+}
+
+/// Parses the lines of a docstring into a DocComment for `statement`
+fn parse_docstring(statement: &Statement, lines: &[(&str, Span)]) -> DocComment {
+    let sections = sections(lines);
+    let intro = &lines[..sections.first().map_or(lines.len(), |s| s.heading)];
+
+    let description: Vec<&str> = intro
+        .iter()
+        .map(|(l, _)| l.trim())
+        .filter(|l| !l.is_empty())
+        .collect();
+    let mut doc = DocComment::new(&statement.name, description.join(" "));
+    doc.span = lines
+        .iter()
+        .find(|(l, _)| !l.trim().is_empty())
+        .map_or(lines[0].1, |(_, span)| *span);
+
+    for section in &sections {
+        doc = match section.kind {
+            Kind::Params => params(doc, section),
+            Kind::Returns => returns(doc, section),
+            Kind::Raises => entries(section.lines)
+                .iter()
+                .map(|e| match e.line.split_once(':') {
+                    Some((error, desc)) => format!("{error}: {}", e.description(desc.trim())),
+                    None if !e.more.is_empty() => format!("{}: {}", e.line, e.more),
+                    None => e.line.to_string(),
+                })
+                .fold(doc, DocComment::with_error),
+            Kind::Examples => {
+                let code: String = section
+                    .lines
+                    .iter()
+                    .map(|(l, _)| format!("{l}\n"))
+                    .collect();
+                let example = Example {
+                    code: dedent(code.trim_matches('\n')),
+                    ..Example::default().with_language("python")
+                };
+                doc.with_example(example)
+            }
+            Kind::Notes => paragraphs(section.lines)
+                .into_iter()
+                .fold(doc, DocComment::with_note),
+            Kind::SeeAlso => entries(section.lines)
+                .iter()
+                .flat_map(|e| e.line.split(':').next().unwrap_or_default().split(','))
+                .map(|title| title.trim().trim_end_matches("()"))
+                .filter(|title| !title.is_empty())
+                .fold(doc, DocComment::with_see),
+            Kind::Deprecated => {
+                let message = paragraphs(section.lines).join(" ");
+                doc.with_deprecated(message)
+            }
+        };
+    }
+
+    let (definition, annotation) = definition(statement).unzip();
+
+    // the annotation types a Returns section that doesn't give one itself,
+    // and without either the return could be anything
+    let annotation = annotation.flatten().unwrap_or_else(|| "Any".to_string());
+    for ret in doc.returns.iter_mut().filter(|r| r.data_type.is_empty()) {
+        ret.data_type = union(&annotation);
+    }
+
+    match definition {
+        Some(definition) => doc.with_definition(definition),
+        None => doc,
+    }
+}
+
+/// Split the lines of a docstring into its sections, leaving out the lines before the first
+fn sections<'a, 'b>(lines: &'b [(&'a str, Span)]) -> Vec<SectionLines<'a, 'b>> {
+    let mut headings = Vec::new();
+
+    for (i, (line, _)) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        let underlined = lines.get(i + 1).is_some_and(|(next, _)| {
+            let next = next.trim();
+            next.len() >= 3 && next.chars().all(|c| c == '-')
+        });
+
+        if let Some(kind) = trimmed.strip_suffix(':').and_then(Kind::from_heading) {
+            headings.push((i, kind, false));
+        } else if let Some(kind) = Kind::from_heading(trimmed).filter(|_| underlined) {
+            headings.push((i, kind, true));
+        }
+    }
+
+    headings
+        .iter()
+        .enumerate()
+        .map(|(h, &(i, kind, numpy))| {
+            let start = i + if numpy { 2 } else { 1 };
+            let end = headings
+                .get(h + 1)
+                .map_or(lines.len(), |(next, _, _)| *next);
+            SectionLines {
+                kind,
+                heading: i,
+                numpy,
+                lines: &lines[start.min(end)..end],
+            }
+        })
+        .collect()
+}
+
+/// The entries of a section, each a line at the section's indentation
+/// with the lines indented further joined onto it
+fn entries<'a>(lines: &[(&'a str, Span)]) -> Vec<Entry<'a>> {
+    let indent = |l: &str| l.len() - l.trim_start().len();
+    let base = lines
+        .iter()
+        .filter(|(l, _)| !l.trim().is_empty())
+        .map(|(l, _)| indent(l))
+        .min()
+        .unwrap_or(0);
+
+    let mut entries: Vec<Entry> = Vec::new();
+    for (line, span) in lines.iter().filter(|(l, _)| !l.trim().is_empty()) {
+        match entries.last_mut() {
+            Some(entry) if indent(line) > base => {
+                if !entry.more.is_empty() {
+                    entry.more.push(' ');
+                }
+                entry.more.push_str(line.trim());
+            }
+            _ => entries.push(Entry {
+                line: line.trim(),
+                span: Span {
+                    line: span.line,
+                    column: span.column + indent(line),
+                },
+                more: String::new(),
+            }),
+        }
+    }
+
+    entries
+}
+
+/// The paragraphs of a section, each joined onto a single line
+fn paragraphs(lines: &[(&str, Span)]) -> Vec<String> {
+    lines
+        .split(|(l, _)| l.trim().is_empty())
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            p.iter()
+                .map(|(l, _)| l.trim())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// Add the params of an `Args:` or `Parameters` section
+/// e.g. "points (int, optional): Points the question is worth" in Google style
+/// or "points : int, optional" with the description below it in NumPy style
+fn params(mut doc: DocComment, section: &SectionLines) -> DocComment {
+    let google_re =
+        Regex::new(r"^(?P<name>\*{0,2}\w+)\s*(?:\((?P<type>.*?)\))?\s*:(?P<desc>.*)$").unwrap();
+    let numpy_re =
+        Regex::new(r"^(?P<names>\*{0,2}\w+(?:\s*,\s*\*{0,2}\w+)*)\s*(?::(?P<type>.*))?$").unwrap();
+
+    for entry in entries(section.lines) {
+        let (names, data_type, description) = match section.numpy {
+            false => google_re.captures(entry.line).map(|caps| {
+                let desc = entry.description(caps["desc"].trim());
+                (vec![caps["name"].to_string()], caps.name("type"), desc)
+            }),
+            // several params may share a line, e.g. "x, y : float"
+            true => numpy_re.captures(entry.line).map(|caps| {
+                let names = caps["names"].split(',').map(|n| n.trim().to_string());
+                (names.collect(), caps.name("type"), entry.more.clone())
+            }),
+        }
+        .unwrap_or_else(|| {
+            warn(format!(
+                "{} has a param `{}` without a name, skipping it",
+                doc.title, entry.line
+            ));
+            (Vec::new(), None, String::new())
+        });
+
+        let (data_type, default) = param_type(data_type.map_or("", |t| t.as_str()));
+        for name in names {
+            if doc.params.iter().any(|existing| existing.name == name) {
+                warn(format!(
+                    "{} documents param {name} more than once, keeping the first",
+                    doc.title
+                ));
+                continue;
+            }
+
+            doc = doc.with_param(Param {
+                name,
+                data_type: union(data_type),
+                default: default.map(str::to_string),
+                description: description.clone(),
+                span: Some(entry.span),
+                ..Default::default()
+            });
+        }
+    }
+
+    doc
+}
+
+/// The type of a param less the `optional` marker, and its default if the type gives one
+/// e.g. "int, optional" -> ("int", None), "int, default: 1" -> ("int", Some("1"))
+fn param_type(text: &str) -> (&str, Option<&str>) {
+    let default_re =
+        Regex::new(r",?\s*default(?:\s*[:=]\s*|\s+is\s+|\s+)(?P<default>.+)$").unwrap();
+
+    let (text, default) = match default_re.captures(text) {
+        Some(caps) => (
+            &text[..caps.get(0).map_or(text.len(), |m| m.start())],
+            caps.name("default").map(|d| d.as_str().trim()),
+        ),
+        None => (text, None),
+    };
+
+    let text = text.trim();
+    let text = text.strip_suffix("optional").unwrap_or(text).trim();
+    (text.trim_end_matches(',').trim(), default)
+}
+
+/// Add the return of a `Returns:` or `Returns` section
+/// e.g. "int: The sum" in Google style, or "int" with the description below it in NumPy style
+fn returns(doc: DocComment, section: &SectionLines) -> DocComment {
+    let entries = entries(section.lines);
+    let Some(first) = entries.first() else {
+        return doc;
+    };
+
+    let (data_type, description) = match section.numpy {
+        // a named return, e.g. "total : int"
+        true => match first.line.split_once(" : ") {
+            Some((_, data_type)) => (data_type.trim().to_string(), first.more.clone()),
+            None => (first.line.to_string(), first.more.clone()),
+        },
+        // the whole section is the description, which may start with the type
+        false => {
+            let text = paragraphs(section.lines).join(" ");
+            match text.split_once(':') {
+                Some((data_type, desc)) if is_type(data_type) => {
+                    (data_type.trim().to_string(), desc.trim().to_string())
+                }
+                _ => (String::new(), text),
+            }
+        }
+    };
+
+    let mut ret = Return::new(&data_type).with_description(description);
+    ret.span = Some(first.span);
+    doc.with_return(ret)
+}
+
+/// Whether `text` reads as a type rather than prose, having no spaces
+/// outside of brackets other than around a |, e.g. "dict[str, int] | None"
+fn is_type(text: &str) -> bool {
+    let text = text.trim().replace(" | ", "|");
+    let mut depth = 0usize;
+    !text.is_empty()
+        && text.chars().all(|c| {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            depth > 0 || !c.is_whitespace()
+        })
+}
+
+/// The definition of a `def` statement, and its return annotation
+/// `None` for classes, whose arguments are their base classes
+fn definition(statement: &Statement) -> Option<(Definition, Option<String>)> {
+    if statement.is_class {
+        return None;
+    }
+
+    let rest = statement.rest.trim_start().strip_prefix('(')?;
+    let close = find_top(rest, ')')?;
+    let after = &rest[close + 1..];
+    let annotation = after
+        .trim_start()
+        .strip_prefix("->")
+        .and_then(|a| Some(a[..find_top(a, ':')?].trim().to_string()))
+        .filter(|a| !a.is_empty() && a != "None");
+
+    let params = split_top(&rest[..close], ',')
+        .into_iter()
+        .map(str::trim)
+        // the receiver and the markers for positional or keyword only params aren't arguments
+        .filter(|a| !matches!(*a, "" | "self" | "cls" | "/" | "*"))
+        .map(|a| {
+            let (a, default) = match find_top(a, '=') {
+                Some(eq) => (&a[..eq], Some(a[eq + 1..].trim().to_string())),
+                None => (a, None),
+            };
+            let (name, data_type) = match a.split_once(':') {
+                Some((name, data_type)) => (name.trim(), union(data_type)),
+                None => (a.trim(), Vec::new()),
+            };
+            Param {
+                name: name.to_string(),
+                data_type,
+                default,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let definition = Definition {
+        name: statement.name.clone(),
+        params,
+        ..Default::default()
+    };
+    Some((definition, annotation))
+}
+
+/// Split `text` at every `sep` outside of brackets and strings
+fn split_top(text: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(i) = find_top(rest, sep) {
+        parts.push(&rest[..i]);
+        rest = &rest[i + sep.len_utf8()..];
+    }
+    parts.push(rest);
+    parts
+}
+
+/// Byte index of the first `c` in `text` outside of brackets and strings
+fn find_top(text: &str, c: char) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (i, ch) in text.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (quote, ch) {
+            (Some(_), '\\') => escaped = true,
+            (Some(q), ch) if ch == q => quote = None,
+            (Some(_), _) => {}
+            (None, ch) if ch == c && depth == 0 => return Some(i),
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every doc comment of a python file, failing the test on any that doesn't parse
+    fn docs(input: &str) -> Vec<DocComment> {
+        parse_python(input)
+            .into_iter()
+            .collect::<Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn google_docstring_with_args_and_returns() {
+        let docs = docs(
+            "def add(a: int, b: int = 1) -> int:\n    \"\"\"Add two numbers.\n\n    Args:\n        a: the first\n        b: the second\n\n    Returns:\n        the sum\n    \"\"\"\n    return a + b\n",
+        );
+
+        assert_eq!(docs[0].title, "add");
+        assert_eq!(docs[0].description, "Add two numbers.");
+        assert_eq!(docs[0].params.len(), 2);
+        assert_eq!(docs[0].params[0].description, "the first");
+        // types and defaults come from the annotations
+        assert_eq!(docs[0].params[1].data_type, ["int"]);
+        assert_eq!(docs[0].params[1].default.as_deref(), Some("1"));
+        assert_eq!(docs[0].returns[0].data_type, ["int"]);
+        assert_eq!(docs[0].returns[0].description, "the sum");
+    }
+
+    #[test]
+    fn numpy_docstring_parameters() {
+        let docs = docs(
+            "def scale(x, factor):\n    \"\"\"Scale a value.\n\n    Parameters\n    ----------\n    x : float\n        the value\n    factor : float\n        how much\n    \"\"\"\n",
+        );
+
+        assert_eq!(docs[0].params.len(), 2);
+        assert_eq!(docs[0].params[0].data_type, ["float"]);
+        assert_eq!(docs[0].params[1].description, "how much");
+    }
+
+    #[test]
+    fn docstring_without_a_description() {
+        let docs = docs("def bare(a):\n    \"\"\"\n    Args:\n        a: the value\n    \"\"\"\n");
+
+        assert_eq!(docs[0].title, "bare");
+        assert_eq!(docs[0].description, "");
+        assert_eq!(docs[0].params[0].description, "the value");
+    }
+
+    #[test]
+    fn string_outside_a_definition_is_not_documentation() {
+        let docs = docs("\"\"\"A module docstring\"\"\"\n\nx = 1\n");
+
+        assert!(docs.is_empty());
+    }
+}
@@ -0,0 +1,723 @@
+use std::{
+    collections::HashSet,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    Asciidocable, Config, DocComment, Format, Kind, Manable, Markdownable, Package, Rstable, Sort,
+    Textable, anchor, html, html::Htmlable, markdown, markdown::unique_slug, slugs,
+};
+
+/// Render every doc comment of a single file in the configured format
+/// JSON is rendered as an array of the parsed doc comments,
+/// and html as a sequence of sections without the surrounding page
+/// The doc comment of the file itself, if any, comes first as its introduction
+pub fn render(docs: &[DocComment], config: &Config) -> String {
+    let (modules, docs): (Vec<&DocComment>, Vec<&DocComment>) = docs.iter().partition(|d| d.module);
+    let intro: String = modules.iter().map(|d| intro(d, config)).collect();
+
+    let body: String = match config.format {
+        Format::Markdown | Format::Mdbook | Format::Mdx => {
+            // other items follow the functions, under a heading per kind
+            let of_kind = |kind| -> Vec<&DocComment> {
+                docs.iter().copied().filter(|d| d.kind() == kind).collect()
+            };
+            let functions = of_kind(Kind::Function);
+            let others: Vec<(&str, Vec<&DocComment>)> = Kind::ALL[1..]
+                .iter()
+                .map(|&kind| (config.locale.kinds(kind), of_kind(kind)))
+                .filter(|(_, docs)| !docs.is_empty())
+                .collect();
+            match grouped(&functions, config) {
+                true => categories(&functions, &others, config),
+                false => {
+                    let body: String = functions
+                        .iter()
+                        .map(|d| format!("{}\n", markdown(d, config)))
+                        .collect();
+                    let listed = functions.iter().chain(others.iter().flat_map(|(_, d)| d));
+                    let contents = contents(listed.copied(), config);
+                    let others: String = others
+                        .iter()
+                        .map(|(heading, docs)| section(heading, docs, config))
+                        .collect();
+                    format!("{contents}{body}{others}")
+                }
+            }
+        }
+        Format::Text => docs
+            .iter()
+            .map(|d| format!("{}\n", d.text(config)))
+            .collect(),
+        Format::Json => {
+            let all: Vec<&DocComment> = modules.iter().chain(&docs).copied().collect();
+            let json = serde_json::to_string_pretty(&all).expect("doc comments are valid json");
+            return format!("{json}\n");
+        }
+        Format::Html => docs.iter().map(|d| d.html(config)).collect(),
+        Format::Asciidoc => docs.iter().map(|d| d.asciidoc(config)).collect(),
+        Format::Rst => docs.iter().map(|d| d.rst(config)).collect(),
+        Format::Man => docs.iter().map(|d| d.man(config)).collect(),
+    };
+    format!("{intro}{body}")
+}
+
+/// The introduction to a file from its doc comment, the description and examples
+/// Man pages have no room for one
+fn intro(doc: &DocComment, config: &Config) -> String {
+    match config.format {
+        Format::Markdown | Format::Mdbook => markdown::intro(doc, config),
+        Format::Mdx => mdx_escape(&markdown::intro(doc, config)),
+        Format::Html => html::intro(doc, config),
+        Format::Text | Format::Asciidoc | Format::Rst => {
+            format!("{}\n\n", config.wrap(&doc.description))
+        }
+        Format::Json | Format::Man => String::new(),
+    }
+}
+
+/// Put the doc comments of a file in the order `sort`
+pub fn sort(docs: &mut [DocComment], sort: Sort) {
+    match sort {
+        Sort::Source => {}
+        Sort::Alpha => docs.sort_by_key(|d| d.title.to_lowercase()),
+        Sort::Group => {
+            let mut categories: Vec<String> = Vec::new();
+            for category in docs.iter().filter_map(DocComment::category) {
+                if !categories.iter().any(|c| c == category) {
+                    categories.push(category.to_string());
+                }
+            }
+            docs.sort_by_key(|d| {
+                d.category()
+                    .and_then(|c| categories.iter().position(|known| known == c))
+                    .unwrap_or(categories.len())
+            });
+        }
+    }
+}
+
+/// The markdown of a doc comment, escaped for mdx when that is the format
+fn markdown(doc: &DocComment, config: &Config) -> String {
+    match config.format {
+        Format::Mdx => mdx_escape(&doc.markdown(config)),
+        _ => doc.markdown(config),
+    }
+}
+
+/// Whether `docs` render under a heading per @category,
+/// which they do when sorted by group and any of them has one
+fn grouped(docs: &[&DocComment], config: &Config) -> bool {
+    config.sort == Sort::Group && docs.iter().any(|d| d.category().is_some())
+}
+
+/// The markdown of `functions` under a heading per @category, in the order categories
+/// first appear, and of the `others` after them, given as (heading, docs)
+/// Functions without a category go after the rest
+/// With the table of contents turned on, the file starts with a list of the headings
+fn categories(
+    functions: &[&DocComment],
+    others: &[(&str, Vec<&DocComment>)],
+    config: &Config,
+) -> String {
+    let mut groups: Vec<(&str, Vec<&DocComment>)> = Vec::new();
+    for doc in functions {
+        let category = doc.category().unwrap_or(config.locale.uncategorized());
+        match groups.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, group)) => group.push(doc),
+            None => groups.push((category, vec![doc])),
+        }
+    }
+    if let Some(last) = groups.iter().position(|(_, g)| g[0].category().is_none()) {
+        let other = groups.remove(last);
+        groups.push(other);
+    }
+    groups.extend(others.iter().cloned());
+
+    let mut md = String::new();
+    if config.toc {
+        let mut used: HashSet<String> = functions
+            .iter()
+            .chain(others.iter().flat_map(|(_, docs)| docs))
+            .map(|d| d.slug.clone())
+            .collect();
+        md.push_str("**Contents**\n\n");
+        for (heading, _) in &groups {
+            let slug = unique_slug(heading, &mut used);
+            md.push_str(&format!("- [{heading}](#{slug})\n"));
+        }
+        md.push('\n');
+    }
+    for (heading, group) in &groups {
+        md.push_str(&section(heading, group, config));
+    }
+    md
+}
+
+/// The markdown of `docs` under `heading`, starting with a list of them,
+/// with every heading of theirs one level deeper
+/// Empty when there are no docs
+fn section(heading: &str, docs: &[&DocComment], config: &Config) -> String {
+    if docs.is_empty() {
+        return String::new();
+    }
+
+    let nested = Config {
+        heading_level: Some(config.heading_level.unwrap_or(2) + 1),
+        ..config.clone()
+    };
+    let mut md = format!("{} {heading}\n\n", config.heading(0));
+    md.push_str(&toc(docs.iter().copied(), ""));
+    md.push('\n');
+    for doc in docs {
+        md.push_str(&markdown(doc, &nested));
+        md.push('\n');
+    }
+    md
+}
+
+/// The table of contents at the top of a markdown file, if it is turned on
+fn contents<'a>(docs: impl IntoIterator<Item = &'a DocComment>, config: &Config) -> String {
+    let toc = toc(docs, "");
+    match config.toc && !toc.is_empty() {
+        true => format!("**Contents**\n\n{toc}\n"),
+        false => String::new(),
+    }
+}
+
+/// A markdown list linking to every doc comment by the anchor of its heading
+/// `page` is put in front of every link, e.g. "exam.md" -> "exam.md#question"
+pub fn toc<'a>(docs: impl IntoIterator<Item = &'a DocComment>, page: &str) -> String {
+    docs.into_iter()
+        .filter(|d| !d.module)
+        .map(|d| format!("- [{}]({page}#{})\n", d.title, d.slug))
+        .collect()
+}
+
+/// A table of contents across files, given as (path, link to its docs, docs),
+/// with a heading per file listing every doc comment in it
+pub fn toc_index(files: &[(String, String, &[DocComment])]) -> String {
+    let mut index = String::from("# Contents\n");
+
+    for (file, page, docs) in files {
+        match page.is_empty() {
+            true => index.push_str(&format!("\n## {file}\n\n")),
+            false => index.push_str(&format!("\n## [{file}]({page})\n\n")),
+        }
+        index.push_str(&toc(*docs, page));
+    }
+
+    index
+}
+
+/// An alphabetical index of every doc comment across files, given as
+/// (path, link to its docs, docs), with a heading per first letter
+/// Each entry links to the full docs and gives the first line of the description,
+/// and each @alias links to the docs of its function
+pub fn symbol_index(files: &[(String, String, &[DocComment])]) -> String {
+    // every doc comment is listed under its title, and again under each @alias
+    let mut entries: Vec<(&str, &DocComment, &str)> = files
+        .iter()
+        .flat_map(|(_, page, docs)| {
+            docs.iter().filter(|d| !d.module).flat_map(move |d| {
+                std::iter::once(d.title.as_str())
+                    .chain(d.aliases.iter().map(String::as_str))
+                    .map(move |name| (name, d, page.as_str()))
+            })
+        })
+        .collect();
+    // private functions sort by their name without the leading _
+    let key = |name: &str| name.trim_start_matches('_').to_lowercase();
+    entries.sort_by_cached_key(|(name, _, _)| (key(name), name.to_string()));
+
+    let mut index = String::from("# Index\n");
+    let mut letter = None;
+
+    for (name, doc, page) in entries {
+        let first = key(name).chars().next().map(|c| c.to_ascii_uppercase());
+        if first != letter {
+            letter = first;
+            index.push_str(&format!("\n## {}\n\n", first.unwrap_or('_')));
+        }
+
+        let link = format!("[{}]({page}#{})", doc.title, doc.slug);
+        match doc.summary() {
+            _ if name != doc.title => index.push_str(&format!("- {name} → {link}\n")),
+            "" => index.push_str(&format!("- {link}\n")),
+            summary => index.push_str(&format!("- {link} — {summary}\n")),
+        }
+    }
+
+    index
+}
+
+/// A JSON search index of every doc comment across files, given as
+/// (path, link to its docs, docs), for the search box of html pages
+/// Each entry has the name, summary, file, anchor and a link to the full docs
+pub fn search_index(files: &[(String, String, &[DocComment])]) -> String {
+    let entries: Vec<serde_json::Value> = files
+        .iter()
+        .flat_map(|(file, page, docs)| {
+            // each @alias finds the function too
+            docs.iter().filter(|d| !d.module).flat_map(move |d| {
+                std::iter::once(&d.title)
+                    .chain(&d.aliases)
+                    .map(move |name| {
+                        serde_json::json!({
+                            "name": name,
+                            "summary": d.summary(),
+                            "file": file,
+                            "anchor": d.slug,
+                            "url": format!("{page}#{}", d.slug),
+                        })
+                    })
+            })
+        })
+        .collect();
+
+    let json = serde_json::to_string_pretty(&entries).expect("index is valid json");
+    format!("{json}\n")
+}
+
+/// Finish the rendered output of a single file so it can be written on its own
+/// e.g. wrapping html in a page titled by the file name
+pub fn standalone(file: &str, rendered: &str, config: &Config) -> String {
+    match config.format {
+        Format::Html => {
+            // how many directories under the root of the docs the page is
+            let depth = page_path(file, config).components().count() - 1;
+            html::page(&file_title(file), rendered, config, depth)
+        }
+        Format::Mdbook => format!("# {}\n\n{rendered}", file_title(file)),
+        Format::Mdx => mdx_page(file, rendered, None),
+        Format::Asciidoc => format!("= {}\n\n{rendered}", file_title(file)),
+        Format::Rst => {
+            let title = file_title(file);
+            let rule = "=".repeat(title.chars().count());
+            format!("{rule}\n{title}\n{rule}\n\n{rendered}")
+        }
+        Format::Markdown => match &config.front_matter {
+            Some(keys) => format!("{}{rendered}", front_matter(file, keys)),
+            None => rendered.to_string(),
+        },
+        _ => rendered.to_string(),
+    }
+}
+
+/// YAML front matter for the page of `file`, as static site generators like Hugo and Jekyll read it,
+/// with its title, slug and `keys`, where a key named like one before it replaces it
+fn front_matter(file: &str, keys: &[(String, String)]) -> String {
+    let title = file_title(file);
+    let mut entries = vec![
+        // quoted as json, which is also valid yaml, since titles may hold any character
+        ("title".to_string(), serde_json::json!(title).to_string()),
+        ("slug".to_string(), anchor(&title)),
+    ];
+    for (key, value) in keys {
+        match entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value.clone(),
+            None => entries.push((key.clone(), value.clone())),
+        }
+    }
+
+    let lines: String = entries
+        .iter()
+        .map(|(key, value)| format!("{key}: {value}\n"))
+        .collect();
+    format!("---\n{lines}---\n\n")
+}
+
+/// Comment starting the region of a document `inject` fills with docs
+pub const INJECT_START: &str = "<!-- marker:start -->";
+/// Comment ending the region of a document `inject` fills with docs
+pub const INJECT_END: &str = "<!-- marker:end -->";
+
+/// Replace what is between the `<!-- marker:start -->` and `<!-- marker:end -->` comments
+/// of `document`, e.g. a README, with `docs`, leaving the rest of it as it is
+/// `None` if the document doesn't have both comments in that order
+pub fn inject(document: &str, docs: &str) -> Option<String> {
+    let start = document.find(INJECT_START)? + INJECT_START.len();
+    let end = start + document[start..].find(INJECT_END)?;
+
+    Some(format!(
+        "{}\n\n{}\n\n{}",
+        &document[..start],
+        docs.trim(),
+        &document[end..]
+    ))
+}
+
+/// An index of the packages of a workspace, given as (package, link to its docs),
+/// listing each with its version and description
+/// Html gets a page of its own, every other format a markdown list
+pub fn workspace_index(packages: &[(Package, String)], config: &Config) -> String {
+    match config.format {
+        Format::Html => {
+            let items: String = packages
+                .iter()
+                .map(|(package, link)| {
+                    let description = match package.description.as_deref() {
+                        Some(description) => format!(" {}", html::escape(description)),
+                        None => String::new(),
+                    };
+                    format!(
+                        "<li><a href=\"{}\">{}</a>{description}</li>\n",
+                        html::escape(link),
+                        html::escape(&package.title())
+                    )
+                })
+                .collect();
+            html::page("Packages", &format!("<ul>\n{items}</ul>\n"), config, 0)
+        }
+        _ => {
+            let items: String = packages
+                .iter()
+                .map(|(package, link)| {
+                    let description = match package.description.as_deref() {
+                        Some(description) => format!(": {description}"),
+                        None => String::new(),
+                    };
+                    format!("- [{}]({link}){description}\n", package.title())
+                })
+                .collect();
+            format!("# Packages\n\n{items}")
+        }
+    }
+}
+
+/// Split the rendered output of several files, given as (path, output),
+/// into the pages of an output directory, given as (path in the directory, contents)
+/// Each file becomes a page at the same relative path
+/// mdBook also gets a SUMMARY.md listing every chapter,
+/// and Docusaurus pages are positioned in the sidebar in input order
+/// Man pages are the exception, with a page per function instead of per file
+pub fn pages(files: &[(String, String)], config: &Config) -> Vec<(PathBuf, String)> {
+    if config.format == Format::Man {
+        return man_pages(files, config);
+    }
+
+    let mut pages: Vec<(PathBuf, String)> = files
+        .iter()
+        .enumerate()
+        .map(|(i, (f, rendered))| {
+            let page = match config.format {
+                Format::Mdx => mdx_page(f, rendered, Some(i + 1)),
+                _ => standalone(f, rendered, config),
+            };
+            (page_path(f, config), page)
+        })
+        .collect();
+
+    if config.format == Format::Mdbook {
+        let mut summary = String::from("# Summary\n\n");
+        files.iter().for_each(|(f, _)| {
+            summary.push_str(&format!("- [{}]({}.md)\n", file_title(f), page_id(f)));
+        });
+        pages.insert(0, (PathBuf::from("SUMMARY.md"), summary));
+    }
+
+    pages
+}
+
+/// A Docusaurus sidebar listing the page of every file in input order
+/// Meant to be spread into the `sidebars.js` of the site
+pub fn sidebar(files: &[(String, String)]) -> String {
+    let items: String = files
+        .iter()
+        .map(|(f, _)| format!("  '{}',\n", page_id(f)))
+        .collect();
+
+    format!("// generated by marker\nmodule.exports = [\n{items}];\n")
+}
+
+/// Split rendered man pages at every `.TH` into a page per function,
+/// named after the function, e.g. "question.3typst"
+fn man_pages(files: &[(String, String)], config: &Config) -> Vec<(PathBuf, String)> {
+    let mut pages: Vec<(PathBuf, String)> = Vec::new();
+
+    for line in files.iter().flat_map(|(_, rendered)| rendered.lines()) {
+        if let Some(header) = line.strip_prefix(".TH ") {
+            let name = header.split_whitespace().next().unwrap_or_default();
+            let path = PathBuf::from(format!("{name}.{}", config.format.extension()));
+            pages.push((path, String::new()));
+        }
+        if let Some((_, page)) = pages.last_mut() {
+            page.push_str(line);
+            page.push('\n');
+        }
+    }
+
+    pages
+}
+
+/// Put the front matter Docusaurus expects before the rendered docs of `file`
+fn mdx_page(file: &str, rendered: &str, position: Option<usize>) -> String {
+    let mut front_matter = format!("id: {id}\ntitle: {id}\n", id = file_title(file));
+    if let Some(position) = position {
+        front_matter.push_str(&format!("sidebar_position: {position}\n"));
+    }
+
+    format!("---\n{front_matter}---\n\n{rendered}")
+}
+
+/// Where the page for `file` goes inside an output directory
+/// e.g. "src/exam.typ" -> "src/exam.md", while "/abs/exam.typ" and "../exam.typ" -> "exam.md"
+/// Files under the config's root are placed relative to it instead
+pub fn page_path(file: &str, config: &Config) -> PathBuf {
+    let file = Path::new(file);
+    config
+        .root
+        .as_deref()
+        .and_then(|root| file.strip_prefix(root).ok())
+        .unwrap_or(file)
+        .with_extension(config.format.extension())
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect()
+}
+
+/// The relative path of the page for `file` without an extension, always using /
+/// which is how mdBook and Docusaurus link to pages
+fn page_id(file: &str) -> String {
+    let parts: Vec<String> = Path::new(file)
+        .with_extension("")
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    parts.join("/")
+}
+
+/// Escape the characters MDX would read as JSX or expressions, outside of code
+fn mdx_escape(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut fence: Option<String> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        let ticks: String = trimmed.chars().take_while(|&c| c == '`').collect();
+
+        match &fence {
+            // the fence is closed by a run of at least as many backticks
+            Some(open) => {
+                if ticks.len() >= open.len() && trimmed.trim_end() == ticks {
+                    fence = None;
+                }
+                out.push_str(line);
+            }
+            None if ticks.len() >= 3 => {
+                fence = Some(ticks);
+                out.push_str(line);
+            }
+            // collapsed sections are meant to be JSX
+            None if matches!(line, "<details>" | "</details>") || line.starts_with("<summary>") => {
+                out.push_str(line);
+            }
+            None => {
+                // alternate between prose and inline code at every backtick
+                for (i, part) in line.split('`').enumerate() {
+                    if i > 0 {
+                        out.push('`');
+                    }
+                    if i % 2 == 1 {
+                        out.push_str(part);
+                    } else {
+                        out.push_str(
+                            &part
+                                .replace('{', "\\{")
+                                .replace('}', "\\}")
+                                .replace('<', "\\<"),
+                        );
+                    }
+                }
+            }
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// The name of a file without its directory or extension
+fn file_title(file: &str) -> String {
+    Path::new(file)
+        .file_stem()
+        .map_or(file.to_string(), |s| s.to_string_lossy().to_string())
+}
+
+/// Combine the rendered output of several files, given as (path, output),
+/// into a single reference with a section per file and a table of contents
+/// across all of them, listing the titles of each file's doc comments
+/// `None` for formats that cannot nest files, functions and their sections
+pub fn single_file(
+    files: &[(String, String)],
+    titles: &[(String, Vec<String>)],
+    config: &Config,
+) -> Option<String> {
+    let titles_of = |file: &str| {
+        titles
+            .iter()
+            .find(|(f, _)| f == file)
+            .map_or(&[][..], |(_, t)| t.as_slice())
+    };
+
+    match config.format {
+        Format::Markdown => {
+            let mut toc = String::from("**Contents**\n\n");
+            let mut body = String::new();
+
+            // every heading of the document shares one set of anchors
+            let headings: Vec<&str> = files
+                .iter()
+                .flat_map(|(f, _)| {
+                    [f.as_str()]
+                        .into_iter()
+                        .chain(titles_of(f).iter().map(String::as_str))
+                })
+                .collect();
+            let mut slugs = slugs(headings).into_iter();
+
+            for (f, rendered) in files {
+                let slug = slugs.next().unwrap_or_default();
+                toc.push_str(&format!("- [{f}](#{slug})\n"));
+                titles_of(f)
+                    .iter()
+                    .zip(slugs.by_ref())
+                    .for_each(|(t, slug)| {
+                        toc.push_str(&format!("  - [{t}](#{slug})\n"));
+                    });
+                body.push_str(&format!("\n# {f}\n\n{rendered}"));
+            }
+
+            Some(format!("{}{toc}{body}", package_header(config)))
+        }
+        Format::Html => {
+            let mut toc = String::from("<nav>\n<ul>\n");
+            let mut body = String::new();
+
+            for (file, rendered) in files {
+                let f = html::escape(file);
+                toc.push_str(&format!("<li><a href=\"#{f}\">{f}</a>\n<ul>\n"));
+                let file_titles = titles_of(file);
+                let file_slugs = slugs(file_titles.iter().map(String::as_str));
+                file_titles.iter().zip(file_slugs).for_each(|(t, slug)| {
+                    toc.push_str(&format!(
+                        "<li><a href=\"#{}\">{}</a></li>\n",
+                        html::escape(&slug),
+                        html::escape(t)
+                    ));
+                });
+                toc.push_str("</ul>\n</li>\n");
+                body.push_str(&format!(
+                    "<article id=\"{f}\">\n<h1>{f}</h1>\n{rendered}</article>\n"
+                ));
+            }
+            toc.push_str("</ul>\n</nav>\n");
+
+            Some(html::page(
+                &docs_title(config),
+                &format!("{}{toc}{body}", package_header(config)),
+                config,
+                0,
+            ))
+        }
+        Format::Rst => {
+            // sphinx builds the table of contents from the sections
+            let body: String = files
+                .iter()
+                .map(|(f, rendered)| format!("\n{}", standalone(f, rendered, config)))
+                .collect();
+            Some(format!(
+                "{}.. contents::\n   :depth: 2\n{body}",
+                package_header(config)
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Combine the rendered output of several files, given as (path, output),
+/// into a single document
+/// JSON becomes an array of `{ "file", "docs" }`, and html a single page
+/// with an article per file
+pub fn combine(files: &[(String, String)], config: &Config) -> String {
+    match config.format {
+        Format::Json => {
+            let files: Vec<serde_json::Value> = files
+                .iter()
+                .map(|(f, rendered)| {
+                    let docs: serde_json::Value =
+                        serde_json::from_str(rendered).expect("rendered docs are valid json");
+                    serde_json::json!({ "file": f, "docs": docs })
+                })
+                .collect();
+            let json = serde_json::to_string_pretty(&files).expect("docs are valid json");
+            format!("{json}\n")
+        }
+        Format::Html => {
+            let body: String = files
+                .iter()
+                .map(|(f, rendered)| {
+                    format!(
+                        "<article>\n<h1>{}</h1>\n{rendered}</article>\n",
+                        html::escape(f)
+                    )
+                })
+                .collect();
+            html::page(
+                &docs_title(config),
+                &format!("{}{body}", package_header(config)),
+                config,
+                0,
+            )
+        }
+        _ => {
+            let body: String = files
+                .iter()
+                .map(|(_, rendered)| rendered.as_str())
+                .collect();
+            format!("{}{body}", package_header(config))
+        }
+    }
+}
+
+/// Title of docs covering several files, the package and its version when there is one
+fn docs_title(config: &Config) -> String {
+    config
+        .package
+        .as_ref()
+        .map_or("Documentation".to_string(), Package::title)
+}
+
+/// The start of docs covering several files, naming the package, its version and description,
+/// so readers know which release they describe
+/// Empty without a package, and for formats with nowhere to put it
+/// Html pages are already titled by the package, so only get the description
+fn package_header(config: &Config) -> String {
+    let Some(package) = &config.package else {
+        return String::new();
+    };
+    let title = package.title();
+    let description = package.description.as_deref().unwrap_or_default();
+    let paragraph = match description.is_empty() {
+        true => String::new(),
+        false => format!("{description}\n\n"),
+    };
+
+    match config.format {
+        Format::Markdown => format!("# {title}\n\n{paragraph}"),
+        Format::Text => {
+            let rule = "#".repeat(title.chars().count());
+            format!("{title}\n{rule}\n\n{paragraph}")
+        }
+        Format::Asciidoc => format!("= {title}\n\n{paragraph}"),
+        Format::Rst => {
+            let rule = "#".repeat(title.chars().count());
+            format!("{rule}\n{title}\n{rule}\n\n{paragraph}")
+        }
+        Format::Html if !description.is_empty() => {
+            format!("<p>{}</p>\n", html::escape(description))
+        }
+        _ => String::new(),
+    }
+}
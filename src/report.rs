@@ -0,0 +1,61 @@
+use marker_typ::{Error, Span};
+
+/// Report an error on stderr in the form `file:line:column: message`
+/// followed by the offending line of `source` with the column underlined
+pub fn error(file: &str, source: &str, e: &Error) {
+    let Some(span) = e.span() else {
+        eprintln!("error: {e}");
+        return;
+    };
+
+    eprintln!("error: {file}:{}:{}: {e}", span.line, span.column);
+
+    let Some(line) = source.lines().nth(span.line - 1) else {
+        return;
+    };
+    let line = line.trim_end();
+
+    // the gutter is as wide as the line number so the snippet lines up
+    let number = span.line.to_string();
+    let gutter = " ".repeat(number.len());
+    let width = line.chars().count().saturating_sub(span.column - 1).max(1);
+
+    eprintln!("{gutter} |");
+    eprintln!("{number} | {line}");
+    eprintln!(
+        "{gutter} | {}{}",
+        " ".repeat(span.column - 1),
+        "^".repeat(width)
+    );
+}
+
+/// Print a GitHub Actions workflow command on stdout, which shows `message` inline on
+/// `file` in pull requests as a `level` annotation, i.e. "warning" or "error",
+/// at `span` when it is known
+pub fn annotation(level: &str, file: &str, span: Option<Span>, title: Option<&str>, message: &str) {
+    let mut properties = vec![format!("file={}", escape_property(file))];
+    if let Some(span) = span {
+        properties.push(format!("line={},col={}", span.line, span.column));
+    }
+    if let Some(title) = title {
+        properties.push(format!("title={}", escape_property(title)));
+    }
+
+    println!(
+        "::{level} {}::{}",
+        properties.join(","),
+        escape_data(message)
+    );
+}
+
+/// Escape the message of a workflow command
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property of a workflow command, which also ends at `:` and `,`
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
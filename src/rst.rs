@@ -0,0 +1,278 @@
+use crate::{Config, DocComment, Example, Param, Return, Section, TagStyle};
+
+/// Trait representing the ability to be converted into reStructuredText
+pub trait Rstable {
+    fn rst(&self, config: &Config) -> String;
+}
+
+/// Indent of directive content
+const INDENT: &str = "   ";
+
+impl Rstable for DocComment {
+    /// Convert a DocComment into a section with a label Sphinx can reference
+    /// Params and the return become a field list
+    fn rst(&self, config: &Config) -> String {
+        let mut rst = String::new();
+
+        // quoted, as titles starting with _ would otherwise be anonymous targets
+        rst.push_str(&format!(".. _`{}`:\n\n", self.title));
+        rst.push_str(&format!("{}\n", self.title));
+        rst.push_str(&format!("{}\n\n", "-".repeat(self.title.chars().count())));
+        rst.push_str(&format!("``{}``\n\n", self.signature()));
+
+        if let Some(metadata) = config.metadata(self) {
+            rst.push_str(&format!("*{}*\n\n", escape(&metadata)));
+        }
+
+        let mut badges: Vec<String> = config
+            .tags_in(TagStyle::Badge)
+            .flat_map(|t| {
+                self.tag_values(&t.name).map(move |v| match v {
+                    "" => format!("``{}``", t.label()),
+                    v => format!("``{}: {v}``", t.label()),
+                })
+            })
+            .collect();
+        let marks = config.marks(self).into_iter();
+        badges.splice(0..0, marks.map(|label| format!("``{label}``")));
+        if !badges.is_empty() {
+            rst.push_str(&format!("{}\n\n", badges.join(" ")));
+        }
+
+        for tag in config.tags_in(TagStyle::KeyValue) {
+            self.tag_values(&tag.name).for_each(|v| {
+                rst.push_str(&format!(
+                    "**{}:** {}\n\n",
+                    tag.label(),
+                    config.wrap(&escape(v))
+                ));
+            });
+        }
+
+        if let Some(msg) = &self.deprecated {
+            rst.push_str(&format!(
+                ".. warning::\n{INDENT}**Deprecated** {}",
+                escape(msg)
+            ));
+            rst.push_str("\n\n");
+        }
+
+        for section in config.sections() {
+            match section {
+                Section::Description => {
+                    rst.push_str(&config.wrap(&escape(&self.description)));
+                    rst.push_str("\n\n");
+                }
+                Section::Params if !self.params.is_empty() => {
+                    self.params.iter().for_each(|p| {
+                        rst.push_str(&p.rst(config));
+                    });
+                    rst.push('\n');
+                }
+                Section::Returns if !self.returns.is_empty() => {
+                    match self.returns.as_slice() {
+                        [ret] => rst.push_str(&ret.rst(config)),
+                        // a single :returns: field holding a bullet list, as Sphinx allows one
+                        returns => {
+                            rst.push_str(":returns:\n");
+                            returns.iter().for_each(|r| {
+                                let field = r
+                                    .name
+                                    .as_ref()
+                                    .map(|n| format!("{n}: "))
+                                    .unwrap_or_default();
+                                let item = format!(
+                                    "{INDENT}- {field}``{}`` {}",
+                                    r.data_type.join(" | "),
+                                    escape(&r.description)
+                                );
+                                rst.push_str(&hanging(
+                                    item.trim_end(),
+                                    &format!("{INDENT}  "),
+                                    config,
+                                ));
+                            });
+                        }
+                    }
+                    rst.push('\n');
+                }
+                Section::Errors if !self.errors.is_empty() => {
+                    rst.push_str(&format!(
+                        ".. rubric:: {}\n\n",
+                        config.label(Section::Errors)
+                    ));
+
+                    self.errors.iter().for_each(|e| {
+                        rst.push_str(&hanging(&format!("- {}", escape(e)), "  ", config));
+                    });
+                    rst.push('\n');
+                }
+                Section::Examples if !self.examples.is_empty() => {
+                    rst.push_str(&format!(
+                        ".. rubric:: {}\n\n",
+                        config.label(Section::Examples)
+                    ));
+
+                    self.examples.iter().for_each(|e| {
+                        rst.push_str(&e.rst(config));
+                    });
+                }
+                Section::Notes if !self.notes.is_empty() => {
+                    rst.push_str(".. note::\n\n");
+
+                    self.notes.iter().for_each(|n| {
+                        rst.push_str(&format!("{INDENT}- {}\n", escape(n)));
+                    });
+                    rst.push('\n');
+                }
+                Section::SeeAlso if !self.see.is_empty() => {
+                    let refs: Vec<String> =
+                        self.see.iter().map(|s| format!(":ref:`{s}`")).collect();
+                    rst.push_str(&format!(".. seealso:: {}\n\n", refs.join(", ")));
+                }
+                _ => {}
+            }
+        }
+
+        for tag in config.tags_in(TagStyle::Section) {
+            let mut values = self.tag_values(&tag.name).peekable();
+            if values.peek().is_some() {
+                rst.push_str(&format!(".. rubric:: {}\n\n", tag.label()));
+
+                values.for_each(|v| {
+                    rst.push_str(&hanging(&format!("- {}", escape(v)), "  ", config));
+                });
+                rst.push('\n');
+            }
+        }
+
+        rst
+    }
+}
+
+impl Rstable for Param {
+    /// Convert a parameter into its `:param:` and `:type:` fields
+    fn rst(&self, config: &Config) -> String {
+        let requirement = config.locale.requirement(self.is_required());
+        let description = match &self.default {
+            Some(def) => format!(
+                "{} ({requirement}, default: ``{def}``)",
+                escape(&self.description)
+            ),
+            None => format!("{} ({requirement})", escape(&self.description)),
+        };
+
+        let field = format!(":param {}: {}", self.label(), description.trim());
+        let mut rst = hanging(field.trim_end(), INDENT, config);
+
+        // params only found in the definition may have no type
+        if !self.data_type.is_empty() {
+            rst.push_str(&format!(
+                ":type {}: {}\n",
+                self.label(),
+                self.types().join(" | ")
+            ));
+        }
+
+        rst
+    }
+}
+
+impl Rstable for Return {
+    /// Convert a Return into its `:returns:` and `:rtype:` fields
+    fn rst(&self, config: &Config) -> String {
+        let mut rst = String::new();
+
+        let field = self
+            .name
+            .as_ref()
+            .map(|n| format!("{n}: "))
+            .unwrap_or_default();
+        if !self.description.is_empty() || !field.is_empty() {
+            let field = format!(":returns: {field}{}", escape(&self.description));
+            rst.push_str(&hanging(field.trim_end(), INDENT, config));
+        }
+        rst.push_str(&format!(":rtype: {}\n", self.data_type.join(" | ")));
+
+        rst
+    }
+}
+
+impl Rstable for Example {
+    /// Convert an example into a code block directive in its language
+    /// Code is never wrapped
+    fn rst(&self, _config: &Config) -> String {
+        let mut rst = format!(".. code-block:: {}\n\n", self.language);
+
+        self.code.lines().for_each(|l| {
+            if l.is_empty() {
+                rst.push('\n');
+            } else {
+                rst.push_str(&format!("{INDENT}{l}\n"));
+            }
+        });
+        rst.push('\n');
+
+        if let Some(image) = &self.image {
+            rst.push_str(&format!(
+                ".. image:: {image}\n{INDENT}:alt: Output of the example\n\n"
+            ));
+        }
+
+        rst
+    }
+}
+
+/// Wrap `text` at the configured column, indenting every line after the first
+/// so list items and fields keep going instead of ending at the wrap
+fn hanging(text: &str, indent: &str, config: &Config) -> String {
+    config
+        .wrap(text)
+        .lines()
+        .enumerate()
+        .map(|(i, l)| match i {
+            0 => format!("{l}\n"),
+            _ => format!("{indent}{l}\n"),
+        })
+        .collect()
+}
+
+/// Escape the characters RST would read as markup
+/// `code` spans written like markdown become inline literals
+pub fn escape(text: &str) -> String {
+    text.split('`')
+        .enumerate()
+        .map(|(i, part)| {
+            if i % 2 == 1 {
+                format!("``{part}``")
+            } else {
+                part.chars()
+                    .map(|c| match c {
+                        '\\' | '*' | '_' | '|' => format!("\\{c}"),
+                        c => c.to_string(),
+                    })
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_block;
+
+    #[test]
+    fn prose_is_escaped() {
+        let doc = parse_block(
+            "f: Pick <b> or *a* | b_c, see `x * y`\n@param a int the <first> * | one\n@return int the *sum*\n",
+        )
+        .unwrap();
+        let rst = doc.rst(&Config::default());
+
+        // angle brackets are only markup inside `links <url>`_
+        assert!(rst.contains("Pick <b> or \\*a\\* \\| b\\_c, see ``x * y``\n"));
+        assert!(rst.contains(":param a: the <first> \\* \\| one (required)\n"));
+        assert!(rst.contains(":returns: the \\*sum\\*\n"));
+    }
+}
@@ -0,0 +1,454 @@
+use regex::Regex;
+
+use crate::{
+    Definition, DocComment, Example, Language, Param, Return, Span,
+    markdown::slugs,
+    parse::{dedent, gutter, offset, signature},
+    warn,
+};
+
+/// Fence attributes rustdoc reads that aren't a language, e.g. ```no_run
+const RUSTDOC_ATTRIBUTES: [&str; 6] = [
+    "ignore",
+    "should_panic",
+    "no_run",
+    "compile_fail",
+    "test_harness",
+    "standalone_crate",
+];
+
+/// Parses a rust file into a DocComment per `pub fn` with a `///` doc comment,
+/// reading its `# Arguments` and `# Returns` sections into params and the return
+/// The title is the name of the function, the description is everything before
+/// the first heading, and types are taken from the signature
+pub fn parse_rust(input: &str) -> Vec<DocComment> {
+    parse(input, &Language::rust())
+}
+
+/// Parses a file of a language with the `Rustdoc` syntax
+pub(crate) fn parse(input: &str, language: &Language) -> Vec<DocComment> {
+    let mut docs: Vec<DocComment> = items(input, language)
+        .into_iter()
+        .map(|(signature, lines)| parse_item(&signature, &lines))
+        .collect();
+
+    // methods of different types often share a name
+    let slugs = slugs(docs.iter().map(|d| d.title.as_str()));
+    docs.iter_mut()
+        .zip(slugs)
+        .for_each(|(doc, slug)| doc.slug = slug);
+
+    docs
+}
+
+/// Titles of every `pub fn` with a doc comment in a rust file
+pub fn rust_titles(input: &str) -> Vec<String> {
+    titles(input, &Language::rust())
+}
+
+/// Titles of every documented function in a file of a language with the `Rustdoc` syntax
+pub(crate) fn titles(input: &str, language: &Language) -> Vec<String> {
+    items(input, language)
+        .into_iter()
+        .map(|(signature, _)| signature.name)
+        .collect()
+}
+
+/// A `pub fn` signature, joined onto a single line
+struct Signature {
+    name: String,
+    /// Everything after the name up to the body, e.g. "<T>(x: T) -> T where T: Copy"
+    rest: String,
+}
+
+/// The lines of a doc comment with the `///` stripped, each with where it starts in the file
+type DocLines<'a> = Vec<(&'a str, Span)>;
+
+/// Every doc comment in a rust file attached to a `pub fn`, with its signature
+fn items<'a>(input: &'a str, language: &Language) -> Vec<(Signature, DocLines<'a>)> {
+    let Some(fn_re) = signature(language) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = input.lines().collect();
+    let mut found = Vec::new();
+    let mut block = Vec::new();
+
+    for (number, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        // //// is a plain comment, not a doc comment
+        if let Some(rest) = language.line_comments.iter().find_map(|p| {
+            let rest = trimmed.strip_prefix(p.as_str())?;
+            (!rest.starts_with(gutter(p))).then_some(rest)
+        }) {
+            let content = rest.strip_prefix(' ').unwrap_or(rest).trim_end();
+            let origin = Span {
+                line: number + 1,
+                column: line[..offset(line, content)].chars().count() + 1,
+            };
+            block.push((content, origin));
+            continue;
+        }
+
+        // attributes sit between a doc comment and its item
+        if block.is_empty() || trimmed.starts_with("#[") {
+            continue;
+        }
+
+        // doc comments on anything but a pub fn are left out
+        let doc_lines = std::mem::take(&mut block);
+        let Some(caps) = fn_re.captures(line).filter(|c| c.name("name").is_some()) else {
+            continue;
+        };
+
+        // the signature may run over several lines, e.g. one argument per line
+        let mut rest = line[caps.get(0).map_or(0, |m| m.end())..].to_string();
+        for next in &lines[number + 1..] {
+            if find_top(&rest, '{').is_some() || find_top(&rest, ';').is_some() {
+                break;
+            }
+            rest.push(' ');
+            rest.push_str(next.trim());
+        }
+
+        let signature = Signature {
+            name: caps["name"].to_string(),
+            rest,
+        };
+        found.push((signature, doc_lines));
+    }
+
+    found
+}
+
+/// A section of a doc comment, from the markdown heading that starts it
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Description,
+    Params,
+    Returns,
+    Errors,
+    Examples,
+    SeeAlso,
+    Notes,
+}
+
+impl Kind {
+    /// The section a heading starts, e.g. "Arguments"
+    /// Headings without a section of their own are kept as notes
+    fn from_heading(heading: &str) -> Kind {
+        match heading.trim().to_lowercase().as_str() {
+            "arguments" | "args" | "parameters" | "params" => Kind::Params,
+            "returns" | "return" => Kind::Returns,
+            "panics" | "errors" => Kind::Errors,
+            "examples" | "example" => Kind::Examples,
+            "see also" => Kind::SeeAlso,
+            _ => Kind::Notes,
+        }
+    }
+}
+
+/// Parses the lines of a doc comment into a DocComment for the function with `signature`
+fn parse_item(signature: &Signature, lines: &[(&str, Span)]) -> DocComment {
+    let heading_re = Regex::new(r"^#+\s+(?P<name>.+)$").unwrap();
+    let mut sections: Vec<(Kind, DocLines)> = vec![(Kind::Description, Vec::new())];
+    let mut examples = Vec::new();
+    let mut fence: Option<Example> = None;
+
+    for &(line, span) in lines {
+        let trimmed = line.trim();
+
+        // code blocks are examples, wherever they are
+        if let Some(info) = trimmed.strip_prefix("```") {
+            match fence.take() {
+                Some(example) => examples.push(example),
+                None => fence = Some(Example::default().with_language(language(info))),
+            }
+            continue;
+        }
+
+        if let Some(example) = &mut fence {
+            // rustdoc hides lines starting with # in rust examples
+            if example.language == "rust" && (trimmed == "#" || trimmed.starts_with("# ")) {
+                continue;
+            }
+            example.code.push_str(line);
+            example.code.push('\n');
+            continue;
+        }
+
+        match heading_re.captures(trimmed) {
+            Some(caps) => sections.push((Kind::from_heading(&caps["name"]), Vec::new())),
+            None => sections.last_mut().unwrap().1.push((line, span)),
+        }
+    }
+    // an unclosed fence runs to the end of the doc comment
+    examples.extend(fence);
+
+    let description = paragraphs(&sections[0].1).join(" ");
+    let mut doc = DocComment::new(&signature.name, description);
+    doc.span = lines
+        .iter()
+        .find(|(l, _)| !l.trim().is_empty())
+        .map_or(Span::default(), |(_, span)| *span);
+
+    let (definition, return_type) = definition(signature).unzip();
+
+    for (kind, lines) in &sections[1..] {
+        doc = match kind {
+            Kind::Description | Kind::Examples => doc,
+            Kind::Params => params(doc, lines),
+            Kind::Returns => {
+                let Some(&(_, span)) = lines.iter().find(|(l, _)| !l.trim().is_empty()) else {
+                    continue;
+                };
+                // functions without a return type give back ()
+                let data_type = return_type.clone().flatten().unwrap_or("()".to_string());
+                let mut ret = Return::new(&data_type).with_description(paragraphs(lines).join(" "));
+                ret.span = Some(span);
+                doc.with_return(ret)
+            }
+            Kind::Errors => list(lines)
+                .into_iter()
+                .fold(doc, |doc, (item, _)| doc.with_error(item)),
+            Kind::SeeAlso => list(lines)
+                .iter()
+                .flat_map(|(item, _)| item.split(','))
+                .map(|title| {
+                    title
+                        .trim_matches(|c: char| "[]`() ".contains(c))
+                        .to_string()
+                })
+                .filter(|title| !title.is_empty())
+                .fold(doc, DocComment::with_see),
+            Kind::Notes => paragraphs(lines)
+                .into_iter()
+                .fold(doc, DocComment::with_note),
+        };
+    }
+
+    let mut doc = examples.into_iter().fold(doc, DocComment::with_example);
+    doc.examples
+        .iter_mut()
+        .for_each(|e| e.code = dedent(&e.code));
+
+    match definition {
+        Some(definition) => doc.with_definition(definition),
+        None => doc,
+    }
+}
+
+/// The language of a code block from its fence, which is rust unless it says otherwise
+/// e.g. "" and "no_run" -> "rust", "text" -> "text"
+fn language(info: &str) -> String {
+    info.split(',')
+        .map(str::trim)
+        .find(|t| !t.is_empty() && !RUSTDOC_ATTRIBUTES.contains(t) && !t.starts_with("edition"))
+        .unwrap_or("rust")
+        .to_string()
+}
+
+/// Add the params of an `# Arguments` section, a list of items like "* `points` - Points it is worth"
+fn params(mut doc: DocComment, lines: &[(&str, Span)]) -> DocComment {
+    let item_re =
+        Regex::new(r"^(?:`(?P<code>\w+)`\s*(?:[-:–—]\s*)?|(?P<name>\w+)\s*[-:–—]\s+)(?P<desc>.*)$")
+            .unwrap();
+
+    for (item, span) in list(lines) {
+        let Some(caps) = item_re.captures(&item) else {
+            warn(format!(
+                "{} has an argument `{item}` without a name, skipping it",
+                doc.title
+            ));
+            continue;
+        };
+
+        let name = caps.name("code").or(caps.name("name")).unwrap().as_str();
+        if doc.params.iter().any(|existing| existing.name == name) {
+            warn(format!(
+                "{} documents argument {name} more than once, keeping the first",
+                doc.title
+            ));
+            continue;
+        }
+
+        doc = doc.with_param(Param {
+            name: name.to_string(),
+            description: caps["desc"].trim().to_string(),
+            span: Some(span),
+            ..Default::default()
+        });
+    }
+
+    doc
+}
+
+/// The items of a markdown list, each joined onto a single line with where it starts,
+/// or each paragraph when there is no list
+fn list(lines: &[(&str, Span)]) -> Vec<(String, Span)> {
+    if !lines.iter().any(|(l, _)| bullet(l).is_some()) {
+        return lines
+            .split(|(l, _)| l.trim().is_empty())
+            .filter(|p| !p.is_empty())
+            .map(|p| (paragraphs(p).join(" "), p[0].1))
+            .collect();
+    }
+
+    let mut items: Vec<(String, Span)> = Vec::new();
+    for (line, span) in lines.iter().filter(|(l, _)| !l.trim().is_empty()) {
+        match (bullet(line), items.last_mut()) {
+            (Some(item), _) => {
+                let column = span.column + offset(line, item);
+                items.push((item.to_string(), Span { column, ..*span }));
+            }
+            // lines after an item continue it
+            (None, Some((item, _))) => {
+                item.push(' ');
+                item.push_str(line.trim());
+            }
+            (None, None) => {}
+        }
+    }
+
+    items
+}
+
+/// The text of a list item, `None` if `line` does not start one
+fn bullet(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    ["* ", "- ", "+ "]
+        .iter()
+        .find_map(|b| line.strip_prefix(b))
+        .map(str::trim)
+}
+
+/// The paragraphs of a section, each joined onto a single line
+fn paragraphs(lines: &[(&str, Span)]) -> Vec<String> {
+    lines
+        .split(|(l, _)| l.trim().is_empty())
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            p.iter()
+                .map(|(l, _)| l.trim())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// The definition of a function from its signature, and its return type
+fn definition(signature: &Signature) -> Option<(Definition, Option<String>)> {
+    let mut rest = signature.rest.trim_start();
+
+    // generics come between the name and the arguments
+    if let Some(generics) = rest.strip_prefix('<') {
+        rest = generics[find_top(generics, '>')? + 1..].trim_start();
+    }
+
+    let args = rest.strip_prefix('(')?;
+    let close = find_top(args, ')')?;
+    let after = &args[close + 1..];
+
+    let return_type = after.trim_start().strip_prefix("->").map(|r| {
+        let end = [find_top(r, '{'), find_top(r, ';'), r.find(" where ")]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(r.len());
+        r[..end].trim().to_string()
+    });
+
+    let params = split_top(&args[..close])
+        .into_iter()
+        // self has no type, or is typed like `self: Box<Self>`
+        .filter_map(|a| a.split_once(':'))
+        .filter_map(|(pattern, data_type)| {
+            let name = pattern.trim().trim_start_matches("mut ").trim();
+            // destructured arguments have no single name to document
+            let named = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+            (named && name != "self").then(|| Param {
+                name: name.to_string(),
+                data_type: vec![data_type.trim().to_string()],
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    let definition = Definition {
+        name: signature.name.clone(),
+        params,
+        ..Default::default()
+    };
+    Some((definition, return_type))
+}
+
+/// Split `text` at every comma outside of brackets and generics
+pub(crate) fn split_top(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(i) = find_top(rest, ',') {
+        parts.push(&rest[..i]);
+        rest = &rest[i + 1..];
+    }
+    parts.push(rest);
+    parts.retain(|p| !p.trim().is_empty());
+    parts
+}
+
+/// Byte index of the first `c` in `text` outside of brackets and generics
+/// The > of an arrow, as in `impl Fn(u8) -> u8`, closes nothing
+pub(crate) fn find_top(text: &str, c: char) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut prev = ' ';
+
+    for (i, ch) in text.char_indices() {
+        let arrow = ch == '>' && prev == '-';
+        prev = ch;
+        match ch {
+            ch if ch == c && depth == 0 && !arrow => return Some(i),
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' | ']' | '}' | '>' if !arrow => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doc_comment_with_arguments_and_returns() {
+        let docs = parse_rust(
+            "/// Add two numbers\n///\n/// # Arguments\n///\n/// * `a` - the first\n/// * `b` - the second\n///\n/// # Returns\n///\n/// the sum\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+        );
+
+        assert_eq!(docs[0].title, "add");
+        assert_eq!(docs[0].description, "Add two numbers");
+        assert_eq!(docs[0].params.len(), 2);
+        assert_eq!(docs[0].params[1].description, "the second");
+        // types come from the signature
+        assert_eq!(docs[0].params[0].data_type, ["i32"]);
+        assert_eq!(docs[0].returns[0].data_type, ["i32"]);
+        assert_eq!(docs[0].returns[0].description, "the sum");
+    }
+
+    #[test]
+    fn doc_comment_without_a_description() {
+        let docs =
+            parse_rust("/// # Arguments\n///\n/// * `a` - the value\npub fn bare(a: i32) {}\n");
+
+        assert_eq!(docs[0].title, "bare");
+        assert_eq!(docs[0].description, "");
+        assert_eq!(docs[0].params[0].description, "the value");
+    }
+
+    #[test]
+    fn doc_comment_not_on_a_pub_fn_is_skipped() {
+        let docs =
+            parse_rust("/// A private helper\nfn helper() {}\n\n/// A loose comment\nstruct S;\n");
+
+        assert!(docs.is_empty());
+    }
+}
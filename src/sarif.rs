@@ -0,0 +1,111 @@
+use marker_typ::{RULES, Span};
+use serde_json::{Value, json};
+
+/// Rule of the results for doc comments that could not be parsed
+pub const PARSE_ERROR: &str = "parse-error";
+
+/// A SARIF 2.1.0 log of the problems `marker check` found, for code scanning dashboards
+#[derive(Debug, Default)]
+pub struct Sarif {
+    results: Vec<Value>,
+}
+
+impl Sarif {
+    /// Add a problem of `rule` at `span` in `file`, at `level` "warning" or "error"
+    pub fn add(&mut self, level: &str, file: &str, span: Option<Span>, rule: &str, message: &str) {
+        let mut location = json!({ "artifactLocation": { "uri": uri(file) } });
+        if let Some(span) = span {
+            location["region"] = json!({ "startLine": span.line, "startColumn": span.column });
+        }
+
+        self.results.push(json!({
+            "ruleId": rule,
+            "level": level,
+            "message": { "text": message },
+            "locations": [{ "physicalLocation": location }],
+        }));
+    }
+
+    /// The log as JSON, with a rule for every warning and for parse errors
+    pub fn json(&self) -> String {
+        let rules: Vec<Value> = RULES
+            .iter()
+            .chain(std::iter::once(&PARSE_ERROR))
+            .map(|id| json!({ "id": id }))
+            .collect();
+
+        let log = json!({
+            "version": "2.1.0",
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "marker",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                        "rules": rules,
+                    }
+                },
+                "results": self.results,
+            }],
+        });
+
+        serde_json::to_string_pretty(&log).expect("SARIF logs are valid json")
+    }
+}
+
+/// The relative URI of `file`, with `/` separators
+fn uri(file: &str) -> String {
+    file.replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use marker_typ::{Language, collect_findings, lint};
+    use serde_json::Value;
+
+    use super::*;
+
+    /// The ids of the rules a SARIF log declares
+    fn rule_ids(log: &Value) -> Vec<&str> {
+        log["runs"][0]["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["id"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn rules_are_every_warning_rule_and_parse_errors() {
+        let log: Value = serde_json::from_str(&Sarif::default().json()).unwrap();
+        let expected: Vec<&str> = RULES.iter().copied().chain([PARSE_ERROR]).collect();
+
+        assert_eq!(rule_ids(&log), expected);
+    }
+
+    #[test]
+    fn results_only_use_declared_rules() {
+        let input = "/// f: Do it\n/// @param a int = 1 the first\n/// @param a str again\n/// @param z int not there\n/// @Retrun int nothing\n#let f(a: 2, b) = a\n";
+        let (docs, problems) = collect_findings(|| Language::typst().parse(input));
+        let lints = docs.iter().flatten().flat_map(lint);
+
+        let mut sarif = Sarif::default();
+        for finding in problems.into_iter().chain(lints) {
+            sarif.add(
+                "warning",
+                "f.typ",
+                Some(finding.span),
+                finding.rule,
+                &finding.message,
+            );
+        }
+        let log: Value = serde_json::from_str(&sarif.json()).unwrap();
+        let results = log["runs"][0]["results"].as_array().unwrap();
+
+        assert!(results.len() >= 4);
+        for result in results {
+            assert!(rule_ids(&log).contains(&result["ruleId"].as_str().unwrap()));
+        }
+    }
+}
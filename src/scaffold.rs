@@ -0,0 +1,108 @@
+use crate::{
+    Kind, Language,
+    directive::ignores_file,
+    parse::{definition, signature},
+};
+
+/// Placeholder for what the author has to fill in
+const TODO: &str = "TODO";
+
+/// Insert a skeleton doc comment above every public `#let` function of a typst file
+/// that has none, with an @param for each of its arguments, e.g.
+/// ```text
+/// /// question: TODO
+/// /// @param body any TODO
+/// /// @param points any = 1 TODO
+/// ```
+/// Returns the new file, and the names of the functions that got one
+/// Functions starting with `_` are private, and files with `marker:ignore-file` are left alone
+pub fn scaffold(input: &str) -> (String, Vec<String>) {
+    let Some(signature) = signature(&Language::typst()).filter(|_| !ignores_file(input)) else {
+        return (input.to_string(), Vec::new());
+    };
+
+    let lines: Vec<&str> = input.lines().collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut scaffolded = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        // a doc comment ends right above what it documents, with /// or */
+        let documented = i
+            .checked_sub(1)
+            .map(|prev| lines[prev].trim())
+            .is_some_and(|prev| prev.starts_with("///") || prev.ends_with("*/"));
+
+        if let Some(definition) = definition(&lines[i..], &signature)
+            && !documented
+            && !definition.name.starts_with('_')
+            && definition.kind == Kind::Function
+        {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            out.push(format!("{indent}/// {}: {TODO}", definition.name));
+            for p in &definition.params {
+                let default = p
+                    .default
+                    .as_ref()
+                    .map(|d| format!(" = {d}"))
+                    .unwrap_or_default();
+                out.push(format!(
+                    "{indent}/// @param {} any{default} {TODO}",
+                    p.source_name()
+                ));
+            }
+            scaffolded.push(definition.name);
+        }
+
+        out.push(line.to_string());
+    }
+
+    let mut scaffolded_input = out.join("\n");
+    if input.ends_with('\n') {
+        scaffolded_input.push('\n');
+    }
+    (scaffolded_input, scaffolded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undocumented_function_gets_a_stub() {
+        let (out, names) = scaffold("#let question(body, points: 1) = body\n");
+
+        assert_eq!(
+            out,
+            "/// question: TODO\n/// @param body any TODO\n/// @param points any = 1 TODO\n#let question(body, points: 1) = body\n"
+        );
+        assert_eq!(names, ["question"]);
+    }
+
+    #[test]
+    fn documented_functions_are_skipped() {
+        let input = "/// add: Add two numbers\n#let add(a, b) = a + b\n\n/**\n * sub: Subtract\n */\n#let sub(a, b) = a - b\n";
+        let (out, names) = scaffold(input);
+
+        assert_eq!(out, input);
+        assert!(names.is_empty());
+    }
+
+    #[test]
+    fn private_functions_and_bindings_are_skipped() {
+        let input = "#let _helper(x) = x\n#let limit = 10\n";
+        let (out, names) = scaffold(input);
+
+        assert_eq!(out, input);
+        assert!(names.is_empty());
+    }
+
+    #[test]
+    fn stub_keeps_the_indentation_of_its_let() {
+        let (out, _) = scaffold("#if true {\n    #let inner(x) = x\n}\n");
+
+        assert_eq!(
+            out,
+            "#if true {\n    /// inner: TODO\n    /// @param x any TODO\n    #let inner(x) = x\n}\n"
+        );
+    }
+}
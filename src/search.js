@@ -0,0 +1,42 @@
+(() => {
+  const form = document.currentScript.previousElementSibling;
+  const input = form.querySelector("input");
+  const results = form.querySelector("ul");
+  const base = new URL(input.dataset.index, location.href);
+  let index = null;
+
+  form.addEventListener("submit", (e) => {
+    e.preventDefault();
+    const first = results.querySelector("a");
+    if (first) location.href = first.href;
+  });
+
+  input.addEventListener("input", async () => {
+    index ??= await fetch(base).then((r) => r.json()).catch(() => []);
+    const query = input.value.trim().toLowerCase();
+    results.replaceChildren();
+    if (!query) return;
+
+    // names starting with the query first, then any other match
+    const rank = (e) => {
+      const name = e.name.toLowerCase();
+      if (name.startsWith(query)) return 0;
+      if (name.includes(query)) return 1;
+      return e.summary.toLowerCase().includes(query) ? 2 : -1;
+    };
+    index
+      .map((e) => [rank(e), e])
+      .filter(([r]) => r >= 0)
+      .sort((a, b) => a[0] - b[0] || a[1].name.localeCompare(b[1].name))
+      .slice(0, 20)
+      .forEach(([, e]) => {
+        const item = document.createElement("li");
+        const link = document.createElement("a");
+        link.href = new URL(e.url, base);
+        link.textContent = e.name;
+        item.append(link);
+        if (e.summary) item.append(` — ${e.summary}`);
+        results.append(item);
+      });
+  });
+})();
@@ -0,0 +1,263 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// Default directory `marker serve` builds into when no --out-dir is given
+pub const SERVE_DIR: &str = ".marker-serve";
+
+/// Path the browser listens on for reloads
+const RELOAD_PATH: &str = "/__marker/reload";
+
+/// Script added to every served html page, reloading it when the docs are rebuilt
+const RELOAD_SCRIPT: &str =
+    "<script>new EventSource(\"/__marker/reload\").onmessage = () => location.reload();</script>\n";
+
+/// How often an idle reload stream is written to, to notice closed tabs
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Number of times the docs have been built, shared with every open page
+#[derive(Debug, Default)]
+pub struct Builds {
+    count: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl Builds {
+    /// Record a new build, telling every open page to reload
+    pub fn bump(&self) {
+        *self.count.lock().unwrap() += 1;
+        self.changed.notify_all();
+    }
+
+    /// Number of the latest build
+    fn latest(&self) -> u64 {
+        *self.count.lock().unwrap()
+    }
+
+    /// Wait up to `timeout` for a build after build number `seen`, returning the latest
+    fn wait(&self, seen: u64, timeout: Duration) -> u64 {
+        let count = self.count.lock().unwrap();
+        let (count, _) = self
+            .changed
+            .wait_timeout_while(count, timeout, |c| *c == seen)
+            .unwrap();
+        *count
+    }
+}
+
+/// Serve the files in `root` on localhost at `port` on a thread of its own,
+/// with a thread per connection
+pub fn spawn(root: &str, port: u16, builds: Arc<Builds>) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    let root = PathBuf::from(root);
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let root = root.clone();
+            let builds = Arc::clone(&builds);
+            thread::spawn(move || {
+                // a browser going away mid response is nothing to report
+                let _ = respond(stream, &root, &builds);
+            });
+        }
+    });
+
+    Ok(())
+}
+
+/// Answer a single request on `stream`
+fn respond(mut stream: TcpStream, root: &Path, builds: &Builds) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+
+    // the headers aren't needed, but have to be read before answering
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return send(
+            &mut stream,
+            "400 Bad Request",
+            "text/plain",
+            b"bad request\n",
+        );
+    };
+    if method != "GET" {
+        return send(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            b"only GET\n",
+        );
+    }
+
+    let path = decode(target.split(['?', '#']).next().unwrap_or_default());
+    if path == RELOAD_PATH {
+        return reload_stream(stream, builds);
+    }
+
+    let Some(file) = resolve(root, &path) else {
+        return send(&mut stream, "404 Not Found", "text/plain", b"not found\n");
+    };
+
+    if file.is_dir() {
+        let listing = listing(root, &file);
+        return send(
+            &mut stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            listing.as_bytes(),
+        );
+    }
+
+    match fs::read(&file) {
+        Ok(contents) if content_type(&file).starts_with("text/html") => {
+            let page = String::from_utf8_lossy(&contents);
+            let page = match page.rfind("</body>") {
+                Some(end) => format!("{}{RELOAD_SCRIPT}{}", &page[..end], &page[end..]),
+                None => format!("{page}{RELOAD_SCRIPT}"),
+            };
+            send(&mut stream, "200 OK", content_type(&file), page.as_bytes())
+        }
+        Ok(contents) => send(&mut stream, "200 OK", content_type(&file), &contents),
+        Err(_) => send(&mut stream, "404 Not Found", "text/plain", b"not found\n"),
+    }
+}
+
+/// Keep `stream` open as server-sent events, sending a message after every build
+fn reload_stream(mut stream: TcpStream, builds: &Builds) -> io::Result<()> {
+    stream.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n",
+    )?;
+
+    let mut seen = builds.latest();
+    loop {
+        let now = builds.wait(seen, KEEP_ALIVE);
+        match now == seen {
+            true => stream.write_all(b": keep-alive\n\n")?,
+            false => stream.write_all(b"data: reload\n\n")?,
+        }
+        stream.flush()?;
+        seen = now;
+    }
+}
+
+/// Write a complete response with `body`
+fn send(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+/// The file a request path points to under `root`, `None` if it leaves `root` or is missing
+/// Directories are served by their index.html when they have one
+fn resolve(root: &Path, path: &str) -> Option<PathBuf> {
+    let relative = Path::new(path.trim_start_matches('/'));
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+
+    let file = root.join(relative);
+    match file.join("index.html") {
+        index if file.is_dir() && index.is_file() => Some(index),
+        _ if file.exists() => Some(file),
+        _ => None,
+    }
+}
+
+/// An html page linking to every page in `dir`, for directories without an index.html
+fn listing(root: &Path, dir: &Path) -> String {
+    let mut pages: Vec<PathBuf> = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(d) = pending.pop() {
+        for entry in fs::read_dir(&d).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|e| e == "html") {
+                pages.push(path);
+            }
+        }
+    }
+    pages.sort();
+
+    let items: String = pages
+        .iter()
+        .filter_map(|p| p.strip_prefix(root).ok())
+        .map(|p| {
+            let href: Vec<_> = p
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect();
+            format!(
+                "<li><a href=\"/{href}\">{href}</a></li>\n",
+                href = href.join("/")
+            )
+        })
+        .collect();
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Documentation</title>\n</head>\n<body>\n<h1>Documentation</h1>\n<ul>\n{items}</ul>\n{RELOAD_SCRIPT}</body>\n</html>\n"
+    )
+}
+
+/// Content type of a served file, by its extension
+fn content_type(file: &Path) -> &'static str {
+    match file
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+    {
+        "html" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "md" | "txt" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Undo the percent encoding of a request path, e.g. "%20" -> " "
+fn decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
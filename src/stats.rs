@@ -0,0 +1,327 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use marker_typ::{Language, Syntax, ignores_file};
+use regex::Regex;
+use serde::Serialize;
+
+/// Documentation coverage numbers for a single file
+#[derive(Debug, Default, Serialize)]
+pub struct FileStats {
+    /// Empty for the totals, which leave it out of the JSON report
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub path: String,
+    pub total_functions: usize,
+    pub undocumented_functions: usize,
+    /// Functions not starting with `_`, which typst treats as public by convention
+    #[serde(skip)]
+    pub public_functions: usize,
+    #[serde(skip)]
+    pub undocumented_public_functions: usize,
+    pub documented_params: usize,
+    pub warnings: usize,
+}
+
+/// Documentation coverage numbers across every file in a run
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    pub files: Vec<FileStats>,
+}
+
+/// The JSON report of a run, the totals next to the stats of every file
+#[derive(Serialize)]
+struct Report<'a> {
+    generated_at: String,
+    #[serde(flatten)]
+    total: FileStats,
+    #[serde(flatten)]
+    stats: &'a Stats,
+}
+
+impl FileStats {
+    /// Count the functions in a file of `language`, and how many have no doc comment
+    /// Definitions are found with the language's signature pattern, and are documented
+    /// when a doc comment names them, so languages without a pattern only count
+    /// their documented functions
+    /// Files with a `marker:ignore-file` comment have none
+    pub fn count_functions(&mut self, input: &str, language: &Language) {
+        if ignores_file(input) {
+            return;
+        }
+
+        let documented: HashSet<String> = language.titles(input).into_iter().collect();
+        let names: Vec<String> = match signature(language) {
+            Some(re) => input
+                .lines()
+                .filter_map(|line| re.captures(line))
+                // a typst binding ending at its = isn't a function
+                .filter(|caps| language.syntax != Syntax::Tags || caps[0].trim_end().ends_with('('))
+                .filter_map(|caps| Some(caps.name("name")?.as_str().to_string()))
+                .collect(),
+            None => documented.iter().cloned().collect(),
+        };
+
+        for name in names {
+            let public = !name.starts_with('_');
+            let undocumented = !documented.contains(&name);
+
+            self.total_functions += 1;
+            self.public_functions += usize::from(public);
+            self.undocumented_functions += usize::from(undocumented);
+            self.undocumented_public_functions += usize::from(public && undocumented);
+        }
+    }
+
+    /// Percentage of public functions with a doc comment
+    /// A file without public functions is fully covered
+    pub fn coverage(&self) -> f64 {
+        if self.public_functions == 0 {
+            return 100.0;
+        }
+
+        let documented = self.public_functions - self.undocumented_public_functions;
+        100.0 * documented as f64 / self.public_functions as f64
+    }
+}
+
+impl Stats {
+    /// Sum the counts of every file
+    pub fn total(&self) -> FileStats {
+        self.files
+            .iter()
+            .fold(FileStats::default(), |acc, f| FileStats {
+                path: String::new(),
+                total_functions: acc.total_functions + f.total_functions,
+                undocumented_functions: acc.undocumented_functions + f.undocumented_functions,
+                public_functions: acc.public_functions + f.public_functions,
+                undocumented_public_functions: acc.undocumented_public_functions
+                    + f.undocumented_public_functions,
+                documented_params: acc.documented_params + f.documented_params,
+                warnings: acc.warnings + f.warnings,
+            })
+    }
+
+    /// Serialize the report as JSON in the form
+    /// { "generated_at", ...totals, "files": [{ "path", ...counts }] }
+    pub fn json(&self) -> String {
+        let report = Report {
+            generated_at: timestamp(),
+            total: self.total(),
+            stats: self,
+        };
+        serde_json::to_string_pretty(&report).expect("stats are valid json") + "\n"
+    }
+
+    /// A shields.io endpoint badge of the total coverage, in the form
+    /// { "schemaVersion": 1, "label": "docs", "message": "87%", "color": "green" }
+    pub fn badge(&self) -> String {
+        let coverage = self.total().coverage();
+        let color = match coverage {
+            c if c >= 90.0 => "brightgreen",
+            c if c >= 75.0 => "green",
+            c if c >= 60.0 => "yellow",
+            c if c >= 40.0 => "orange",
+            _ => "red",
+        };
+
+        format!(
+            "{{\"schemaVersion\":1,\"label\":\"docs\",\"message\":\"{coverage:.0}%\",\"color\":\"{color}\"}}\n"
+        )
+    }
+
+    /// A table of public functions documented in each file, and in total
+    pub fn coverage_table(&self) -> String {
+        let total = FileStats {
+            path: "total".to_string(),
+            ..self.total()
+        };
+
+        let rows: Vec<[String; 3]> = self
+            .files
+            .iter()
+            .chain(std::iter::once(&total))
+            .map(|f| {
+                [
+                    f.path.clone(),
+                    format!(
+                        "{}/{}",
+                        f.public_functions - f.undocumented_public_functions,
+                        f.public_functions
+                    ),
+                    format!("{:.1}%", f.coverage()),
+                ]
+            })
+            .collect();
+
+        let width = |i: usize| rows.iter().map(|r| r[i].chars().count()).max().unwrap_or(0);
+        let (path_w, count_w) = (width(0), width(1));
+
+        rows.iter()
+            .map(|[path, count, percent]| {
+                format!("{path:path_w$}  {count:>count_w$}  {percent:>6}\n")
+            })
+            .collect()
+    }
+}
+
+/// The compiled signature pattern of `language`, compiled once per run
+/// An invalid pattern was already reported when parsing, so it finds nothing here
+fn signature(language: &Language) -> Option<Regex> {
+    static SIGNATURES: OnceLock<Mutex<HashMap<String, Option<Regex>>>> = OnceLock::new();
+
+    let pattern = language.signature.as_ref()?;
+    let mut signatures = SIGNATURES.get_or_init(Default::default).lock().unwrap();
+    signatures
+        .entry(pattern.clone())
+        .or_insert_with(|| Regex::new(pattern).ok())
+        .clone()
+}
+
+/// The current UTC date, e.g. "2024-01-31"
+pub fn today() -> String {
+    timestamp()[..10].to_string()
+}
+
+/// The current time as an RFC 3339 UTC timestamp, e.g. "2024-01-31T12:00:00Z"
+fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+
+    // days since the epoch to a civil date, from Howard Hinnant's date algorithms
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = "\
+/// add: Add two numbers
+/// @param a int the first
+/// @param b int the second
+#let add(a, b) = a + b
+
+#let sub(a, b) = a - b
+
+/// _helper: Used by the others
+#let _helper(x) = x
+
+#let _scratch() = none
+";
+
+    #[test]
+    fn counts_documented_and_undocumented_functions() {
+        let mut stats = FileStats::default();
+        stats.count_functions(FIXTURE, &Language::typst());
+
+        assert_eq!(stats.total_functions, 4);
+        assert_eq!(stats.undocumented_functions, 2);
+        assert_eq!(stats.public_functions, 2);
+        assert_eq!(stats.undocumented_public_functions, 1);
+        assert_eq!(stats.coverage(), 50.0);
+    }
+
+    #[test]
+    fn jsdoc_comments_document_and_bindings_are_not_functions() {
+        let mut stats = FileStats::default();
+        stats.count_functions(
+            "/**\n * add: Add two numbers\n */\n#let add(a, b) = a + b\n#let limit = 10\n",
+            &Language::typst(),
+        );
+
+        assert_eq!(stats.total_functions, 1);
+        assert_eq!(stats.undocumented_functions, 0);
+    }
+
+    #[test]
+    fn counts_python_rust_and_c_from_their_own_comments() {
+        let mut python = FileStats::default();
+        python.count_functions(
+            "def add(a, b):\n    \"\"\"Add two numbers\"\"\"\n    return a + b\n\ndef sub(a, b):\n    return a - b\n",
+            &Language::python(),
+        );
+        assert_eq!(python.total_functions, 2);
+        assert_eq!(python.undocumented_functions, 1);
+
+        let mut rust = FileStats::default();
+        rust.count_functions(
+            "/// Add two numbers\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\npub fn sub(a: i32, b: i32) -> i32 {\n    a - b\n}\n",
+            &Language::rust(),
+        );
+        assert_eq!(rust.total_functions, 2);
+        assert_eq!(rust.undocumented_functions, 1);
+
+        let mut c = FileStats::default();
+        c.count_functions(
+            "/** Add two numbers */\nint add(int a, int b);\n",
+            &Language::c(),
+        );
+        assert_eq!(c.total_functions, 1);
+        assert_eq!(c.undocumented_functions, 0);
+    }
+
+    #[test]
+    fn json_report_sums_every_file() {
+        let mut first = FileStats {
+            path: "a.typ".to_string(),
+            documented_params: 2,
+            ..Default::default()
+        };
+        first.count_functions(FIXTURE, &Language::typst());
+        let mut second = FileStats {
+            path: "b \"quoted\".typ".to_string(),
+            warnings: 1,
+            ..Default::default()
+        };
+        second.count_functions("#let f() = 1\n", &Language::typst());
+
+        let json = Stats {
+            files: vec![first, second],
+        }
+        .json();
+
+        assert!(json.contains("  \"total_functions\": 5,\n"));
+        assert!(json.contains("  \"undocumented_functions\": 3,\n"));
+        assert!(json.contains("  \"documented_params\": 2,\n"));
+        assert!(json.contains("  \"warnings\": 1\n"));
+        assert!(json.contains("\"path\": \"b \\\"quoted\\\".typ\""));
+
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["total_functions"], 5);
+        assert_eq!(parsed["files"][1]["path"], "b \"quoted\".typ");
+        assert!(parsed.get("path").is_none());
+    }
+
+    #[test]
+    fn ignored_file_has_no_functions() {
+        let mut stats = FileStats::default();
+        stats.count_functions(
+            &format!("// marker:ignore-file\n{FIXTURE}"),
+            &Language::typst(),
+        );
+
+        assert_eq!(stats.total_functions, 0);
+    }
+}
@@ -0,0 +1,31 @@
+use std::error::Error;
+
+use tera::{Context, Tera};
+
+/// Render the Tera template `name` with the variables of `context`,
+/// e.g. `{{ title }}`, `{% if params %}` or `{% for p in doc.params %}`
+/// Fails with why it couldn't, e.g. a variable missing from `context`
+pub fn fill(name: &str, template: &str, context: &Context) -> Result<String, String> {
+    let mut tera = Tera::default();
+    tera.add_raw_template(name, template)
+        .and_then(|()| tera.render(name, context))
+        .map_err(|e| reason(&e))
+}
+
+/// Why `template` isn't a valid Tera template, `None` if it is
+pub fn check_template(template: &str) -> Option<String> {
+    Tera::default()
+        .add_raw_template("template", template)
+        .err()
+        .map(|e| reason(&e))
+}
+
+/// The innermost cause of a Tera error, which says what went wrong
+/// where the error itself only names the template
+fn reason(e: &tera::Error) -> String {
+    let mut source: &dyn Error = e;
+    while let Some(inner) = source.source() {
+        source = inner;
+    }
+    source.to_string()
+}
@@ -0,0 +1,230 @@
+use crate::{Config, DocComment, Example, Return, Section, TagStyle, wrap::wrap};
+
+/// Trait representing the ability to be converted into plain text
+/// with no markdown syntax, suitable for `less` or a README code block
+pub trait Textable {
+    fn text(&self, config: &Config) -> String;
+}
+
+/// Indent used for every entry under a section label
+const INDENT: &str = "  ";
+
+/// Gap between aligned columns
+const GAP: &str = "  ";
+
+impl Textable for DocComment {
+    /// Convert a DocComment into plain text in the form
+    /// title
+    /// =====
+    /// signature
+    ///
+    /// description
+    ///
+    /// Parameters:
+    ///   name  type  description
+    fn text(&self, config: &Config) -> String {
+        let mut txt = String::new();
+
+        txt.push_str(&self.title);
+        txt.push('\n');
+        txt.push_str(&"=".repeat(self.title.chars().count()));
+        txt.push('\n');
+        txt.push_str(&self.signature());
+        txt.push('\n');
+
+        if let Some(metadata) = config.metadata(self) {
+            txt.push_str(&format!("{metadata}\n"));
+        }
+
+        let mut badges: Vec<String> = config
+            .tags_in(TagStyle::Badge)
+            .flat_map(|t| {
+                self.tag_values(&t.name).map(move |v| match v {
+                    "" => format!("[{}]", t.label()),
+                    v => format!("[{}: {v}]", t.label()),
+                })
+            })
+            .collect();
+        let marks = config.marks(self).into_iter();
+        badges.splice(0..0, marks.map(|label| format!("[{label}]")));
+        if !badges.is_empty() {
+            txt.push_str(&format!("{}\n", badges.join(" ")));
+        }
+
+        for tag in config.tags_in(TagStyle::KeyValue) {
+            self.tag_values(&tag.name).for_each(|v| {
+                txt.push_str(&format!("{}: {}\n", tag.label(), config.wrap(v)));
+            });
+        }
+
+        if let Some(msg) = &self.deprecated {
+            txt.push_str(format!("\nDEPRECATED {}", config.wrap(msg)).trim_end());
+            txt.push('\n');
+        }
+
+        for section in config.sections() {
+            match section {
+                Section::Description => {
+                    txt.push('\n');
+                    txt.push_str(&config.wrap(&self.description));
+                    txt.push('\n');
+                }
+                Section::Params if !self.params.is_empty() => {
+                    txt.push_str(&format!("\n{}:\n", config.label(Section::Params)));
+
+                    for (group, params) in config.param_groups(self) {
+                        if let Some(group) = group {
+                            txt.push_str(&format!("{INDENT}{group}:\n"));
+                        }
+                        let rows: Vec<[String; 3]> = params
+                            .iter()
+                            .map(|p| {
+                                let requirement = config.locale.requirement(p.is_required());
+                                let description = match &p.default {
+                                    Some(def) => {
+                                        format!("({requirement}, default: {def}) {}", p.description)
+                                    }
+                                    None => format!("({requirement}) {}", p.description),
+                                };
+                                [p.label(), p.types().join(" | "), description]
+                            })
+                            .collect();
+
+                        txt.push_str(&columns(&rows, config));
+                    }
+                }
+                Section::Returns if !self.returns.is_empty() => {
+                    txt.push_str(&format!("\n{}:\n", config.label(Section::Returns)));
+                    match self.returns.as_slice() {
+                        [ret] => txt.push_str(&ret.text(config)),
+                        returns => {
+                            let rows: Vec<_> = returns.iter().map(row).collect();
+                            txt.push_str(&columns(&rows, config));
+                        }
+                    }
+                }
+                Section::Examples if !self.examples.is_empty() => {
+                    txt.push_str(&format!("\n{}:\n", config.label(Section::Examples)));
+
+                    self.examples.iter().for_each(|e| {
+                        txt.push_str(&e.text(config));
+                    });
+                }
+                Section::Errors if !self.errors.is_empty() => {
+                    txt.push_str(&format!("\n{}:\n", config.label(Section::Errors)));
+
+                    let rows: Vec<[String; 3]> = self
+                        .errors
+                        .iter()
+                        .map(|e| ["-".to_string(), String::new(), e.clone()])
+                        .collect();
+
+                    txt.push_str(&columns(&rows, config));
+                }
+                Section::SeeAlso if !self.see.is_empty() => {
+                    txt.push_str(&format!("\n{}:\n", config.label(Section::SeeAlso)));
+                    txt.push_str(&format!("{INDENT}{}\n", self.see.join(", ")));
+                }
+                Section::Notes if !self.notes.is_empty() => {
+                    txt.push_str(&format!("\n{}:\n", config.label(Section::Notes)));
+
+                    let rows: Vec<[String; 3]> = self
+                        .notes
+                        .iter()
+                        .map(|n| ["-".to_string(), String::new(), n.clone()])
+                        .collect();
+
+                    txt.push_str(&columns(&rows, config));
+                }
+                _ => {}
+            }
+        }
+
+        for tag in config.tags_in(TagStyle::Section) {
+            let rows: Vec<[String; 3]> = self
+                .tag_values(&tag.name)
+                .map(|v| ["-".to_string(), String::new(), v.to_string()])
+                .collect();
+
+            if !rows.is_empty() {
+                txt.push_str(&format!("\n{}:\n", tag.label()));
+                txt.push_str(&columns(&rows, config));
+            }
+        }
+
+        txt
+    }
+}
+
+impl Textable for Return {
+    /// Convert a Return into the form "  type  description", or "  name  type  description"
+    fn text(&self, config: &Config) -> String {
+        columns(&[row(self)], config)
+    }
+}
+
+/// The columns of a Return, its type taking the name column when it names no field
+fn row(ret: &Return) -> [String; 3] {
+    let data_type = ret.data_type.join(" | ");
+    match &ret.name {
+        Some(name) => [name.clone(), data_type, ret.description.clone()],
+        None => [data_type, String::new(), ret.description.clone()],
+    }
+}
+
+impl Textable for Example {
+    /// Convert an example into an indented block of code
+    fn text(&self, _config: &Config) -> String {
+        let mut txt: String = self
+            .code
+            .lines()
+            .map(|l| format!("{INDENT}{INDENT}{l}\n"))
+            .collect();
+
+        if let Some(image) = &self.image {
+            txt.push_str(&format!("{INDENT}{INDENT}Output: {image}\n"));
+        }
+
+        txt
+    }
+}
+
+/// Lay out rows of [name, type, description] into aligned columns
+/// An empty type column collapses entirely
+/// Descriptions are wrapped to fit the configured width, with continuation
+/// lines indented to line up under the description column
+fn columns(rows: &[[String; 3]], config: &Config) -> String {
+    let width = |i: usize| rows.iter().map(|r| r[i].chars().count()).max().unwrap_or(0);
+    let name_w = width(0);
+    let type_w = width(1);
+
+    let mut out = String::new();
+
+    for [name, data_type, description] in rows {
+        let mut prefix = format!("{INDENT}{name:name_w$}{GAP}");
+        if type_w > 0 {
+            prefix.push_str(&format!("{data_type:type_w$}{GAP}"));
+        }
+
+        let desc_col = prefix.chars().count();
+        let description = match config.wrap {
+            // never wrap narrower than a single word per line
+            Some(cols) => wrap(description, cols.saturating_sub(desc_col).max(1)),
+            None => description.clone(),
+        };
+
+        let pad = " ".repeat(desc_col);
+        let mut lines = description.lines();
+
+        match lines.next() {
+            Some(first) => out.push_str(&format!("{prefix}{first}\n")),
+            None => out.push_str(&format!("{}\n", prefix.trim_end())),
+        }
+
+        lines.for_each(|l| {
+            out.push_str(&format!("{pad}{l}\n"));
+        });
+    }
+
+    out
+}
@@ -0,0 +1,88 @@
+use crate::grammar;
+
+/// Where the typst reference documents its types
+const REFERENCE: &str = "https://typst.app/docs/reference";
+
+/// The types built into typst, with the part of the reference documenting each
+const TYPES: [(&str, &str); 36] = [
+    ("arguments", "foundations"),
+    ("array", "foundations"),
+    ("auto", "foundations"),
+    ("bool", "foundations"),
+    ("bytes", "foundations"),
+    ("content", "foundations"),
+    ("datetime", "foundations"),
+    ("decimal", "foundations"),
+    ("dictionary", "foundations"),
+    ("duration", "foundations"),
+    ("float", "foundations"),
+    ("function", "foundations"),
+    ("int", "foundations"),
+    ("label", "foundations"),
+    ("module", "foundations"),
+    ("none", "foundations"),
+    ("regex", "foundations"),
+    ("selector", "foundations"),
+    ("str", "foundations"),
+    ("symbol", "foundations"),
+    ("type", "foundations"),
+    ("version", "foundations"),
+    ("counter", "introspection"),
+    ("location", "introspection"),
+    ("state", "introspection"),
+    ("alignment", "layout"),
+    ("angle", "layout"),
+    ("direction", "layout"),
+    ("fraction", "layout"),
+    ("length", "layout"),
+    ("ratio", "layout"),
+    ("relative", "layout"),
+    ("color", "visualize"),
+    ("gradient", "visualize"),
+    ("stroke", "visualize"),
+    ("tiling", "visualize"),
+];
+
+/// Elements, which documentation names as the type of content holding one, e.g. `raw`
+const ELEMENTS: [(&str, &str); 11] = [
+    ("block", "layout"),
+    ("box", "layout"),
+    ("grid", "layout"),
+    ("figure", "model"),
+    ("heading", "model"),
+    ("link", "model"),
+    ("table", "model"),
+    ("raw", "text"),
+    ("text", "text"),
+    ("image", "visualize"),
+    ("math.equation", "math"),
+];
+
+/// Names that stand for a type without being one, accepted but never linked
+const PSEUDO_TYPES: [&str; 1] = ["any"];
+
+/// Every type named in `data_type` that typst doesn't have,
+/// including those inside it, e.g. "array(ms)" -> ["ms"]
+pub fn unknown(data_type: &str) -> Vec<String> {
+    grammar::type_names(data_type)
+        .into_iter()
+        .filter(|t| !PSEUDO_TYPES.contains(&t.as_str()) && page(t).is_none())
+        .collect()
+}
+
+/// The page of the typst reference documenting the outer type of `data_type`,
+/// e.g. "array(int)" -> ".../foundations/array/"
+/// `None` for types typst doesn't have
+pub fn reference(data_type: &str) -> Option<String> {
+    let name = data_type.split('(').next().unwrap_or(data_type).trim();
+    page(name).map(|part| format!("{REFERENCE}/{part}/{}/", name.trim_start_matches("math.")))
+}
+
+/// The part of the reference documenting the type or element `name`
+fn page(name: &str) -> Option<&'static str> {
+    TYPES
+        .iter()
+        .chain(&ELEMENTS)
+        .find(|(t, _)| *t == name)
+        .map(|(_, part)| *part)
+}
@@ -0,0 +1,66 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use marker_typ::version_numbers;
+
+/// Directory holding a copy of the docs of the newest version
+pub const LATEST_DIR: &str = "latest";
+
+/// File listing every version of the docs, for a version switcher
+pub const VERSIONS_FILE: &str = "versions.json";
+
+/// Update the versions beside the docs of `version` just written into `dir/version`:
+/// replace the `latest` copy if it is the newest version, and rewrite versions.json
+pub fn update(dir: &Path, version: &str) -> io::Result<()> {
+    let versions = versions(dir)?;
+
+    // docs of an old release shouldn't replace the newest
+    if versions.first().is_some_and(|newest| newest == version) {
+        let latest = dir.join(LATEST_DIR);
+        if latest.exists() {
+            fs::remove_dir_all(&latest)?;
+        }
+        copy_dir(&dir.join(version), &latest)?;
+    }
+
+    let manifest = serde_json::json!({
+        "latest": versions.first(),
+        "versions": versions,
+    });
+    let json = serde_json::to_string_pretty(&manifest).expect("versions are valid json");
+    fs::write(dir.join(VERSIONS_FILE), format!("{json}\n"))
+}
+
+/// Every version with docs in `dir`, from the names of its directories, newest first
+fn versions(dir: &Path) -> io::Result<Vec<String>> {
+    let mut versions: Vec<(Vec<u64>, String)> = fs::read_dir(dir)?
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            Some((version_numbers(&name)?, name))
+        })
+        .collect();
+
+    versions.sort_by(|a, b| b.cmp(a));
+    Ok(versions.into_iter().map(|(_, name)| name).collect())
+}
+
+/// Copy the directory `from` and everything in it to `to`
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target: PathBuf = to.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
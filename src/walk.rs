@@ -0,0 +1,61 @@
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+
+/// Glob used to pick files out of a directory when none is given
+pub const DEFAULT_GLOB: &str = "**/*.typ";
+
+/// File in a directory whose patterns leave paths out of a walk, in `.gitignore` syntax
+pub const IGNORE_FILE: &str = ".markerignore";
+
+/// Recursively collect every file under `dir` whose path relative to `dir`
+/// matches `glob`, sorted so output order is stable between runs
+/// Paths matched by a `.gitignore` or `.markerignore` in `dir`, below it,
+/// or above it within its git repository are skipped
+pub fn walk(dir: &Path, glob: &str) -> Vec<PathBuf> {
+    let matcher = glob_set(glob);
+    let walker = WalkBuilder::new(dir)
+        // hidden files are only skipped when ignored
+        .hidden(false)
+        // a .gitignore applies whether or not `dir` is in a git repository
+        .require_git(false)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+
+    let mut files: Vec<PathBuf> = walker
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                eprintln!("warning: {err}");
+                None
+            }
+        })
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            let relative = path.strip_prefix(dir).unwrap_or(path);
+            matcher.is_match(relative)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// The matcher of a glob over `/` separated paths, where
+/// `**` matches any number of directories, `*` matches anything within a
+/// single path segment, `?` matches a single character,
+/// and `{a,b}` matches either alternative, e.g. `**/*.{typ,py}`
+/// An invalid glob is reported, and matches nothing
+pub fn glob_set(glob: &str) -> GlobSet {
+    let set = GlobBuilder::new(glob)
+        .literal_separator(true)
+        .build()
+        .and_then(|glob| GlobSetBuilder::new().add(glob).build());
+
+    set.unwrap_or_else(|err| {
+        eprintln!("warning: {err}");
+        GlobSet::empty()
+    })
+}
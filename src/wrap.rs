@@ -0,0 +1,59 @@
+/// Hard wrap `text` so no line is longer than `width` columns
+/// Existing newlines are preserved, and inline code spans are never broken,
+/// so a single word or code span longer than `width` gets a line to itself
+pub fn wrap(text: &str, width: usize) -> String {
+    text.split('\n')
+        .map(|line| wrap_line(line, width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Greedily fill a single line with as many tokens as fit in `width`
+fn wrap_line(line: &str, width: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut cur = String::new();
+
+    for token in tokens(line) {
+        let len = cur.chars().count();
+        if !cur.is_empty() && len + 1 + token.chars().count() > width {
+            lines.push(cur.clone());
+            cur.clear();
+        }
+
+        if !cur.is_empty() {
+            cur.push(' ');
+        }
+        cur.push_str(&token);
+    }
+    lines.push(cur);
+
+    lines.join("\n")
+}
+
+/// Split a line on whitespace, keeping `code spans` together as one token
+fn tokens(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut cur = String::new();
+    let mut in_code = false;
+
+    for c in line.chars() {
+        if c == '`' {
+            in_code = !in_code;
+        }
+
+        if c.is_whitespace() && !in_code {
+            if !cur.is_empty() {
+                tokens.push(cur.clone());
+                cur.clear();
+            }
+        } else {
+            cur.push(c);
+        }
+    }
+
+    if !cur.is_empty() {
+        tokens.push(cur);
+    }
+
+    tokens
+}